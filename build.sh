@@ -2,7 +2,13 @@
 set -e
 
 echo "Building WASM..."
-wasm-pack build --target web --release
+# Extra arguments go to cargo, e.g. ./build.sh --no-default-features --features minimal
+if [ $# -gt 0 ]; then
+    wasm-pack build --target web --release -- "$@"
+else
+    wasm-pack build --target web --release
+fi
+echo "Binary size: $(wc -c < pkg/snake3d_rs_bg.wasm) bytes"
 
 echo "Build complete. To run, use a local server, e.g.:"
 echo "python3 -m http.server"
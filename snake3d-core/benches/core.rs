@@ -0,0 +1,85 @@
+// Timings for the hot paths of the game logic, to measure changes to board
+// representation or collision checks against. Run with
+// `cargo bench -p snake3d-core`.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use snake3d_core::topology::cells;
+use snake3d_core::{verify_replay, Direction, Face, GameConfig, GameMode, GameState, Position, Replay};
+
+const GRID: i32 = 20;
+
+// Turns every few ticks so the snake keeps crossing edges.
+fn wander(game: &GameState) -> Option<Direction> {
+    match game.ticks % 7 {
+        0 => Some(Direction::Left),
+        3 => Some(Direction::Up),
+        5 => Some(Direction::Right),
+        _ => None,
+    }
+}
+
+fn bench_update(c: &mut Criterion) {
+    for mode in [GameMode::Classic, GameMode::Hazards, GameMode::Tron] {
+        // Timed from a few seconds in, with a full rewind history.
+        let mut game = GameState::with_config(GameConfig::with_mode(GRID, mode), 1);
+        for _ in 0..150 {
+            if let Some(dir) = wander(&game) {
+                game.steer(dir);
+            }
+            game.update();
+        }
+        c.bench_function(&format!("update/{}", mode.name()), |b| {
+            b.iter_batched_ref(
+                || game.clone(),
+                |game| {
+                    if let Some(dir) = wander(game) {
+                        game.steer(dir);
+                    }
+                    black_box(game.update())
+                },
+                BatchSize::SmallInput,
+            );
+        });
+    }
+}
+
+fn bench_next_position(c: &mut Criterion) {
+    let game = GameState::new(GRID, 1);
+    let edge = Position { face: Face::Top, u: GRID - 1, v: GRID / 2 };
+    let inner = Position { face: Face::Front, u: GRID / 2, v: GRID / 2 };
+    c.bench_function("next_position/edge", |b| {
+        b.iter(|| game.calculate_next_position(black_box(edge), black_box(Direction::Right)))
+    });
+    c.bench_function("next_position/inner", |b| {
+        b.iter(|| game.calculate_next_position(black_box(inner), black_box(Direction::Right)))
+    });
+}
+
+// A body over 90% of the board, leaving spawns to search for a gap.
+fn bench_food_spawn(c: &mut Criterion) {
+    let mut game = GameState::new(GRID, 1);
+    let all: Vec<Position> = cells(GRID).collect();
+    game.snake.set_body(all.iter().copied().take(all.len() * 9 / 10));
+    c.bench_function("spawn_food/90%", |b| {
+        b.iter(|| {
+            game.spawn_food();
+            black_box(game.food)
+        })
+    });
+}
+
+// A run that goes straight round the cube from the first turn on, which
+// keeps verification busy for a long time.
+fn bench_verify(c: &mut Criterion) {
+    let mut replay = Replay::new(1, GRID, GameMode::Classic);
+    replay.record(0, Direction::Right);
+    let mut group = c.benchmark_group("verify_replay");
+    group.sample_size(10);
+    group.bench_function("long_run", |b| b.iter(|| verify_replay(black_box(&replay), 0)));
+    group.finish();
+}
+
+criterion_group!(benches, bench_update, bench_next_position, bench_food_spawn, bench_verify);
+criterion_main!(benches);
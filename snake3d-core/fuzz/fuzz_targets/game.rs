@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Err(e) = snake3d_core::fuzz::run(data) {
+        panic!("{}", e);
+    }
+});
@@ -0,0 +1,318 @@
+// Boss fights: a 2x2 creature that patrols one face and drops hazards
+// around itself. It goes down once the snake eats its weak points in order.
+// A boss runs through a short script of phases, each laying out more weak
+// points and moving faster; every boss after the first runs it faster still.
+
+use crate::game::{DeathCause, DeathInfo, Direction, Face, GameEvent, GameState, Position};
+use crate::hazard::Hazard;
+
+// Cells along each side of a boss.
+pub const BOSS_SIZE: i32 = 2;
+// Points for bringing one down.
+pub const BOSS_BONUS: u32 = 25;
+// Ticks between one boss falling and the next showing up.
+const BOSS_BREAK: u64 = 40;
+// Hazards a boss keeps out at once.
+const MAX_MINIONS: usize = 4;
+// Tries at a weak point on the boss's face before it goes anywhere.
+const PLACE_ATTEMPTS: u32 = 50;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BossPhase {
+    // Ticks per move.
+    pub move_period: u64,
+    // Ticks between hazards.
+    pub spawn_period: u64,
+    pub weak_points: usize,
+}
+
+pub const SCRIPT: [BossPhase; 3] = [
+    BossPhase { move_period: 4, spawn_period: 30, weak_points: 2 },
+    BossPhase { move_period: 3, spawn_period: 24, weak_points: 3 },
+    BossPhase { move_period: 2, spawn_period: 18, weak_points: 4 },
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Boss {
+    pub face: Face,
+    // Lowest corner of its body.
+    pub u: i32,
+    pub v: i32,
+    pub dir: Direction,
+    // Index into `SCRIPT`.
+    pub phase: usize,
+    // Weak points left in this phase, the one to eat next first.
+    pub weak_points: Vec<Position>,
+    // Bosses beaten before this one.
+    pub encounter: u32,
+}
+
+impl Boss {
+    pub fn cells(&self) -> impl Iterator<Item = Position> + '_ {
+        (0..BOSS_SIZE * BOSS_SIZE).map(|i| Position {
+            face: self.face,
+            u: self.u + i % BOSS_SIZE,
+            v: self.v + i / BOSS_SIZE,
+        })
+    }
+
+    pub fn covers(&self, pos: Position) -> bool {
+        pos.face == self.face
+            && (self.u..self.u + BOSS_SIZE).contains(&pos.u)
+            && (self.v..self.v + BOSS_SIZE).contains(&pos.v)
+    }
+
+    // This phase of the script, a tick quicker per boss already beaten.
+    pub fn script(&self) -> BossPhase {
+        let phase = SCRIPT[self.phase];
+        let faster = self.encounter as u64;
+        BossPhase {
+            move_period: phase.move_period.saturating_sub(faster).max(1),
+            spawn_period: phase.spawn_period.saturating_sub(faster * 3).max(6),
+            ..phase
+        }
+    }
+}
+
+impl GameState {
+    pub fn weak_point_at(&self, pos: Position) -> Option<usize> {
+        self.boss.as_ref()?.weak_points.iter().position(|p| *p == pos)
+    }
+
+    pub(crate) fn schedule_boss(&mut self) {
+        self.next_boss = self.config.boss.then_some(self.ticks);
+    }
+
+    // Puts the next boss on a face the snake isn't on, clear of everything.
+    fn spawn_boss(&mut self) {
+        let head_face = self.snake.head().face;
+        let room = self.config.grid_size - BOSS_SIZE + 1;
+        let encounter = self.bosses_defeated;
+        let boss = loop {
+            let face = Face::ALL[self.rng.below(6) as usize];
+            let (u, v) = (self.rng.below(room as u32) as i32, self.rng.below(room as u32) as i32);
+            let dir = self.random_direction();
+            let boss = Boss { face, u, v, dir, phase: 0, weak_points: Vec::new(), encounter };
+            if face != head_face && boss.cells().all(|cell| self.cell_is_free(cell)) {
+                break boss;
+            }
+        };
+        self.boss = Some(boss);
+        self.next_boss = None;
+        self.lay_weak_points();
+    }
+
+    // The phase's weak points, on the boss's face where there's room.
+    fn lay_weak_points(&mut self) {
+        let Some(boss) = &self.boss else { return };
+        let (face, count) = (boss.face, boss.script().weak_points);
+        let n = self.config.grid_size as u32;
+        let mut points = Vec::new();
+        while points.len() < count {
+            let on_face = (0..PLACE_ATTEMPTS).find_map(|_| {
+                let pos = Position { face, u: self.rng.below(n) as i32, v: self.rng.below(n) as i32 };
+                (self.cell_is_free(pos) && !points.contains(&pos)).then_some(pos)
+            });
+            let pos = match on_face {
+                Some(pos) => pos,
+                None => self.random_free_cell(),
+            };
+            if !points.contains(&pos) {
+                points.push(pos);
+            }
+        }
+        if let Some(boss) = &mut self.boss {
+            boss.weak_points = points;
+        }
+    }
+
+    // Eats a weak point under the head, then moves the boss and lets it
+    // spawn on its own ticks. Out of order, the weak points scatter.
+    pub(crate) fn step_boss(&mut self) -> GameEvent {
+        if self.boss.is_none() {
+            if self.next_boss.is_some_and(|tick| self.ticks >= tick) {
+                self.spawn_boss();
+            }
+            return GameEvent::None;
+        }
+        let event = match self.weak_point_at(self.snake.head()) {
+            Some(0) => self.hit_boss(),
+            Some(_) => {
+                self.lay_weak_points();
+                GameEvent::None
+            }
+            None => GameEvent::None,
+        };
+        if self.boss.is_none() {
+            return event;
+        }
+        let script = self.boss.as_ref().map(Boss::script).expect("boss is out");
+        if self.ticks.is_multiple_of(script.spawn_period) {
+            self.spawn_minion();
+        }
+        if self.ticks.is_multiple_of(script.move_period) {
+            let moved = self.move_boss();
+            if moved != GameEvent::None {
+                return moved;
+            }
+        }
+        event
+    }
+
+    fn hit_boss(&mut self) -> GameEvent {
+        let Some(boss) = &mut self.boss else { return GameEvent::None };
+        boss.weak_points.remove(0);
+        if !boss.weak_points.is_empty() {
+            return GameEvent::BossHit;
+        }
+        if boss.phase + 1 < SCRIPT.len() {
+            boss.phase += 1;
+            self.lay_weak_points();
+            return GameEvent::BossHit;
+        }
+        // Down: its hazards go with it.
+        self.boss = None;
+        self.hazards.clear();
+        self.bosses_defeated += 1;
+        self.score += BOSS_BONUS;
+        self.high_score = self.high_score.max(self.score);
+        self.next_boss = Some(self.ticks + BOSS_BREAK);
+        GameEvent::BossDefeated
+    }
+
+    // One cell along its face, turning back at the edges or when something
+    // other than the head or food is in the way. Food it rolls over moves.
+    fn move_boss(&mut self) -> GameEvent {
+        let Some(boss) = self.boss.clone() else { return GameEvent::None };
+        let head = self.snake.head();
+        let room = self.config.grid_size - BOSS_SIZE;
+        // Now and then it turns of its own accord.
+        let preferred = if self.rng.below(6) == 0 { self.random_direction() } else { boss.dir };
+        let step = [preferred, preferred.opposite()].into_iter().find_map(|dir| {
+            let (du, dv) = match dir {
+                Direction::Up => (0, 1),
+                Direction::Down => (0, -1),
+                Direction::Left => (-1, 0),
+                Direction::Right => (1, 0),
+            };
+            let (u, v) = (boss.u + du, boss.v + dv);
+            if !(0..=room).contains(&u) || !(0..=room).contains(&v) {
+                return None;
+            }
+            let next = Boss { u, v, dir, ..boss.clone() };
+            let clear = next.cells().all(|cell| {
+                boss.covers(cell) || cell == head || cell == self.food || self.cell_is_free(cell)
+            });
+            clear.then_some(next)
+        });
+        let Some(next) = step else { return GameEvent::None };
+        let (food_hit, head_hit) = (next.covers(self.food), next.covers(head));
+        self.boss = Some(next);
+        if food_hit {
+            self.spawn_food();
+        }
+        if !head_hit {
+            return GameEvent::None;
+        }
+        if self.shielded {
+            self.shielded = false;
+            return GameEvent::ShieldBreak;
+        }
+        self.die(DeathInfo::new(DeathCause::Boss, head))
+    }
+
+    // A hazard on a free cell next to the boss, heading away from it.
+    fn spawn_minion(&mut self) {
+        if self.hazards.len() >= MAX_MINIONS {
+            return;
+        }
+        let dir = self.random_direction();
+        let Some(boss) = &self.boss else { return };
+        let spot = boss
+            .cells()
+            .map(|cell| self.calculate_next_position(cell, dir))
+            .find(|(pos, _)| !boss.covers(*pos) && self.cell_is_free(*pos));
+        if let Some((pos, dir)) = spot {
+            self.hazards.push(Hazard { pos, dir });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::GameConfig;
+    use crate::mode::GameMode;
+
+    use super::*;
+
+    fn boss_game() -> GameState {
+        GameState::with_config(GameConfig::with_mode(10, GameMode::Boss), 5)
+    }
+
+    #[test]
+    fn test_boss_appears_off_the_snakes_face() {
+        let mut game = boss_game();
+        game.food = Position { face: Face::Front, u: 0, v: 0 };
+        game.update();
+        let boss = game.boss.clone().expect("a boss from the start");
+        assert_ne!(boss.face, Face::Front);
+        assert_eq!(boss.weak_points.len(), SCRIPT[0].weak_points);
+        assert!(boss.weak_points.iter().all(|p| !boss.covers(*p)));
+    }
+
+    #[test]
+    fn test_weak_points_in_order_bring_the_boss_down() {
+        let mut game = boss_game();
+        game.food = Position { face: Face::Front, u: 0, v: 0 };
+        game.update();
+        let mut phases = 0;
+        let event = loop {
+            let target = game.boss.as_ref().unwrap().weak_points[0];
+            // Stand the head on the next weak point and let the boss react.
+            game.snake.set_body([target]);
+            let event = game.step_boss();
+            if event != GameEvent::BossHit {
+                break event;
+            }
+            if game.boss.as_ref().unwrap().phase > phases {
+                phases += 1;
+            }
+        };
+        assert_eq!(event, GameEvent::BossDefeated);
+        assert_eq!(phases, SCRIPT.len() - 1);
+        assert!(game.boss.is_none());
+        assert_eq!(game.score, BOSS_BONUS);
+        assert_eq!(game.bosses_defeated, 1);
+    }
+
+    #[test]
+    fn test_out_of_order_weak_point_scatters_them() {
+        let mut game = boss_game();
+        game.food = Position { face: Face::Front, u: 0, v: 0 };
+        game.update();
+        let before = game.boss.as_ref().unwrap().weak_points.clone();
+        game.snake.set_body([before[1]]);
+        assert_eq!(game.step_boss(), GameEvent::None);
+        let after = &game.boss.as_ref().unwrap().weak_points;
+        assert_eq!(after.len(), before.len());
+        assert_ne!(*after, before);
+    }
+
+    #[test]
+    fn test_running_into_the_boss_is_fatal() {
+        let mut game = boss_game();
+        game.food = Position { face: Face::Back, u: 0, v: 0 };
+        let head = game.snake.head();
+        game.boss = Some(Boss {
+            face: head.face,
+            u: head.u,
+            v: head.v + 1,
+            dir: Direction::Up,
+            phase: 0,
+            weak_points: Vec::new(),
+            encounter: 0,
+        });
+        assert_eq!(game.update(), GameEvent::GameOver);
+        assert_eq!(game.death.map(|death| death.cause), Some(DeathCause::Boss));
+    }
+}
@@ -0,0 +1,93 @@
+// The campaign: a fixed run of levels, each with an objective measured in
+// points, seconds survived or prizes eaten. Three thresholds on it give one
+// to three stars; reaching the third ends the run as a win. The levels
+// themselves ship as data (assets/campaign.json in the web crate).
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::GameState;
+use crate::level::{LevelDefinition, WinCondition};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Objective {
+    Score,
+    Survive,
+    Prizes,
+}
+
+// A campaign level in JSON is a level definition plus `objective` and
+// `stars`. The level's own `win` is ignored; the third star is the goal.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CampaignLevel {
+    #[serde(flatten)]
+    pub level: LevelDefinition,
+    pub objective: Objective,
+    // Objective amounts for one, two and three stars, ascending.
+    pub stars: [u32; 3],
+}
+
+impl CampaignLevel {
+    // The level as played: it ends once three stars are in.
+    pub fn definition(&self) -> LevelDefinition {
+        let goal = self.stars[2];
+        LevelDefinition {
+            win: match self.objective {
+                Objective::Score => WinCondition::Score(goal),
+                Objective::Survive => WinCondition::Survive(goal as f64),
+                Objective::Prizes => WinCondition::Prizes(goal),
+            },
+            ..self.level.clone()
+        }
+    }
+
+    // How far `game` got on the objective, in its unit.
+    pub fn progress(&self, game: &GameState) -> u32 {
+        match self.objective {
+            Objective::Score => game.score,
+            Objective::Survive => game.elapsed as u32,
+            Objective::Prizes => game.prizes_eaten,
+        }
+    }
+
+    // Stars `game` has earned so far, 0 to 3.
+    pub fn stars_for(&self, game: &GameState) -> u8 {
+        let progress = self.progress(game);
+        self.stars.iter().filter(|threshold| progress >= **threshold).count() as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dungeon::is_solvable;
+
+    use super::*;
+
+    const CAMPAIGN: &str = include_str!("../../assets/campaign.json");
+
+    #[test]
+    fn test_shipped_campaign_is_playable() {
+        let levels: Vec<CampaignLevel> = serde_json::from_str(CAMPAIGN).unwrap();
+        assert_eq!(levels.len(), 20);
+        for level in &levels {
+            let definition = level.definition();
+            assert!(definition.validate().is_ok(), "{}: {:?}", level.level.name, definition.validate());
+            assert!(is_solvable(&definition), "{} has unreachable cells", level.level.name);
+            assert!(level.stars.windows(2).all(|pair| pair[0] < pair[1]), "{}", level.level.name);
+        }
+    }
+
+    #[test]
+    fn test_stars_count_thresholds_met() {
+        let level = CampaignLevel {
+            level: LevelDefinition::default(),
+            objective: Objective::Score,
+            stars: [5, 10, 20],
+        };
+        let mut game = GameState::with_level(&level.definition(), 1);
+        assert_eq!(level.stars_for(&game), 0);
+        game.score = 12;
+        assert_eq!(level.stars_for(&game), 2);
+        assert_eq!(game.config.win, WinCondition::Score(20));
+    }
+}
@@ -0,0 +1,98 @@
+// Fixed-step timing for the game loop. Frames come at whatever rate the
+// display runs, while a tick is due every `GameState::move_interval` seconds.
+// The clock banks frame time and pays it out a tick at a time, with two
+// limits so that a stall (a GC pause, a slow frame) doesn't become a jump
+// across the board: a frame counts for `max_frame` seconds at most, and at
+// most `max_steps` ticks run in one frame, the rest of the time dropped.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TickClock {
+    pub max_frame: f64,
+    pub max_steps: u32,
+    // Time not yet spent on ticks, in seconds.
+    banked: f64,
+    // Ticks run since the last `advance`.
+    steps: u32,
+}
+
+impl Default for TickClock {
+    // Twice the fastest default interval, so a stall costs two cells at most.
+    fn default() -> Self {
+        Self { max_frame: 0.1, max_steps: 3, banked: 0.0, steps: 0 }
+    }
+}
+
+impl TickClock {
+    // Banks a frame's worth of time.
+    pub fn advance(&mut self, dt: f64) {
+        self.banked += dt.clamp(0.0, self.max_frame);
+        self.steps = 0;
+    }
+
+    // Spends `interval` on a tick, if that much is banked and the frame has
+    // ticks left.
+    pub fn tick(&mut self, interval: f64) -> bool {
+        if self.banked < interval {
+            return false;
+        }
+        if self.steps >= self.max_steps {
+            self.banked = 0.0;
+            return false;
+        }
+        self.banked -= interval;
+        self.steps += 1;
+        true
+    }
+
+    pub fn banked(&self) -> f64 {
+        self.banked
+    }
+
+    // How far along the next tick is, 0 to 1.
+    pub fn alpha(&self, interval: f64) -> f32 {
+        (self.banked / interval).clamp(0.0, 1.0) as f32
+    }
+
+    pub fn reset(&mut self) {
+        self.banked = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticks(clock: &mut TickClock, dt: f64, interval: f64) -> u32 {
+        clock.advance(dt);
+        let mut n = 0;
+        while clock.tick(interval) {
+            n += 1;
+        }
+        n
+    }
+
+    #[test]
+    fn test_pays_out_whole_ticks_and_carries_the_rest() {
+        let mut clock = TickClock::default();
+        assert_eq!(ticks(&mut clock, 0.07, 0.05), 1);
+        assert!((clock.banked() - 0.02).abs() < 1e-9);
+        assert_eq!(ticks(&mut clock, 0.04, 0.05), 1);
+        assert!((clock.alpha(0.05) - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_a_stall_moves_the_snake_a_cell_or_two() {
+        let mut clock = TickClock::default();
+        // Half a second lost to a pause counts as one long frame.
+        assert_eq!(ticks(&mut clock, 0.5, 0.05), 2);
+        assert_eq!(ticks(&mut clock, 0.5, 0.15), 0);
+        assert_eq!(ticks(&mut clock, 0.5, 0.15), 1);
+    }
+
+    #[test]
+    fn test_drops_what_the_frame_has_no_steps_left_for() {
+        let mut clock = TickClock { max_steps: 2, ..Default::default() };
+        assert_eq!(ticks(&mut clock, 0.1, 0.02), 2);
+        assert_eq!(clock.banked(), 0.0);
+    }
+}
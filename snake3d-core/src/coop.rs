@@ -0,0 +1,235 @@
+// Local co-op: a second snake, steered from the same keyboard, shares the
+// score and a pool of lives with the first. The two pass through each other
+// harmlessly, and every meal pays more the longer the pair is together.
+
+use crate::game::{DeathCause, DeathInfo, Direction, Face, GameEvent, GameState, Position, Snake};
+
+// Lives the pair starts with.
+pub const COOP_LIVES: u32 = 3;
+// Every this many cells of combined length add a point to each meal.
+const COMBINED_STEP: usize = 10;
+// How far left of the first snake the second starts.
+const PARTNER_OFFSET: i32 = 2;
+
+impl GameState {
+    // The second snake starts beside the first, heading the same way.
+    pub(crate) fn spawn_partner(&mut self) {
+        if !self.config.coop {
+            return;
+        }
+        let n = self.config.grid_size;
+        let start = Position { face: Face::Front, u: (n / 2 - PARTNER_OFFSET).max(0), v: n / 2 };
+        self.partner = Some(Snake::new(start, Direction::Up));
+    }
+
+    pub fn steer_partner(&mut self, dir: Direction) {
+        if let Some(partner) = &mut self.partner {
+            if partner.direction != dir.opposite() {
+                partner.next_direction = dir;
+            }
+        }
+    }
+
+    // Extra points per meal for the pair's combined length.
+    pub fn combined_bonus(&self) -> u32 {
+        let Some(partner) = &self.partner else { return 0 };
+        ((self.snake.body.len() + partner.body.len()) / COMBINED_STEP) as u32
+    }
+
+    // Moves the second snake a cell. Only its own body and the obstacles
+    // stop it; food, feast cells and rush food it reaches score for both,
+    // and a shield it picks up guards the pair.
+    pub(crate) fn step_partner(&mut self) -> GameEvent {
+        let Some(partner) = &self.partner else { return GameEvent::None };
+        let dir = partner.next_direction;
+        let (next, new_dir) = self.calculate_next_position(partner.head(), dir);
+        let next = self.portal_exit(next).unwrap_or(next);
+        let eating = next == self.food;
+        let bites_feast = self.feast_at(next);
+        let eats_rush = self.rush_food_at(next);
+        let growing = eating || bites_feast || eats_rush;
+        let chasing_tail = !growing && partner.body.back() == Some(&next);
+        if partner.contains(next) && !chasing_tail {
+            let segment = partner.body.iter().position(|pos| *pos == next);
+            return self.partner_crash(DeathInfo { segment, ..DeathInfo::new(DeathCause::Snake, next) });
+        }
+        if self.obstacle_at(next) {
+            return self.partner_crash(DeathInfo::new(self.obstacle_cause(next), next));
+        }
+
+        let Some(partner) = &mut self.partner else { return GameEvent::None };
+        partner.push_front(next);
+        partner.direction = new_dir;
+        partner.next_direction = new_dir;
+        if !growing {
+            partner.pop_back();
+        }
+        let mut shield_event = GameEvent::None;
+        if self.shield == Some(next) {
+            self.shield = None;
+            self.shielded = true;
+            shield_event = GameEvent::ShieldPickup;
+        }
+        if bites_feast {
+            return self.bite_feast(next);
+        }
+        if eats_rush {
+            return self.eat_rush_food(next);
+        }
+        if !eating {
+            return shield_event;
+        }
+        let prize = self.is_prize;
+        self.score += if prize { 5 } else { 1 } + self.combined_bonus();
+        self.high_score = self.high_score.max(self.score);
+        self.food_eaten_count += 1;
+        if prize {
+            self.prizes_eaten += 1;
+        }
+        self.spawn_food();
+        if prize {
+            GameEvent::EatPrize
+        } else {
+            GameEvent::Eat
+        }
+    }
+
+    // Where the second snake's body covers `pos`, counted from its head.
+    pub(crate) fn partner_segment(&self, pos: Position) -> Option<usize> {
+        let partner = self.partner.as_ref().filter(|partner| partner.contains(pos))?;
+        partner.body.iter().position(|p| *p == pos)
+    }
+
+    // Spends a life on the second snake, or ends the run on the last one.
+    // The pair's shield spares the life, though the snake still starts over.
+    pub(crate) fn partner_crash(&mut self, death: DeathInfo) -> GameEvent {
+        let event = if self.shielded {
+            self.shielded = false;
+            GameEvent::ShieldBreak
+        } else if self.lives <= 1 {
+            return self.die(death);
+        } else {
+            self.lives -= 1;
+            GameEvent::LifeLost
+        };
+        let start = self.random_free_cell();
+        self.partner = Some(Snake::new(start, Direction::Up));
+        event
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::feast::{Feast, BITE_POINTS};
+    use crate::game::GameConfig;
+    use crate::hazard::Hazard;
+    use crate::mode::GameMode;
+    use crate::turret::Projectile;
+
+    use super::*;
+
+    fn coop_game() -> GameState {
+        let mut game = GameState::with_config(GameConfig::with_mode(10, GameMode::Coop), 9);
+        game.food = Position { face: Face::Back, u: 0, v: 0 };
+        game
+    }
+
+    #[test]
+    fn test_partner_meals_feed_the_shared_score() {
+        let mut game = coop_game();
+        let partner_head = game.partner.as_ref().unwrap().head();
+        game.food = Position { v: partner_head.v + 1, ..partner_head };
+        assert_eq!(game.update(), GameEvent::Eat);
+        assert_eq!(game.score, 1);
+        assert_eq!(game.partner.as_ref().unwrap().body.len(), 2);
+        assert_eq!(game.snake.body.len(), 1);
+    }
+
+    #[test]
+    fn test_snakes_pass_through_each_other() {
+        let mut game = coop_game();
+        let head = game.snake.head();
+        // Lay the partner across the first snake's path.
+        let across: Vec<Position> = (0..4).map(|du| Position { u: head.u + 1 - du, v: head.v + 1, ..head }).collect();
+        game.partner.as_mut().unwrap().set_body(across);
+        game.steer_partner(Direction::Right);
+        assert_eq!(game.update(), GameEvent::None);
+        assert!(!game.game_over);
+        assert_eq!(game.lives, COOP_LIVES);
+    }
+
+    // Coils the first snake on the left face so its next move bites itself.
+    fn coil(game: &mut GameState) {
+        let at = |u, v| Position { face: Face::Left, u, v };
+        game.snake.set_body([at(5, 5), at(6, 5), at(6, 6), at(5, 6), at(4, 6)]);
+        game.snake.direction = Direction::Up;
+        game.snake.next_direction = Direction::Up;
+    }
+
+    #[test]
+    fn test_crashes_spend_shared_lives() {
+        let mut game = coop_game();
+        for lives_left in (1..COOP_LIVES).rev() {
+            coil(&mut game);
+            assert_eq!(game.update(), GameEvent::LifeLost);
+            assert_eq!(game.lives, lives_left);
+            assert_eq!(game.snake.body.len(), 1);
+        }
+        coil(&mut game);
+        assert_eq!(game.update(), GameEvent::GameOver);
+        assert_eq!(game.death.map(|death| death.cause), Some(DeathCause::Snake));
+    }
+
+    #[test]
+    fn test_partner_takes_feasts_rush_food_and_shields() {
+        let mut game = coop_game();
+        let head = game.partner.as_ref().unwrap().head();
+        let ahead = |dv| Position { v: head.v + dv, ..head };
+        game.feast = Some(Feast { cells: vec![ahead(1), Position { face: Face::Back, u: 5, v: 5 }] });
+        game.rush_food = vec![ahead(2)];
+        game.shield = Some(ahead(3));
+        assert_eq!(game.update(), GameEvent::FeastBite);
+        assert_eq!(game.score, BITE_POINTS);
+        assert_eq!(game.update(), GameEvent::Eat);
+        assert!(game.rush_food.is_empty());
+        assert_eq!(game.update(), GameEvent::ShieldPickup);
+        assert!(game.shielded);
+        assert_eq!(game.partner.as_ref().unwrap().body.len(), 3);
+    }
+
+    #[test]
+    fn test_turrets_and_hazards_hit_the_partner() {
+        let mut game = coop_game();
+        game.shield = None;
+        let head = game.partner.as_ref().unwrap().head();
+        let at = |du, dv| Position { u: head.u + du, v: head.v + dv, ..head };
+        // A shot coming in from the left onto the partner's next cell.
+        game.projectiles.push(Projectile { pos: at(-1, 1), dir: Direction::Right, ttl: 5 });
+        assert_eq!(game.update(), GameEvent::LifeLost);
+        assert_eq!(game.lives, COOP_LIVES - 1);
+        assert!(game.projectiles.is_empty());
+
+        // A hazard walled in on every side but the partner's next cell.
+        let partner = game.partner.as_mut().unwrap();
+        partner.set_body([head]);
+        partner.direction = Direction::Up;
+        partner.next_direction = Direction::Up;
+        game.walls.extend([at(0, 3), at(-1, 2), at(1, 2)]);
+        game.hazards.push(Hazard { pos: at(0, 2), dir: Direction::Down });
+        game.ticks = 1;
+        game.shielded = true;
+        assert_eq!(game.update(), GameEvent::ShieldBreak);
+        assert!(!game.shielded);
+        assert_eq!(game.lives, COOP_LIVES - 1);
+    }
+
+    #[test]
+    fn test_combined_length_raises_meal_points() {
+        let mut game = coop_game();
+        assert_eq!(game.combined_bonus(), 0);
+        let head = game.snake.head();
+        game.snake.set_body((0..9).map(|dv| Position { face: Face::Bottom, u: head.u, v: dv }));
+        game.partner.as_mut().unwrap().set_body((0..11).map(|dv| Position { face: Face::Top, u: head.u, v: dv }));
+        assert_eq!(game.combined_bonus(), 2);
+    }
+}
@@ -0,0 +1,200 @@
+// Adaptive difficulty: an optional director that keeps the game close to
+// what the player can handle. It watches three things - near misses (a turn
+// made with the way ahead blocked one cell out), how long the player takes
+// to turn once something blocks the way ahead, and crashes - and after each
+// meal or crash nudges an intensity up or down within `min_intensity` and
+// `max_intensity`. The intensity scales the speed ramp; the casual modes it
+// runs in have no hazards to scale.
+//
+// Runs it has paced don't compare with anyone else's, so hosts keep it to
+// casual modes (see `GameMode::competitive`), don't record a replay and keep
+// the score off leaderboards and records.
+
+use crate::game::{GameConfig, GameEvent, GameState, SpeedCurve};
+
+// Cells straight ahead the director looks for something in the way.
+const LOOKAHEAD: usize = 4;
+// Reaction times, in seconds, that count as easy and as struggling.
+const QUICK_REACTION: f64 = 0.3;
+const SLOW_REACTION: f64 = 0.5;
+// Near misses between meals that count as struggling.
+const CLOSE_CALLS: u32 = 2;
+// Intensity gained after an easy meal and lost on a crash.
+const STEP: f64 = 0.05;
+const CRASH_STEP: f64 = 0.15;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Director {
+    pub min_intensity: f64,
+    pub max_intensity: f64,
+    // 1 is the mode's own difficulty; above is harder.
+    intensity: f64,
+    // Cells to whatever was in the way ahead after the last tick.
+    ahead: Option<usize>,
+    // Ticks something has been in view ahead, while it's still there.
+    danger_for: Option<u32>,
+    // Average seconds from seeing something ahead to turning away from it.
+    reaction: f64,
+    // Near misses since the last meal.
+    near_misses: u32,
+}
+
+impl Default for Director {
+    fn default() -> Self {
+        Self {
+            min_intensity: 0.75,
+            max_intensity: 1.25,
+            intensity: 1.0,
+            ahead: None,
+            danger_for: None,
+            reaction: (QUICK_REACTION + SLOW_REACTION) / 2.0,
+            near_misses: 0,
+        }
+    }
+}
+
+impl Director {
+    pub fn intensity(&self) -> f64 {
+        self.intensity
+    }
+
+    // Takes in a tick: `event` is what `GameState::update` returned, and
+    // `turned` whether the snake changed direction on it.
+    pub fn observe(&mut self, game: &GameState, event: GameEvent, turned: bool) {
+        match event {
+            GameEvent::GameOver | GameEvent::LifeLost => {
+                self.nudge(-CRASH_STEP);
+                self.ahead = None;
+                self.danger_for = None;
+                self.near_misses = 0;
+                return;
+            }
+            GameEvent::Eat | GameEvent::EatPrize | GameEvent::AllFacesBonus => self.review(),
+            _ => {}
+        }
+        if turned {
+            // Turning away with the way blocked right in front is cutting it fine.
+            if self.ahead == Some(1) {
+                self.near_misses += 1;
+            }
+            if let Some(ticks) = self.danger_for.take() {
+                let seconds = ticks as f64 * game.move_interval();
+                self.reaction = self.reaction * 0.7 + seconds * 0.3;
+            }
+        }
+        self.ahead = danger_ahead(game);
+        self.danger_for = self.ahead.map(|_| self.danger_for.map_or(1, |ticks| ticks + 1));
+    }
+
+    // Sets the pacing from `base`, the run's own speed curve.
+    pub fn apply(&self, config: &mut GameConfig, base: SpeedCurve) {
+        config.speed.ramp = base.ramp * self.intensity;
+    }
+
+    // Looks back over the stretch since the last meal.
+    fn review(&mut self) {
+        if self.near_misses >= CLOSE_CALLS || self.reaction > SLOW_REACTION {
+            self.nudge(-STEP);
+        } else if self.near_misses == 0 && self.reaction < QUICK_REACTION {
+            self.nudge(STEP);
+        }
+        self.near_misses = 0;
+    }
+
+    fn nudge(&mut self, by: f64) {
+        self.intensity = (self.intensity + by).clamp(self.min_intensity, self.max_intensity);
+    }
+}
+
+// Cells to the first thing in the way straight ahead, if it's in sight.
+fn danger_ahead(game: &GameState) -> Option<usize> {
+    let mut pos = game.snake.head();
+    let mut dir = game.snake.direction;
+    for cells in 1..=LOOKAHEAD {
+        let (next, next_dir) = game.calculate_next_position(pos, dir);
+        pos = game.portal_exit(next).unwrap_or(next);
+        dir = next_dir;
+        if game.path_blocked(pos) {
+            return Some(cells);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Direction, Position};
+
+    // A snake heading right with a wall `cells` ahead of its head.
+    fn walled(cells: i32) -> GameState {
+        let mut game = GameState::new(10, 1);
+        let head = game.snake.head();
+        game.snake.direction = Direction::Right;
+        game.snake.next_direction = Direction::Right;
+        game.walls.insert(Position { face: head.face, u: head.u + cells, v: head.v });
+        game
+    }
+
+    #[test]
+    fn test_crashes_ease_off_down_to_the_floor() {
+        let mut director = Director::default();
+        let game = GameState::new(10, 1);
+        director.observe(&game, GameEvent::LifeLost, false);
+        assert!((director.intensity() - 0.85).abs() < 1e-9);
+        for _ in 0..5 {
+            director.observe(&game, GameEvent::GameOver, false);
+        }
+        assert_eq!(director.intensity(), director.min_intensity);
+    }
+
+    #[test]
+    fn test_quick_clean_play_tightens_up_to_the_ceiling() {
+        let mut director = Director { reaction: 0.1, ..Default::default() };
+        let game = GameState::new(10, 1);
+        director.observe(&game, GameEvent::Eat, false);
+        assert!(director.intensity() > 1.0);
+        for _ in 0..10 {
+            director.observe(&game, GameEvent::Eat, false);
+        }
+        assert_eq!(director.intensity(), director.max_intensity);
+    }
+
+    #[test]
+    fn test_close_calls_count_against_the_next_meal() {
+        let mut director = Director { reaction: 0.1, ..Default::default() };
+        let game = walled(1);
+        assert_eq!(danger_ahead(&game), Some(1));
+        assert_eq!(danger_ahead(&walled(3)), Some(3));
+        director.observe(&game, GameEvent::None, false);
+        director.observe(&game, GameEvent::None, true);
+        director.observe(&game, GameEvent::None, true);
+        assert_eq!(director.near_misses, 2);
+        director.observe(&GameState::new(10, 1), GameEvent::Eat, false);
+        assert!(director.intensity() < 1.0);
+        assert_eq!(director.near_misses, 0);
+    }
+
+    #[test]
+    fn test_slow_turns_raise_the_reaction_time() {
+        let mut director = Director::default();
+        let game = walled(3);
+        director.observe(&game, GameEvent::None, false);
+        director.observe(&game, GameEvent::None, false);
+        director.observe(&game, GameEvent::None, false);
+        let before = director.reaction;
+        director.observe(&GameState::new(10, 1), GameEvent::None, true);
+        // Three ticks at the opening pace, averaged in.
+        assert!((director.reaction - (before * 0.7 + 3.0 * 0.15 * 0.3)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_scales_the_ramp() {
+        let director = Director { intensity: 1.2, ..Default::default() };
+        let mut config = GameConfig::new(10);
+        let base = config.speed;
+        director.apply(&mut config, base);
+        assert!((config.speed.ramp - base.ramp * 1.2).abs() < 1e-12);
+        assert_eq!(config.speed.base_interval, base.base_interval);
+    }
+}
@@ -0,0 +1,153 @@
+// Procedural layouts, and the Endless Dungeon mode built on them: every new
+// level throws away the walls and portals and generates a denser set.
+//
+// A layout is only kept if it's solvable: every free cell can reach every
+// other across the cube's surface, counting portal jumps, so no food can
+// land somewhere the snake can't get to.
+
+use std::collections::HashSet;
+
+use crate::game::{GameState, Position};
+use crate::level::{start_cell, LevelDefinition, Portal};
+use crate::path;
+use crate::rng::Rng;
+use crate::topology;
+
+// Layouts tried before settling for an open board.
+const ATTEMPTS: u32 = 20;
+// Obstacles can cover at most this share of the board.
+const MAX_DENSITY: f32 = 0.35;
+// Endless Dungeon starts at this density and adds a step per level.
+const DUNGEON_DENSITY: f32 = 0.04;
+const DUNGEON_DENSITY_STEP: f32 = 0.02;
+// Cells ahead of the head a new layout keeps clear.
+const HEAD_ROOM: usize = 3;
+
+// What `generate` places.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LayoutParams {
+    pub grid_size: i32,
+    // Share of the board covered by obstacles, up to `MAX_DENSITY`.
+    pub density: f32,
+    pub portals: usize,
+}
+
+// A solvable layout for `seed`, with nothing on `keep_clear` or the start.
+// Falls back to an open board if no attempt passes the check.
+pub fn generate(params: LayoutParams, seed: u64, keep_clear: &HashSet<Position>) -> LevelDefinition {
+    let mut rng = Rng::new(seed);
+    let cells: Vec<Position> = topology::cells(params.grid_size).collect();
+    let obstacles = (cells.len() as f32 * params.density.clamp(0.0, MAX_DENSITY)) as usize;
+    let empty = LevelDefinition { grid_size: params.grid_size, ..Default::default() };
+    for _ in 0..ATTEMPTS {
+        let mut used = keep_clear.clone();
+        used.insert(start_cell(params.grid_size));
+        let mut pick = |rng: &mut Rng| {
+            // Bounded, in case `keep_clear` leaves little room.
+            (0..cells.len()).find_map(|_| {
+                let cell = cells[rng.below(cells.len() as u32) as usize];
+                used.insert(cell).then_some(cell)
+            })
+        };
+        let mut level = empty.clone();
+        level.obstacles = (0..obstacles).map_while(|_| pick(&mut rng)).collect();
+        for _ in 0..params.portals {
+            if let (Some(a), Some(b)) = (pick(&mut rng), pick(&mut rng)) {
+                level.portals.push(Portal { a, b });
+            }
+        }
+        if is_solvable(&level) {
+            return level;
+        }
+    }
+    empty
+}
+
+// Every free cell reachable from the start. Portal ends don't count as free,
+// since nothing stops on the end it enters.
+pub fn is_solvable(level: &LevelDefinition) -> bool {
+    let mut board = GameState::new(level.grid_size, 0);
+    board.portals = level.portals.clone();
+    let blocked: HashSet<Position> = level.obstacles.iter().copied().collect();
+    let seen = path::reachable(&board, start_cell(level.grid_size), |pos| blocked.contains(&pos));
+    topology::cells(level.grid_size)
+        .filter(|cell| !blocked.contains(cell) && board.portal_exit(*cell).is_none())
+        .all(|cell| seen.contains(&cell))
+}
+
+impl GameState {
+    // Lays out a fresh dungeon for the current level, clear of the snake,
+    // the pickups and the cells just ahead of the head.
+    pub(crate) fn build_dungeon(&mut self) {
+        if !self.config.mode.dungeon() {
+            return;
+        }
+        let mut keep_clear: HashSet<Position> = self.snake.body.iter().copied().collect();
+        keep_clear.extend([self.food]);
+        keep_clear.extend(self.shield);
+        keep_clear.extend(self.rush_food.iter().copied());
+        keep_clear.extend(self.feast.iter().flat_map(|f| f.cells.iter().copied()));
+        let (mut pos, mut dir) = (self.snake.head(), self.snake.direction);
+        for _ in 0..HEAD_ROOM {
+            (pos, dir) = self.calculate_next_position(pos, dir);
+            keep_clear.insert(pos);
+        }
+        let level = self.level();
+        let params = LayoutParams {
+            grid_size: self.config.grid_size,
+            density: DUNGEON_DENSITY + DUNGEON_DENSITY_STEP * (level - 1) as f32,
+            portals: (level as usize).min(4),
+        };
+        let layout = generate(params, self.rng.next_u64(), &keep_clear);
+        self.walls = layout.obstacles.into_iter().collect();
+        self.portals = layout.portals;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{Direction, Face, GameConfig};
+    use crate::mode::GameMode;
+
+    use super::*;
+
+    #[test]
+    fn test_generated_layouts_are_solvable() {
+        for seed in 0..10 {
+            let params = LayoutParams { grid_size: 8, density: 0.3, portals: 2 };
+            let level = generate(params, seed, &HashSet::new());
+            assert!(level.validate().is_ok());
+            assert!(is_solvable(&level));
+            assert_eq!(level, generate(params, seed, &HashSet::new()));
+        }
+    }
+
+    #[test]
+    fn test_walled_off_cell_is_unsolvable() {
+        let corner = |u, v| Position { face: Face::Front, u, v };
+        let mut level = LevelDefinition { grid_size: 6, ..Default::default() };
+        assert!(is_solvable(&level));
+        // Front (0, 0) is a corner, but on a cube it still has four neighbours:
+        // two on its own face and one across each edge.
+        level.obstacles = vec![corner(1, 0), corner(0, 1)];
+        let board = GameState::new(6, 0);
+        level.obstacles.push(board.calculate_next_position(corner(0, 0), Direction::Left).0);
+        level.obstacles.push(board.calculate_next_position(corner(0, 0), Direction::Down).0);
+        assert!(!is_solvable(&level));
+    }
+
+    #[test]
+    fn test_dungeon_rebuilds_each_level() {
+        let mut game = GameState::with_config(GameConfig::with_mode(10, GameMode::Dungeon), 4);
+        let first = game.walls.clone();
+        assert!(!first.is_empty());
+        assert!(!game.walls.contains(&game.food));
+
+        game.score = 9;
+        game.food = game.calculate_next_position(game.snake.head(), Direction::Up).0;
+        game.update();
+        assert_eq!(game.level(), 2);
+        assert_ne!(game.walls, first);
+        assert!(game.snake.body.iter().all(|cell| !game.walls.contains(cell)));
+    }
+}
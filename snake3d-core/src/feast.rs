@@ -0,0 +1,90 @@
+// Feasts: occasional 2x2 food blocks that take one bite per cell. Each bite
+// scores and grows the snake like normal food; the block shrinks until the
+// last cell is gone.
+
+use crate::game::{Face, GameEvent, GameState, Position};
+
+// After a meal there's a 1 in FEAST_CHANCE chance of a feast appearing.
+const FEAST_CHANCE: u32 = 10;
+pub const FEAST_SIZE: i32 = 2;
+pub const BITE_POINTS: u32 = 2;
+// Placement attempts before giving up until the next meal.
+const PLACEMENT_TRIES: u32 = 20;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Feast {
+    // Cells not eaten yet.
+    pub cells: Vec<Position>,
+}
+
+impl GameState {
+    pub fn feast_at(&self, pos: Position) -> bool {
+        self.feast.as_ref().is_some_and(|f| f.cells.contains(&pos))
+    }
+
+    pub(crate) fn maybe_spawn_feast(&mut self) {
+        if self.feast.is_some() || self.rng.below(FEAST_CHANCE) != 0 {
+            return;
+        }
+        let span = (self.config.grid_size - FEAST_SIZE + 1) as u32;
+        for _ in 0..PLACEMENT_TRIES {
+            let face = Face::ALL[self.rng.below(6) as usize];
+            let u = self.rng.below(span) as i32;
+            let v = self.rng.below(span) as i32;
+            let cells: Vec<Position> = (0..FEAST_SIZE)
+                .flat_map(|du| (0..FEAST_SIZE).map(move |dv| Position { face, u: u + du, v: v + dv }))
+                .collect();
+            if cells.iter().all(|c| self.cell_is_free(*c)) {
+                self.feast = Some(Feast { cells });
+                return;
+            }
+        }
+    }
+
+    // The head just moved onto a feast cell.
+    pub(crate) fn bite_feast(&mut self, pos: Position) -> GameEvent {
+        if let Some(feast) = &mut self.feast {
+            feast.cells.retain(|c| *c != pos);
+            if feast.cells.is_empty() {
+                self.feast = None;
+            }
+        }
+        self.score += BITE_POINTS;
+        if self.score > self.high_score {
+            self.high_score = self.score;
+        }
+        GameEvent::FeastBite
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feast_takes_four_bites() {
+        let mut game = GameState::new(10, 9);
+        let head = game.snake.head();
+        game.food = Position { face: Face::Back, u: 0, v: 0 };
+        game.shield = None;
+        game.feast = Some(Feast {
+            cells: vec![
+                Position { v: head.v + 1, ..head },
+                Position { v: head.v + 2, ..head },
+                Position { u: head.u + 1, v: head.v + 2, ..head },
+                Position { u: head.u + 1, v: head.v + 1, ..head },
+            ],
+        });
+
+        let turns = [None, None, Some(crate::game::Direction::Right), Some(crate::game::Direction::Down)];
+        for (bite, turn) in turns.into_iter().enumerate() {
+            if let Some(dir) = turn {
+                game.steer(dir);
+            }
+            assert_eq!(game.update(), GameEvent::FeastBite);
+            assert_eq!(game.score, (bite as u32 + 1) * BITE_POINTS);
+        }
+        assert_eq!(game.feast, None);
+        assert_eq!(game.snake.body.len(), 5);
+    }
+}
@@ -0,0 +1,131 @@
+// Fuzzing entry point: plays a game driven by arbitrary bytes and checks the
+// state after every tick. The `cargo fuzz` target in `fuzz/` feeds it, and
+// any failure is a bug in the game logic rather than in the input.
+//
+// Input layout: mode, grid size, eight seed bytes, then one byte per tick
+// whose low two bits pick a turn when bit 2 is clear.
+
+use std::fmt;
+
+use crate::game::{Direction, GameConfig, GameState};
+use crate::mode::GameMode;
+
+const MIN_GRID: i32 = 3;
+const MAX_GRID: i32 = 12;
+const HEADER: usize = 10;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvariantError {
+    pub tick: u64,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for InvariantError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "tick {}: {}", self.tick, self.reason)
+    }
+}
+
+impl std::error::Error for InvariantError {}
+
+fn direction(byte: u8) -> Option<Direction> {
+    if byte & 0b100 != 0 {
+        return None;
+    }
+    Some(match byte & 0b11 {
+        0 => Direction::Up,
+        1 => Direction::Down,
+        2 => Direction::Left,
+        _ => Direction::Right,
+    })
+}
+
+// Plays the game `data` describes until the bytes or the run end. Inputs too
+// short for the header are fine and do nothing.
+pub fn run(data: &[u8]) -> Result<(), InvariantError> {
+    if data.len() < HEADER {
+        return Ok(());
+    }
+    let mode = GameMode::ALL[data[0] as usize % GameMode::ALL.len()];
+    let grid_size = MIN_GRID + data[1] as i32 % (MAX_GRID - MIN_GRID + 1);
+    let mut seed = [0; 8];
+    seed.copy_from_slice(&data[2..HEADER]);
+    let mut game = GameState::with_config(GameConfig::with_mode(grid_size, mode), u64::from_le_bytes(seed));
+
+    for &byte in &data[HEADER..] {
+        if game.game_over {
+            break;
+        }
+        if let Some(dir) = direction(byte) {
+            game.steer(dir);
+        }
+        let before = game.clone();
+        game.update();
+        check(&before, &game)?;
+    }
+    Ok(())
+}
+
+// The invariants between two consecutive states.
+fn check(before: &GameState, after: &GameState) -> Result<(), InvariantError> {
+    let fail = |reason| Err(InvariantError { tick: after.ticks, reason });
+    if after.score < before.score {
+        return fail("score went down");
+    }
+    let snakes = || std::iter::once(&after.snake).chain(after.partner.as_ref());
+    if snakes().any(|snake| snake.contains(after.food)) {
+        return fail("food is under the snake");
+    }
+    if !snakes().all(|snake| snake.occupancy_in_step()) {
+        return fail("occupancy is out of step with the body");
+    }
+    // A lost life starts the snake over, so there's nothing to compare.
+    if after.game_over || after.lives < before.lives {
+        return Ok(());
+    }
+    let head = after.snake.head();
+    let n = after.config.grid_size;
+    if !(0..n).contains(&head.u) || !(0..n).contains(&head.v) {
+        return fail("head is off the board");
+    }
+    // One push a tick, and one pop unless something was eaten. A shield
+    // breaking cuts the body instead.
+    let ate = head == before.food
+        || before.feast.as_ref().is_some_and(|f| f.cells.contains(&head))
+        || before.rush_food.contains(&head);
+    let expected = before.snake.body.len() + usize::from(ate);
+    let shield_broke = before.shielded && !after.shielded;
+    if shield_broke {
+        if after.snake.body.len() > expected {
+            return fail("snake grew past a shield break");
+        }
+    } else if after.snake.body.len() != expected {
+        return fail("snake length doesn't match pushes minus pops");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::rng::Rng;
+
+    #[test]
+    fn test_random_games_hold_up() {
+        for seed in 0..200 {
+            let mut rng = Rng::new(seed);
+            let data: Vec<u8> = (0..HEADER + 400).map(|_| rng.below(256) as u8).collect();
+            assert_eq!(run(&data), Ok(()), "seed {}", seed);
+        }
+        assert_eq!(run(&[]), Ok(()));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_arbitrary_inputs_hold_up(data in prop::collection::vec(any::<u8>(), 0..600)) {
+            prop_assert_eq!(run(&data), Ok(()));
+        }
+    }
+}
@@ -0,0 +1,1329 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::boss::Boss;
+use crate::feast::Feast;
+use crate::hazard::Hazard;
+use crate::level::{Portal, WinCondition};
+use crate::mode::GameMode;
+use crate::rng::Rng;
+use crate::tron::Rival;
+use crate::turret::{Projectile, Turret};
+use crate::world::WorldEvent;
+
+const SHIELD_CHANCE: u32 = 8;
+// Every this many meals is a prize.
+const PRIZE_EVERY: u32 = 5;
+// Extra points per streak step are capped at this.
+const MAX_STREAK_BONUS: u32 = 5;
+// Points for touching all six faces between two pickups.
+pub const ALL_FACES_BONUS: u32 = 10;
+const ALL_FACES: u8 = 0b11_1111;
+pub const LEVEL_POINTS: u32 = 10;
+// Speedrun splits are taken every this many points.
+pub const SPLIT_POINTS: u32 = 25;
+// A prize vanishes if not eaten within this many seconds.
+pub const PRIZE_SECONDS: f64 = 6.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Face {
+    Front,
+    Back,
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl Face {
+    pub const ALL: [Face; 6] = [Face::Front, Face::Back, Face::Left, Face::Right, Face::Top, Face::Bottom];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Face::Front => "front",
+            Face::Back => "back",
+            Face::Left => "left",
+            Face::Right => "right",
+            Face::Top => "top",
+            Face::Bottom => "bottom",
+        }
+    }
+
+    // Bit for this face in a visited-faces mask.
+    pub fn bit(self) -> u8 {
+        1 << Face::ALL.iter().position(|f| *f == self).unwrap_or(0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Position {
+    pub face: Face,
+    pub u: i32,
+    pub v: i32,
+}
+
+// Tick pacing, in seconds between moves. The interval starts at `base_interval`
+// and shrinks by `ramp` per point until it reaches `min_interval`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpeedCurve {
+    pub base_interval: f64,
+    pub min_interval: f64,
+    pub ramp: f64,
+}
+
+impl Default for SpeedCurve {
+    // Full speed at 50 points.
+    fn default() -> Self {
+        Self {
+            base_interval: 0.15,
+            min_interval: 0.05,
+            ramp: 0.002,
+        }
+    }
+}
+
+impl SpeedCurve {
+    // The same pace the whole run, whatever the score.
+    pub fn fixed(ticks_per_second: f64) -> Self {
+        let interval = 1.0 / ticks_per_second;
+        Self { base_interval: interval, min_interval: interval, ramp: 0.0 }
+    }
+
+    // Whether the curve can pace a run: a positive floor no faster than the
+    // start, and a ramp that doesn't slow the game down. NaNs fail.
+    pub fn is_valid(&self) -> bool {
+        self.min_interval > 0.0 && self.min_interval <= self.base_interval && self.ramp >= 0.0
+    }
+
+    pub fn interval(&self, score: u32) -> f64 {
+        let reduction = (score as f64 * self.ramp).min(self.base_interval - self.min_interval);
+        self.base_interval - reduction.max(0.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GameConfig {
+    pub grid_size: i32,
+    pub mode: GameMode,
+    pub speed: SpeedCurve,
+    // Roaming hazards (see `hazard.rs`).
+    pub hazards: bool,
+    // Turrets and their projectiles (see `turret.rs`).
+    pub turrets: bool,
+    // Ticks before uneaten food moves elsewhere; `None` keeps it in place.
+    pub food_lifetime: Option<u64>,
+    // Random world events (see `world.rs`).
+    pub world_events: bool,
+    // The shrinking arena (see `storm.rs`).
+    pub storm: bool,
+    // On-beat turns score (see `rhythm.rs`).
+    pub rhythm: bool,
+    // Rewinds per run (see `rewind.rs`).
+    pub rewinds: u32,
+    // Every this many meals is a prize.
+    pub prize_every: u32,
+    // A meal brings out a shield 1 time in this many.
+    pub shield_chance: u32,
+    // What ends the run as a win.
+    pub win: WinCondition,
+    // Boss encounters (see `boss.rs`).
+    pub boss: bool,
+    // A second snake on the same keyboard (see `coop.rs`).
+    pub coop: bool,
+    // Crashes the run can take; each past the last ends it.
+    pub lives: u32,
+}
+
+impl GameConfig {
+    pub fn new(grid_size: i32) -> Self {
+        Self::with_mode(grid_size, GameMode::Classic)
+    }
+
+    // Uses the mode's own pacing.
+    pub fn with_mode(grid_size: i32, mode: GameMode) -> Self {
+        Self {
+            grid_size,
+            mode,
+            speed: mode.speed(),
+            hazards: mode.hazards(),
+            turrets: mode.turrets(),
+            food_lifetime: mode.food_lifetime(),
+            world_events: mode.world_events(),
+            storm: mode.storm(),
+            rhythm: mode.rhythm(),
+            rewinds: mode.rewinds(),
+            prize_every: PRIZE_EVERY,
+            shield_chance: SHIELD_CHANCE,
+            win: mode.target_score().map_or(WinCondition::Endless, WinCondition::Score),
+            boss: mode.boss(),
+            coop: mode.coop(),
+            lives: mode.lives(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Snake {
+    // Head first. Read freely, but change it through the methods below so
+    // `occupied` keeps up.
+    pub body: VecDeque<Position>,
+    pub direction: Direction,
+    pub next_direction: Direction,
+    // How many segments sit on each cell, for constant-time `contains`. A
+    // count rather than a set since the head can briefly share the tail's
+    // cell while chasing it.
+    occupied: HashMap<Position, u32>,
+}
+
+impl Snake {
+    pub fn new(start_pos: Position, start_dir: Direction) -> Self {
+        let mut snake = Self {
+            body: VecDeque::new(),
+            direction: start_dir,
+            next_direction: start_dir,
+            occupied: HashMap::new(),
+        };
+        // Add a couple more segments for initial length
+        // For simplicity, just one for now, or handle in Game::new
+        snake.push_front(start_pos);
+        snake
+    }
+
+    pub fn head(&self) -> Position {
+        *self.body.front().unwrap()
+    }
+
+    pub fn contains(&self, pos: Position) -> bool {
+        self.occupied.contains_key(&pos)
+    }
+
+    pub fn push_front(&mut self, pos: Position) {
+        self.body.push_front(pos);
+        *self.occupied.entry(pos).or_insert(0) += 1;
+    }
+
+    pub fn pop_back(&mut self) -> Option<Position> {
+        let tail = self.body.pop_back()?;
+        self.vacate(tail);
+        Some(tail)
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        while self.body.len() > len {
+            self.pop_back();
+        }
+    }
+
+    // Replaces the whole body, head first.
+    pub fn set_body(&mut self, cells: impl IntoIterator<Item = Position>) {
+        self.body.clear();
+        self.occupied.clear();
+        for cell in cells {
+            self.body.push_back(cell);
+            *self.occupied.entry(cell).or_insert(0) += 1;
+        }
+    }
+
+    fn vacate(&mut self, pos: Position) {
+        if let Some(count) = self.occupied.get_mut(&pos) {
+            *count -= 1;
+            if *count == 0 {
+                self.occupied.remove(&pos);
+            }
+        }
+    }
+
+    // Whether `occupied` matches `body`, for the fuzzer.
+    pub(crate) fn occupancy_in_step(&self) -> bool {
+        let mut counts = HashMap::new();
+        for cell in &self.body {
+            *counts.entry(*cell).or_insert(0) += 1;
+        }
+        counts == self.occupied
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameEvent {
+    None,
+    Eat,
+    EatPrize,
+    GameOver,
+    ShieldPickup,
+    // A collision was absorbed; the body was cut where it was hit.
+    ShieldBreak,
+    // A pickup after visiting all six faces since the previous one.
+    AllFacesBonus,
+    FeastBite,
+    PrizeExpired,
+    RivalCrashed,
+    // Target score reached (speedrun).
+    Finished,
+    // A random world event just started.
+    World(WorldEvent),
+    // Another ring of the arena closed.
+    StormClosed,
+    // A boss's weak point was eaten, in order.
+    BossHit,
+    BossDefeated,
+    // A crash cost one of the shared lives, and the snake started over.
+    LifeLost,
+}
+
+// What ended a lost run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeathCause {
+    // Ran into its own body.
+    Snake,
+    Hazard,
+    // A turret or its shot.
+    Turret,
+    // A Tron trail or the rival.
+    Wall,
+    Storm,
+    Boss,
+}
+
+impl DeathCause {
+    pub fn name(self) -> &'static str {
+        match self {
+            DeathCause::Snake => "snake",
+            DeathCause::Hazard => "hazard",
+            DeathCause::Turret => "turret",
+            DeathCause::Wall => "wall",
+            DeathCause::Storm => "storm",
+            DeathCause::Boss => "boss",
+        }
+    }
+}
+
+// How and where a lost run ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeathInfo {
+    pub cause: DeathCause,
+    // The fatal cell: what the head ran into, or where a shot struck.
+    pub at: Position,
+    // Body segment that was struck, counting from the head at 0, when the
+    // snake ran into itself or was shot.
+    pub segment: Option<usize>,
+}
+
+impl DeathInfo {
+    pub fn new(cause: DeathCause, at: Position) -> Self {
+        Self { cause, at, segment: None }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GameState {
+    pub snake: Snake,
+    pub food: Position,
+    pub is_prize: bool,
+    pub score: u32,
+    pub high_score: u32,
+    pub food_eaten_count: u32,
+    pub prizes_eaten: u32,
+    pub game_over: bool,
+    pub config: GameConfig,
+    pub rng: Rng,
+    // Shield pickup on the board, if any.
+    pub shield: Option<Position>,
+    // Absorbs the next collision.
+    pub shielded: bool,
+    // Ticks simulated so far.
+    pub ticks: u64,
+    // Consecutive quick pickups; 0 when no streak is running.
+    pub streak: u32,
+    last_eat_tick: u64,
+    // Faces the head has been on since the last pickup, one bit per face.
+    pub faces_visited: u8,
+    pub hazards: Vec<Hazard>,
+    pub turrets: Vec<Turret>,
+    pub projectiles: Vec<Projectile>,
+    pub feast: Option<Feast>,
+    // Tick the current food appeared on.
+    pub food_spawn_tick: u64,
+    // Tick a prize vanishes on; set whenever a prize is out.
+    pub prize_deadline: Option<u64>,
+    // Permanent trail cells in Tron mode (see `tron.rs`).
+    pub walls: HashSet<Position>,
+    // Portal pairs of a level (see `level.rs`).
+    pub portals: Vec<Portal>,
+    pub rival: Option<Rival>,
+    // The boss on the board, if one is out (see `boss.rs`).
+    pub boss: Option<Boss>,
+    // Tick the next boss shows up on.
+    pub next_boss: Option<u64>,
+    pub bosses_defeated: u32,
+    // The second snake in co-op.
+    pub partner: Option<Snake>,
+    pub lives: u32,
+    // Game time in seconds: the sum of tick intervals so far.
+    pub elapsed: f64,
+    // `elapsed` at each `SPLIT_POINTS` threshold, for speedruns.
+    pub splits: Vec<f64>,
+    // Reached the mode's target score; `game_over` is set too.
+    pub finished: bool,
+    // Set when the run is lost.
+    pub death: Option<DeathInfo>,
+    // Tick the next world event fires on; `None` when they're off.
+    pub next_world_event: Option<u64>,
+    // Extra food from a food rush.
+    pub rush_food: Vec<Position>,
+    // Tick a blackout ends on.
+    pub blackout_until: Option<u64>,
+    // Rings of each face lost to the storm, counted from the edge.
+    pub storm_rings: i32,
+    // Tick the next ring closes on.
+    pub next_storm: Option<u64>,
+    // High score when the run started. Taken on the first update, since the
+    // caller loads the stored score after construction.
+    record_to_beat: Option<u32>,
+    // This run has beaten that score. Set on the tick that does it, next to
+    // whatever event the tick had, so hosts celebrate on the flip.
+    pub new_record: bool,
+    // Turns made on the beat since the last pickup, in rhythm mode.
+    pub beat_turns: u32,
+    // States before the last ticks, oldest first (see `rewind.rs`).
+    pub(crate) history: VecDeque<GameState>,
+    pub rewinds_left: u32,
+}
+
+impl GameState {
+    pub fn new(grid_size: i32, seed: u64) -> Self {
+        Self::with_config(GameConfig::new(grid_size), seed)
+    }
+
+    pub fn with_config(config: GameConfig, seed: u64) -> Self {
+        let grid_size = config.grid_size;
+        let start_pos = Position {
+            face: Face::Front,
+            u: grid_size / 2,
+            v: grid_size / 2,
+        };
+        let snake = Snake::new(start_pos, Direction::Up);
+        // The stored high score is loaded by the caller (see `storage::HighScoreStore`).
+
+        let mut game = Self {
+            snake,
+            food: start_pos, // Placeholder
+            is_prize: false,
+            score: 0,
+            high_score: 0,
+            food_eaten_count: 0,
+            prizes_eaten: 0,
+            game_over: false,
+            config,
+            rng: Rng::new(seed),
+            shield: None,
+            shielded: false,
+            ticks: 0,
+            streak: 0,
+            last_eat_tick: 0,
+            faces_visited: start_pos.face.bit(),
+            hazards: Vec::new(),
+            turrets: Vec::new(),
+            projectiles: Vec::new(),
+            feast: None,
+            food_spawn_tick: 0,
+            prize_deadline: None,
+            walls: HashSet::new(),
+            portals: Vec::new(),
+            rival: None,
+            boss: None,
+            next_boss: None,
+            bosses_defeated: 0,
+            partner: None,
+            lives: config.lives,
+            elapsed: 0.0,
+            splits: Vec::new(),
+            finished: false,
+            death: None,
+            next_world_event: None,
+            rush_food: Vec::new(),
+            blackout_until: None,
+            storm_rings: 0,
+            next_storm: None,
+            record_to_beat: None,
+            new_record: false,
+            beat_turns: 0,
+            history: VecDeque::new(),
+            rewinds_left: config.rewinds,
+        };
+        game.spawn_partner();
+        game.spawn_food();
+        game.build_dungeon();
+        game.spawn_rival();
+        game.schedule_world_event();
+        game.schedule_storm();
+        game.schedule_boss();
+        game
+    }
+
+    pub fn spawn_food(&mut self) {
+        self.food = self.random_free_cell();
+        self.food_spawn_tick = self.ticks;
+        self.is_prize = (self.food_eaten_count + 1).is_multiple_of(self.config.prize_every.max(1));
+        // The deadline is fixed in ticks at spawn time, from the current pace.
+        self.prize_deadline = self.is_prize.then(|| {
+            self.ticks + (PRIZE_SECONDS / self.move_interval()).round() as u64
+        });
+    }
+
+    // Ticks left before the prize vanishes, if one is out.
+    pub fn prize_ticks_left(&self) -> Option<u64> {
+        self.prize_deadline.map(|deadline| deadline.saturating_sub(self.ticks))
+    }
+
+    // Fraction of the prize's time left, 1.0 when it appears.
+    pub fn prize_remaining(&self) -> Option<f32> {
+        let deadline = self.prize_deadline?;
+        let total = deadline - self.food_spawn_tick;
+        Some(self.prize_ticks_left()? as f32 / total.max(1) as f32)
+    }
+
+    // Random cell from the seeded game RNG so runs are reproducible.
+    // Retries until it lands on a cell nothing else occupies.
+    pub(crate) fn random_free_cell(&mut self) -> Position {
+        loop {
+            let face = match self.rng.below(6) {
+                0 => Face::Front,
+                1 => Face::Back,
+                2 => Face::Left,
+                3 => Face::Right,
+                4 => Face::Top,
+                _ => Face::Bottom,
+            };
+            let u = self.rng.below(self.config.grid_size as u32) as i32;
+            let v = self.rng.below(self.config.grid_size as u32) as i32;
+
+            let new_pos = Position { face, u, v };
+
+            if self.cell_is_free(new_pos) {
+                return new_pos;
+            }
+        }
+    }
+
+    // Nothing at all on the cell: no snake, food, pickup or obstacle.
+    pub fn cell_is_free(&self, pos: Position) -> bool {
+        !self.snake.contains(pos)
+            && !self.partner.as_ref().is_some_and(|p| p.contains(pos))
+            && pos != self.food
+            && Some(pos) != self.shield
+            && !self.feast_at(pos)
+            && !self.rush_food_at(pos)
+            && !self.obstacle_at(pos)
+            && self.portal_exit(pos).is_none()
+            && self.weak_point_at(pos).is_none()
+    }
+
+    // After each meal there's a 1 in `shield_chance` chance of a shield
+    // appearing, unless one is already out or active.
+    fn maybe_spawn_shield(&mut self) {
+        if self.shield.is_none() && !self.shielded && self.rng.below(self.config.shield_chance.max(1)) == 0 {
+            self.shield = Some(self.random_free_cell());
+        }
+    }
+
+    // Fraction of the food's lifetime left, or `None` if food doesn't expire.
+    pub fn food_remaining(&self) -> Option<f32> {
+        let lifetime = self.config.food_lifetime?;
+        let age = self.ticks - self.food_spawn_tick;
+        Some(1.0 - (age as f32 / lifetime as f32).min(1.0))
+    }
+
+    pub(crate) fn die(&mut self, death: DeathInfo) -> GameEvent {
+        // A spare life starts the snake over somewhere free instead.
+        if self.lives > 1 {
+            self.lives -= 1;
+            let start = self.random_free_cell();
+            self.snake = Snake::new(start, Direction::Up);
+            return GameEvent::LifeLost;
+        }
+        self.lives = 0;
+        self.game_over = true;
+        self.death = Some(death);
+        GameEvent::GameOver
+    }
+
+    // Records splits and ends the run once its win condition is met.
+    fn check_target(&mut self) -> bool {
+        let won = match self.config.win {
+            WinCondition::Endless => return false,
+            WinCondition::Score(target) => {
+                let reached = self.score.min(target) / SPLIT_POINTS;
+                while (self.splits.len() as u32) < reached {
+                    self.splits.push(self.elapsed);
+                }
+                self.score >= target
+            }
+            WinCondition::Survive(seconds) => self.elapsed >= seconds,
+            WinCondition::Prizes(prizes) => self.prizes_eaten >= prizes,
+        };
+        if won {
+            self.finished = true;
+            self.game_over = true;
+        }
+        self.finished
+    }
+
+    // Starts at 1 and goes up every `LEVEL_POINTS` points.
+    pub fn level(&self) -> u32 {
+        1 + self.score / LEVEL_POINTS
+    }
+
+    // Seconds until the next tick at the current score.
+    pub fn move_interval(&self) -> f64 {
+        self.config.speed.interval(self.score)
+    }
+
+    // Ticks allowed between pickups to keep a streak going: about enough to
+    // cross two faces, so chaining needs food on or next to the current face.
+    pub fn streak_window(&self) -> u64 {
+        self.config.grid_size as u64 * 2
+    }
+
+    // Fraction of the streak window left, 1.0 right after a pickup.
+    pub fn streak_remaining(&self) -> f32 {
+        if self.streak == 0 {
+            return 0.0;
+        }
+        let elapsed = self.ticks - self.last_eat_tick;
+        1.0 - (elapsed as f32 / self.streak_window() as f32).min(1.0)
+    }
+
+    // Queue a turn for the next tick. Reversing onto the neck is ignored.
+    pub fn steer(&mut self, dir: Direction) {
+        if self.snake.direction != dir.opposite() {
+            self.snake.next_direction = dir;
+        }
+    }
+
+    pub fn update(&mut self) -> GameEvent {
+        let record = *self.record_to_beat.get_or_insert(self.high_score);
+        if !self.game_over {
+            self.snapshot();
+        }
+        let event = self.step();
+        // Only a record set by an earlier run counts, and not on the tick the
+        // run ends.
+        if record > 0 && self.score > record && !self.game_over {
+            self.new_record = true;
+        }
+        event
+    }
+
+    fn step(&mut self) -> GameEvent {
+        if self.game_over {
+            return GameEvent::None;
+        }
+
+        self.ticks += 1;
+        self.elapsed += self.move_interval();
+        if self.streak > 0 && self.ticks - self.last_eat_tick > self.streak_window() {
+            self.streak = 0;
+        }
+
+        let level = self.level();
+        let event = self.move_snake();
+        if self.game_over {
+            return event;
+        }
+        let partner_event = self.step_partner();
+        if self.game_over {
+            return partner_event;
+        }
+        let event = if event == GameEvent::None { partner_event } else { event };
+        if self.level() > level {
+            self.build_dungeon();
+        }
+        if self.prize_ticks_left() == Some(0) {
+            // Gone: a regular food takes its place and the next prize is
+            // still five meals away.
+            self.spawn_food();
+            self.is_prize = false;
+            self.prize_deadline = None;
+            if event == GameEvent::None {
+                return GameEvent::PrizeExpired;
+            }
+        } else if let Some(lifetime) = self.config.food_lifetime {
+            // Prizes run on their own, shorter timer.
+            if !self.is_prize && self.ticks - self.food_spawn_tick >= lifetime {
+                self.spawn_food();
+            }
+        }
+        let hazard_event = self.step_hazards();
+        if self.game_over {
+            return hazard_event;
+        }
+        let turret_event = self.step_turrets();
+        if self.game_over {
+            return turret_event;
+        }
+        let boss_event = self.step_boss();
+        if self.game_over {
+            return boss_event;
+        }
+        let rival_event = self.step_rival();
+        let storm_event = self.step_storm();
+        if self.game_over {
+            return storm_event;
+        }
+        if self.check_target() {
+            return GameEvent::Finished;
+        }
+        let event = [event, hazard_event, turret_event, boss_event, rival_event, storm_event]
+            .into_iter()
+            .find(|e| *e != GameEvent::None)
+            .unwrap_or(GameEvent::None);
+        // A due world event waits for a quiet tick so its banner isn't lost.
+        if event == GameEvent::None {
+            return self.step_world_events();
+        }
+        event
+    }
+
+    fn move_snake(&mut self) -> GameEvent {
+        self.snake.direction = self.snake.next_direction;
+        let head = self.snake.head();
+        let (new_pos, new_dir) = self.calculate_next_position(head, self.snake.direction);
+        let new_pos = self.portal_exit(new_pos).unwrap_or(new_pos);
+
+        // Check self collision
+        // Note: Tail will move, so we shouldn't collide with tail unless length 2 reverses (impossible by rules)
+        // But we check against current body minus tail if we don't grow.
+        // Simplest: Check full body. If it's the tail, it's fine ONLY if we don't grow.
+        
+        let eating = new_pos == self.food;
+        let bites_feast = self.feast_at(new_pos);
+        let eats_rush = self.rush_food_at(new_pos);
+        let growing = eating || bites_feast || eats_rush;
+        
+        let mut shield_event = GameEvent::None;
+        let mut severed = false;
+        let hit = if self.snake.contains(new_pos) {
+            self.snake.body.iter().position(|p| *p == new_pos)
+        } else {
+            None
+        };
+        if let Some(hit) = hit {
+            // If we are not growing, and new_pos is the tail, it's valid (chasing tail)
+            if !growing && hit == self.snake.body.len() - 1 {
+                // Safe
+            } else if self.shielded {
+                // Sever the body at the hit segment instead of dying.
+                self.shielded = false;
+                self.snake.truncate(hit);
+                severed = true;
+                shield_event = GameEvent::ShieldBreak;
+            } else {
+                return self.die(DeathInfo { segment: Some(hit), ..DeathInfo::new(DeathCause::Snake, new_pos) });
+            }
+        }
+
+        if self.obstacle_at(new_pos) {
+            if self.shielded {
+                self.shielded = false;
+                self.remove_obstacle(new_pos);
+                shield_event = GameEvent::ShieldBreak;
+            } else {
+                return self.die(DeathInfo::new(self.obstacle_cause(new_pos), new_pos));
+            }
+        }
+
+        if self.shield == Some(new_pos) {
+            self.shield = None;
+            self.shielded = true;
+            shield_event = GameEvent::ShieldPickup;
+        }
+
+        self.snake.push_front(new_pos);
+        self.faces_visited |= new_pos.face.bit();
+        // Update direction if changed by transition
+        self.snake.direction = new_dir;
+        self.snake.next_direction = new_dir; // Lock it to avoid quick double turns messing up? 
+        // Actually, we should probably keep next_direction as user input buffer, 
+        // but if transition rotates us, we must update the current direction.
+        
+        if eating {
+            self.streak += 1;
+            self.last_eat_tick = self.ticks;
+            let bonus = (self.streak - 1).min(MAX_STREAK_BONUS);
+            self.score += if self.is_prize { 5 } else { 1 } + bonus + self.take_beat_bonus() + self.combined_bonus();
+            let grand_tour = self.faces_visited == ALL_FACES;
+            if grand_tour {
+                self.score += ALL_FACES_BONUS;
+            }
+            self.faces_visited = new_pos.face.bit();
+            if self.score > self.high_score {
+                self.high_score = self.score;
+            }
+            self.food_eaten_count += 1;
+            if self.is_prize {
+                self.prizes_eaten += 1;
+            }
+            let event = if grand_tour {
+                GameEvent::AllFacesBonus
+            } else if self.is_prize {
+                GameEvent::EatPrize
+            } else {
+                GameEvent::Eat
+            };
+            self.spawn_food();
+            self.maybe_spawn_shield();
+            self.maybe_spawn_feast();
+            self.spawn_hazards();
+            event
+        } else if bites_feast {
+            self.bite_feast(new_pos)
+        } else if eats_rush {
+            self.eat_rush_food(new_pos)
+        } else {
+            // A severed body has already lost its tail this tick.
+            if !severed {
+                let vacated = self.snake.pop_back();
+                if let Some(cell) = vacated.filter(|_| self.config.mode.permanent_trail()) {
+                    self.walls.insert(cell);
+                }
+            }
+            shield_event
+        }
+    }
+
+    pub fn calculate_next_position(&self, pos: Position, dir: Direction) -> (Position, Direction) {
+        let n = self.config.grid_size;
+        let mut u = pos.u;
+        let mut v = pos.v;
+        let mut face = pos.face;
+        let mut new_dir = dir;
+
+        match dir {
+            Direction::Up => v += 1,
+            Direction::Down => v -= 1,
+            Direction::Left => u -= 1,
+            Direction::Right => u += 1,
+        }
+
+        // Check bounds and transition
+        if u < 0 || u >= n || v < 0 || v >= n {
+            // Transition logic
+            match (face, dir) {
+                // Front Transitions
+                (Face::Front, Direction::Up) => { face = Face::Top; v = 0; } // Enter Bottom of Top
+                (Face::Front, Direction::Down) => { face = Face::Bottom; v = n - 1; } // Enter Top of Bottom
+                (Face::Front, Direction::Left) => { face = Face::Left; u = n - 1; } // Enter Right of Left
+                (Face::Front, Direction::Right) => { face = Face::Right; u = 0; } // Enter Left of Right
+
+                // Back Transitions
+                (Face::Back, Direction::Up) => { face = Face::Top; v = n - 1; new_dir = Direction::Down; u = n - 1 - u; } // Top of Back connects to Top of Top? 
+                // Let's define the unfolding.
+                // Standard box unfolding:
+                //   T
+                // L F R B
+                //   Bo
+                
+                // Front (F): u=R, v=U
+                // Top (T): u=R, v=B (Back) -> No, let's keep "Up" consistent with visual up if possible?
+                // If we fold T up 90 deg from F:
+                // F's top edge matches T's bottom edge.
+                // So F(Up) -> T(Up, entering at v=0). Correct.
+                
+                // Right (R):
+                // F's right edge matches R's left edge.
+                // F(Right) -> R(Right, entering at u=0). Correct.
+                
+                // Left (L):
+                // F's left edge matches L's right edge.
+                // F(Left) -> L(Left, entering at u=N-1). Correct.
+                
+                // Bottom (Bo):
+                // F's bottom edge matches Bo's top edge.
+                // F(Down) -> Bo(Down, entering at v=N-1). Correct.
+                
+                // Now the secondary connections (e.g. T -> R).
+                // T is above F. R is right of F.
+                // T's right edge should match R's top edge?
+                // Let's trace:
+                // F(N, N) is top-right corner.
+                // T(N, 0) is bottom-right corner of T.
+                // R(0, N) is top-left corner of R.
+                // So T(Right) -> R(Down)? Or R(Left)?
+                // Let's visualize the corner.
+                // Moving Right on T (increasing u) goes towards the corner shared by F, T, R.
+                // Crossing that edge goes onto R.
+                // On R, we are at the top edge (v=N-1).
+                // And we are moving "Down" (decreasing v) into R?
+                // Or are we moving "Right" relative to R?
+                // If R's "Up" is aligned with F's "Up", then R's top edge is shared with T's right edge.
+                // So T(Right) -> R(Down)?
+                // Let's assume T(Right) enters R at Top edge (v=N-1).
+                // Coordinate mapping:
+                // T(u=N, v) -> R(u=v?, v=N-1)?
+                // Let's trace the corner vertex.
+                // T(N,0) (bottom-right of T) touches F(N,N) (top-right of F) and R(0,N) (top-left of R).
+                // So T(Right) at v=0 enters R at u=0, v=N-1?
+                // Wait, T(Right) at v=0 is the corner.
+                // T(Right) generally means u goes N.
+                // If we cross u=N on T, we enter R.
+                // The edge is T's right edge.
+                // R's corresponding edge is R's Top edge.
+                // So T(Right) -> R(Down).
+                // Mapping: T(v) maps to R(u)?
+                // T(N, 0) -> R(0, N-1).
+                // T(N, N-1) -> R(N-1, N-1).
+                // So T(Right) -> R(Down). New u = T.v. New v = N-1.
+                
+                (Face::Top, Direction::Right) => { face = Face::Right; u = v; v = n - 1; new_dir = Direction::Down; }
+                (Face::Top, Direction::Left) => { face = Face::Left; u = n - 1 - v; v = n - 1; new_dir = Direction::Down; }
+                
+                (Face::Top, Direction::Up) => { face = Face::Back; v = n - 1; new_dir = Direction::Down; u = n - 1 - u; } // Top of T connects to Top of B.
+                // B is back of F.
+                // T(Up) -> B.
+                // If we go "Up" on T (away from F), we go over the back to B.
+                // B's orientation: usually "Up" on B is same global Up?
+                // If so, T's top edge touches B's top edge.
+                // So T(Up) -> B(Down).
+                // T(u) maps to B(u)?
+                // T(0, N) (top-left of T) -> B(N, N) (top-right of B)?
+                // Let's assume B is "unwrapped" such that B's right is L, B's left is R.
+                // Standard T-shape map:
+                //   T
+                // L F R
+                //   Bo
+                //   B (below Bo)
+                // OR
+                //   T
+                // L F R B (strip)
+                //   Bo
+                // Let's stick to:
+                // F neighbors: T(U), Bo(D), L(L), R(R).
+                // B neighbors: T(U), Bo(D), R(L), L(R). (Wrapped around).
+                // Note: B's Left is R (because R wraps around). B's Right is L.
+                // B's Up is T?
+                // If F(Up)->T(Bottom), then T(Top)->B(Top)? Yes.
+                // So T(Up) -> B(Down).
+                // Coordinate matching:
+                // T(u) goes 0..N (Left to Right).
+                // B(u) goes 0..N (Left to Right, looking at B).
+                // If we go over the top:
+                // T(0, N) is T-Left-Top corner.
+                // B(0, N) is B-Left-Top corner?
+                // If B is viewed from behind, "Left" is actually World Right?
+                // Let's define B's u,v such that B(u,v) corresponds to the face rendering.
+                // If we look at B, u increases Right, v increases Up.
+                // T(Top-Left) connects to B(Top-Left)?
+                // No, T(Top-Left) is World-Left-Back.
+                // B(Top-Left) is World-Right-Back (if looking at B from back).
+                // Wait, "Left" on B (u=0) should be adjacent to R?
+                // Let's check the strip: L - F - R - B - L ...
+                // F(Right) -> R(Left).
+                // R(Right) -> B(Left).
+                // B(Right) -> L(Left).
+                // L(Right) -> F(Left).
+                // So B's Left edge (u=0) is R's Right edge (u=N).
+                // B's Right edge (u=N) is L's Left edge (u=0).
+                // B's Top edge (v=N) is T's Top edge (v=N).
+                // B's Bottom edge (v=0) is Bo's Bottom edge (v=0).
+                
+                // So T(Up) -> B(Down).
+                // T(u) matches B(u) but inverted?
+                // T(Left) is L side. T(Right) is R side.
+                // B(Left) is R side. B(Right) is L side.
+                // So T(Left) connects to B(Right)? No.
+                // T(Left) connects to L.
+                // T(Right) connects to R.
+                // T(Top) connects to B(Top).
+                // T(Top-Left) is corner L-T-B.
+                // B(Top-Right) is corner L-T-B (since B(Right) is L).
+                // So T(u=0) -> B(u=N-1).
+                // T(u=N-1) -> B(u=0).
+                // So u = N - 1 - u.
+                
+                (Face::Top, Direction::Down) => { face = Face::Front; v = n - 1; } // Back to Front
+
+                // Bottom Transitions
+                (Face::Bottom, Direction::Up) => { face = Face::Front; v = 0; }
+                (Face::Bottom, Direction::Down) => { face = Face::Back; v = 0; new_dir = Direction::Up; u = n - 1 - u; } // Bo(Bottom) -> B(Bottom).
+                // B(Bottom) is v=0.
+                // Bo(Bottom) is v=0.
+                // Bo(Down) -> B(Up).
+                // Matching u:
+                // Bo(Left) is L. Bo(Right) is R.
+                // B(Left) is R. B(Right) is L.
+                // Bo(Left-Bottom) corner L-Bo-B.
+                // B(Right-Bottom) corner L-Bo-B.
+                // So Bo(u=0) -> B(u=N-1).
+                // u = N - 1 - u.
+                
+                (Face::Bottom, Direction::Left) => { face = Face::Left; u = v; v = 0; new_dir = Direction::Up; } // Bo(Left) -> L(Bottom).
+                // Bo(Left) is u=-1.
+                // L(Bottom) is v=0.
+                // Bo(Left) edge matches L(Bottom) edge.
+                // Bo(u=0, v) -> L(u=?, v=0).
+                // Bo(0, 0) (Left-Bottom) -> L(N-1, 0) (Right-Bottom)?
+                // Bo(0, N-1) (Left-Top) -> L(0, 0) (Left-Bottom)?
+                // Wait, Bo(Top) is F. L(Bottom) is Bo.
+                // L(Right) is F.
+                // So L(Bottom-Right) is F-Bo-L corner.
+                // Bo(Top-Left) is F-Bo-L corner.
+                // So Bo(0, N-1) -> L(N-1, 0).
+                // Bo(0, 0) -> L(0, 0)?
+                // Let's check Bo(Bottom) -> B.
+                // L(Left) -> B.
+                // So L(Bottom-Left) is B-Bo-L corner.
+                // Bo(Bottom-Left) is B-Bo-L corner.
+                // So Bo(0, 0) -> L(0, 0).
+                // So Bo(Left) -> L(Up).
+                // Bo.v maps to L.u.
+                // Bo(v=0) -> L(u=0).
+                // Bo(v=N-1) -> L(u=N-1).
+                // So u = v.
+                
+                (Face::Bottom, Direction::Right) => { face = Face::Right; u = n - 1 - v; v = 0; new_dir = Direction::Up; }
+                // Bo(Right) -> R(Bottom).
+                // Bo(Right) is u=N.
+                // R(Bottom) is v=0.
+                // Bo(N, N-1) (Top-Right) -> R(0, 0) (Left-Bottom).
+                // Bo(N, 0) (Bottom-Right) -> R(N-1, 0) (Right-Bottom).
+                // So Bo.v maps to R.u inverted.
+                // u = N - 1 - v.
+
+                // Right Transitions
+                (Face::Right, Direction::Left) => { face = Face::Front; u = n - 1; }
+                (Face::Right, Direction::Right) => { face = Face::Back; u = 0; } // R(Right) -> B(Left).
+
+                // Wait, R(Up) is v=N.
+                // T(Right) is u=N.
+                // R(Top-Left) -> T(Bottom-Right)?
+                // R(0, N) -> T(N, 0).
+                // R(N-1, N) -> T(N, N-1).
+                // So R.u maps to T.v.
+                // u = R.u. v = R.u.
+                // Wait, R(Up) -> T(Right).
+                // We enter T from the Right edge (u=N).
+                // So we are moving Left on T.
+                // new_dir = Left.
+                // u = N - 1.
+                // v = ?.
+                // R(0, N) -> T(N, 0). (u=0 -> v=0).
+                // R(N-1, N) -> T(N, N-1). (u=N-1 -> v=N-1).
+                // So v = u (old u).
+                (Face::Right, Direction::Up) => { face = Face::Top; let old_u = u; u = n - 1; v = old_u; new_dir = Direction::Left; }
+
+                (Face::Right, Direction::Down) => { face = Face::Bottom; let old_u = u; u = n - 1; v = n - 1 - old_u; new_dir = Direction::Left; }
+                // R(Down) -> Bo(Right).
+                // R(Bottom-Left) -> Bo(Top-Right).
+                // R(0, 0) -> Bo(N, N-1).
+                // R(N-1, 0) -> Bo(N, 0).
+                // So R.u maps to Bo.v inverted.
+                // v = N - 1 - u.
+                // Enter Bo from Right (u=N). Moving Left.
+                
+                // Left Transitions
+                (Face::Left, Direction::Right) => { face = Face::Front; u = 0; }
+                (Face::Left, Direction::Left) => { face = Face::Back; u = n - 1; } // L(Left) -> B(Right).
+                (Face::Left, Direction::Up) => { face = Face::Top; let old_u = u; u = 0; v = n - 1 - old_u; new_dir = Direction::Right; }
+                // L(Up) -> T(Left).
+                // L(Top-Right) -> T(Bottom-Left).
+                // L(N-1, N) -> T(0, 0).
+                // L(0, N) -> T(0, N-1).
+                // So L.u maps to T.v inverted.
+                // v = N - 1 - u.
+                // Enter T from Left (u=-1). Moving Right.
+                
+                (Face::Left, Direction::Down) => { face = Face::Bottom; let old_u = u; u = 0; v = old_u; new_dir = Direction::Right; }
+                // L(Down) -> Bo(Left).
+                // L(Bottom-Right) -> Bo(Top-Left).
+                // L(N-1, 0) -> Bo(0, N-1).
+                // L(0, 0) -> Bo(0, 0).
+                // So L.u maps to Bo.v.
+                // v = u.
+                // Enter Bo from Left (u=-1). Moving Right.
+                
+                // Back Transitions
+                (Face::Back, Direction::Down) => { face = Face::Bottom; v = 0; new_dir = Direction::Up; u = n - 1 - u; } // B(Down) -> Bo(Bottom) ??
+                // Wait, B(Down) is v=-1.
+                // B(Top) is T. B(Bottom) is Bo.
+                // B(Bottom) edge matches Bo(Bottom) edge.
+                // So B(Down) -> Bo(Up).
+                // u = N - 1 - u.
+                
+                (Face::Back, Direction::Left) => { face = Face::Right; u = n - 1; } // B(Left) -> R(Right).
+                (Face::Back, Direction::Right) => { face = Face::Left; u = 0; } // B(Right) -> L(Left).
+                
+
+            }
+        }
+
+        (Position { face, u, v }, new_dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_front_transitions() {
+        let grid_size = 16;
+        let game = GameState::new(grid_size, 0);
+        
+        // Front -> Top
+        let pos = Position { face: Face::Front, u: 5, v: 15 };
+        let (new_pos, _) = game.calculate_next_position(pos, Direction::Up);
+        assert_eq!(new_pos.face, Face::Top);
+        assert_eq!(new_pos.u, 5);
+        assert_eq!(new_pos.v, 0);
+
+        // Front -> Right
+        let pos = Position { face: Face::Front, u: 15, v: 5 };
+        let (new_pos, _) = game.calculate_next_position(pos, Direction::Right);
+        assert_eq!(new_pos.face, Face::Right);
+        assert_eq!(new_pos.u, 0);
+        assert_eq!(new_pos.v, 5);
+    }
+
+    #[test]
+    fn test_top_transitions() {
+        let grid_size = 16;
+        let game = GameState::new(grid_size, 0);
+
+        // Top -> Back (Up)
+        let pos = Position { face: Face::Top, u: 5, v: 15 };
+        let (new_pos, new_dir) = game.calculate_next_position(pos, Direction::Up);
+        assert_eq!(new_pos.face, Face::Back);
+        assert_eq!(new_dir, Direction::Down);
+        assert_eq!(new_pos.u, 16 - 1 - 5); // 10
+        assert_eq!(new_pos.v, 15);
+    }
+
+    #[test]
+    fn test_speed_curve() {
+        let mut game = GameState::new(10, 1);
+        assert!((game.move_interval() - 0.15).abs() < 1e-9);
+        game.score = 25;
+        assert!((game.move_interval() - 0.10).abs() < 1e-9);
+        game.score = 500;
+        assert!((game.move_interval() - 0.05).abs() < 1e-9);
+    }
+
+    // Head at (5,5) turning down into its own body at (5,4).
+    fn coiled_game() -> GameState {
+        let mut game = GameState::new(10, 7);
+        let at = |u, v| Position { face: Face::Front, u, v };
+        game.snake.set_body([at(5, 5), at(4, 5), at(4, 4), at(5, 4), at(6, 4)]);
+        game.snake.direction = Direction::Right;
+        game.steer(Direction::Down);
+        game.food = Position { face: Face::Back, u: 0, v: 0 };
+        game.shield = None;
+        game
+    }
+
+    #[test]
+    fn test_shield_severs_instead_of_dying() {
+        let mut game = coiled_game();
+        game.shielded = true;
+        assert_eq!(game.update(), GameEvent::ShieldBreak);
+        assert!(!game.game_over);
+        assert!(!game.shielded);
+        assert_eq!(game.snake.body.len(), 4);
+        assert_eq!(game.snake.head(), Position { face: Face::Front, u: 5, v: 4 });
+
+        let mut game = coiled_game();
+        assert_eq!(game.update(), GameEvent::GameOver);
+        let fatal = Position { face: Face::Front, u: 5, v: 4 };
+        assert_eq!(game.death, Some(DeathInfo { cause: DeathCause::Snake, at: fatal, segment: Some(3) }));
+    }
+
+    #[test]
+    fn test_occupancy_follows_a_tail_chase() {
+        let mut game = GameState::new(10, 7);
+        let at = |u, v| Position { face: Face::Front, u, v };
+        // A 2x2 loop: the head keeps moving onto the cell the tail leaves.
+        game.snake.set_body([at(5, 5), at(4, 5), at(4, 4), at(5, 4)]);
+        game.snake.direction = Direction::Down;
+        game.snake.next_direction = Direction::Down;
+        game.food = Position { face: Face::Back, u: 0, v: 0 };
+        game.shield = None;
+        for dir in [Direction::Down, Direction::Left, Direction::Up, Direction::Right].into_iter().cycle().take(12) {
+            game.steer(dir);
+            game.update();
+            assert!(!game.game_over);
+            assert!(game.snake.occupancy_in_step());
+        }
+        assert!(game.snake.contains(at(4, 4)));
+        assert!(!game.snake.contains(at(6, 6)));
+    }
+
+    #[test]
+    fn test_shield_pickup() {
+        let mut game = GameState::new(10, 7);
+        let head = game.snake.head();
+        game.shield = Some(Position { v: head.v + 1, ..head });
+        assert_eq!(game.update(), GameEvent::ShieldPickup);
+        assert!(game.shielded);
+        assert_eq!(game.shield, None);
+    }
+
+    #[test]
+    fn test_streak_bonus_and_decay() {
+        let mut game = GameState::new(10, 3);
+        let head = game.snake.head();
+        game.shield = None;
+
+        // Two pickups in a row: the second is worth one extra point.
+        game.food = Position { v: head.v + 1, ..head };
+        game.update();
+        assert_eq!((game.streak, game.score), (1, 1));
+        game.is_prize = false;
+        game.food = Position { v: head.v + 2, ..head };
+        game.update();
+        assert_eq!((game.streak, game.score), (2, 3));
+
+        // Then let the window run out.
+        game.food = Position { face: Face::Back, u: 0, v: 0 };
+        game.shield = None;
+        game.snake.direction = Direction::Right;
+        game.steer(Direction::Right);
+        for _ in 0..=game.streak_window() {
+            game.update();
+        }
+        assert_eq!(game.streak, 0);
+        assert_eq!(game.streak_remaining(), 0.0);
+    }
+
+    #[test]
+    fn test_all_faces_bonus() {
+        let mut game = GameState::new(10, 5);
+        let head = game.snake.head();
+        game.shield = None;
+        game.food = Position { v: head.v + 1, ..head };
+
+        game.faces_visited = 0b11_1111;
+        assert_eq!(game.update(), GameEvent::AllFacesBonus);
+        assert_eq!(game.score, 1 + ALL_FACES_BONUS);
+        // The mask restarts from the face the pickup was on.
+        assert_eq!(game.faces_visited, Face::Front.bit());
+    }
+
+    #[test]
+    fn test_food_expires_and_moves() {
+        let mut config = GameConfig::new(10);
+        config.food_lifetime = Some(5);
+        let mut game = GameState::with_config(config, 13);
+        game.food = Position { face: Face::Back, u: 0, v: 0 };
+        game.food_spawn_tick = 0;
+        game.shield = None;
+
+        for _ in 0..4 {
+            game.update();
+        }
+        assert_eq!(game.food, Position { face: Face::Back, u: 0, v: 0 });
+        assert!((game.food_remaining().unwrap() - 0.2).abs() < 1e-6);
+        game.update();
+        assert_ne!(game.food, Position { face: Face::Back, u: 0, v: 0 });
+        assert_eq!(game.food_remaining(), Some(1.0));
+
+        assert_eq!(GameState::new(10, 13).food_remaining(), None);
+    }
+
+    #[test]
+    fn test_prize_expires() {
+        let mut game = GameState::new(10, 17);
+        game.food_eaten_count = 4;
+        game.spawn_food();
+        assert!(game.is_prize);
+        // 6 seconds at the starting 0.15s per tick.
+        assert_eq!(game.prize_ticks_left(), Some(40));
+
+        game.snake.direction = Direction::Right;
+        game.steer(Direction::Right);
+        game.food = Position { face: Face::Back, u: 0, v: 0 };
+        game.shield = None;
+        let mut expired = false;
+        for _ in 0..40 {
+            expired |= game.update() == GameEvent::PrizeExpired;
+        }
+        assert!(expired);
+        assert!(!game.is_prize);
+        assert_eq!(game.prize_remaining(), None);
+    }
+
+    #[test]
+    fn test_speedrun_splits_and_finish() {
+        let mut game = GameState::with_config(GameConfig::with_mode(10, GameMode::Speedrun), 19);
+        game.snake.direction = Direction::Right;
+        game.steer(Direction::Right);
+        game.food = Position { face: Face::Back, u: 0, v: 0 };
+        game.shield = None;
+
+        game.score = 30;
+        assert_eq!(game.update(), GameEvent::None);
+        assert_eq!(game.splits.len(), 1);
+        assert_eq!(game.splits[0], game.elapsed);
+
+        game.score = 100;
+        assert_eq!(game.update(), GameEvent::Finished);
+        assert!(game.finished && game.game_over);
+        assert_eq!(game.splits.len(), 4);
+    }
+
+    #[test]
+    fn test_same_seed_same_food() {
+        let a = GameState::new(10, 42);
+        let b = GameState::new(10, 42);
+        assert_eq!(a.food, b.food);
+    }
+
+    #[test]
+    fn test_new_high_score_fires_once() {
+        let mut game = GameState::new(10, 42);
+        game.high_score = 1;
+        game.shield = None;
+        game.food = Position { v: game.snake.head().v + 1, ..game.snake.head() };
+        assert_eq!(game.update(), GameEvent::Eat);
+        assert!(!game.new_record);
+
+        game.food = Position { v: game.snake.head().v + 1, ..game.snake.head() };
+        assert_eq!(game.update(), GameEvent::Eat);
+        assert!(game.new_record);
+        assert_eq!(game.high_score, game.score);
+
+        game.food = Position { v: game.snake.head().v + 1, ..game.snake.head() };
+        assert_eq!(game.update(), GameEvent::Eat);
+        assert!(game.new_record);
+    }
+
+    #[test]
+    fn test_record_keeps_the_bite_event() {
+        let mut game = GameState::new(10, 42);
+        game.high_score = 1;
+        game.shield = None;
+        game.faces_visited = 0b11_1111;
+        game.food = Position { v: game.snake.head().v + 1, ..game.snake.head() };
+        assert_eq!(game.update(), GameEvent::AllFacesBonus);
+        assert!(game.new_record);
+    }
+}
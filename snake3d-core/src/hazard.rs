@@ -0,0 +1,207 @@
+// Roaming hazards: blocks that wander the cube on their own, slower tick.
+// Touching one is fatal (a shield destroys it instead) and any food they
+// roll over is knocked to a new cell. Turrets live in `turret.rs`.
+
+use crate::game::{DeathCause, DeathInfo, Direction, GameEvent, GameState, Position};
+
+// Hazards move once every this many snake ticks.
+const HAZARD_PERIOD: u64 = 2;
+const MAX_HAZARDS: usize = 6;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Hazard {
+    pub pos: Position,
+    pub dir: Direction,
+}
+
+impl GameState {
+    pub fn hazard_at(&self, pos: Position) -> Option<usize> {
+        self.hazards.iter().position(|h| h.pos == pos)
+    }
+
+    // Anything the snake dies on besides itself: hazards, turrets,
+    // projectiles, Tron walls and rival, a boss, and the survival storm.
+    pub fn obstacle_at(&self, pos: Position) -> bool {
+        self.storm_at(pos)
+            || self.hazard_at(pos).is_some()
+            || self.turrets.iter().any(|t| t.pos == pos)
+            || self.projectiles.iter().any(|p| p.pos == pos)
+            || self.walls.contains(&pos)
+            || self.rival.is_some_and(|r| r.pos == pos)
+            || self.boss.as_ref().is_some_and(|b| b.covers(pos))
+    }
+
+    // Which kind of obstacle is at `pos`, for a run that ended on it.
+    pub(crate) fn obstacle_cause(&self, pos: Position) -> DeathCause {
+        if self.storm_at(pos) {
+            DeathCause::Storm
+        } else if self.hazard_at(pos).is_some() {
+            DeathCause::Hazard
+        } else if self.turrets.iter().any(|t| t.pos == pos) || self.projectiles.iter().any(|p| p.pos == pos) {
+            DeathCause::Turret
+        } else if self.boss.as_ref().is_some_and(|b| b.covers(pos)) {
+            DeathCause::Boss
+        } else {
+            DeathCause::Wall
+        }
+    }
+
+    // Destroys whatever obstacle is at `pos`; used when a shield absorbs a hit.
+    // The storm and a boss can't be destroyed, only passed through.
+    pub(crate) fn remove_obstacle(&mut self, pos: Position) {
+        self.hazards.retain(|h| h.pos != pos);
+        self.turrets.retain(|t| t.pos != pos);
+        self.projectiles.retain(|p| p.pos != pos);
+        self.walls.remove(&pos);
+        if self.rival.is_some_and(|r| r.pos == pos) {
+            self.rival = None;
+            self.spawn_rival();
+        }
+    }
+
+    // One more hazard per level, up to `MAX_HAZARDS`.
+    fn hazard_target(&self) -> usize {
+        if !self.config.hazards {
+            return 0;
+        }
+        ((self.level() - 1) as usize).min(MAX_HAZARDS)
+    }
+
+    // Tops the hazard count up to the current level's target. New hazards
+    // never appear on the face the snake is on.
+    pub(crate) fn spawn_hazards(&mut self) {
+        self.spawn_turrets();
+        let head_face = self.snake.head().face;
+        while self.hazards.len() < self.hazard_target() {
+            let pos = self.random_free_cell();
+            if pos.face == head_face {
+                continue;
+            }
+            let dir = self.random_direction();
+            self.hazards.push(Hazard { pos, dir });
+        }
+    }
+
+    // Moves every hazard one cell on hazard ticks. Returns `GameOver`,
+    // `LifeLost` or `ShieldBreak` if one ran into a snake's head.
+    pub(crate) fn step_hazards(&mut self) -> GameEvent {
+        if self.hazards.is_empty() || !self.ticks.is_multiple_of(HAZARD_PERIOD) {
+            return GameEvent::None;
+        }
+
+        let mut event = GameEvent::None;
+        let mut i = 0;
+        while i < self.hazards.len() {
+            let hazard = self.hazards[i];
+            // Mostly keep going; now and then turn.
+            let preferred = match self.rng.below(8) {
+                0 => turn_left(hazard.dir),
+                1 => turn_right(hazard.dir),
+                _ => hazard.dir,
+            };
+            let candidates = [preferred, turn_left(preferred), turn_right(preferred), preferred.opposite()];
+            let head = self.snake.head();
+            let partner_head = self.partner.as_ref().map(|partner| partner.head());
+            let step = candidates.into_iter().map(|d| self.calculate_next_position(hazard.pos, d)).find(|(pos, _)| {
+                // The heads are valid targets; the rest of the bodies, other
+                // obstacles and pickups block.
+                (*pos == head || !self.snake.contains(*pos))
+                    && (Some(*pos) == partner_head || self.partner_segment(*pos).is_none())
+                    && !self.obstacle_at(*pos)
+                    && Some(*pos) != self.shield
+                    && !self.feast_at(*pos)
+                    && !self.rush_food_at(*pos)
+            });
+            let (pos, dir) = match step {
+                Some(step) => step,
+                None => {
+                    i += 1;
+                    continue;
+                }
+            };
+            self.hazards[i] = Hazard { pos, dir };
+
+            if pos == self.food {
+                self.spawn_food();
+            }
+            if pos == head {
+                if self.shielded {
+                    self.shielded = false;
+                    self.hazards.remove(i);
+                    event = GameEvent::ShieldBreak;
+                    continue;
+                }
+                return self.die(DeathInfo::new(DeathCause::Hazard, pos));
+            }
+            if Some(pos) == partner_head {
+                event = self.partner_crash(DeathInfo::new(DeathCause::Hazard, pos));
+                if event == GameEvent::GameOver {
+                    return event;
+                }
+            }
+            i += 1;
+        }
+        event
+    }
+
+    pub(crate) fn random_direction(&mut self) -> Direction {
+        match self.rng.below(4) {
+            0 => Direction::Up,
+            1 => Direction::Down,
+            2 => Direction::Left,
+            _ => Direction::Right,
+        }
+    }
+}
+
+fn turn_left(dir: Direction) -> Direction {
+    match dir {
+        Direction::Up => Direction::Left,
+        Direction::Left => Direction::Down,
+        Direction::Down => Direction::Right,
+        Direction::Right => Direction::Up,
+    }
+}
+
+fn turn_right(dir: Direction) -> Direction {
+    turn_left(dir).opposite()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{Face, GameConfig};
+    use crate::mode::GameMode;
+
+    use super::*;
+
+    #[test]
+    fn test_hazard_count_follows_level() {
+        let config = GameConfig::with_mode(10, GameMode::Hazards);
+        let mut game = GameState::with_config(config, 11);
+        game.spawn_hazards();
+        assert!(game.hazards.is_empty());
+
+        game.score = 35; // level 4
+        game.spawn_hazards();
+        assert_eq!(game.hazards.len(), 3);
+        let head_face = game.snake.head().face;
+        assert!(game.hazards.iter().all(|h| h.pos.face != head_face));
+
+        let mut classic = GameState::new(10, 11);
+        classic.score = 35;
+        classic.spawn_hazards();
+        assert!(classic.hazards.is_empty());
+    }
+
+    #[test]
+    fn test_snake_dies_moving_into_hazard() {
+        let config = GameConfig::with_mode(10, GameMode::Hazards);
+        let mut game = GameState::with_config(config, 11);
+        let head = game.snake.head();
+        let ahead = Position { v: head.v + 1, ..head };
+        game.food = Position { face: Face::Back, u: 0, v: 0 };
+        game.hazards.push(Hazard { pos: ahead, dir: Direction::Down });
+        assert_eq!(game.update(), GameEvent::GameOver);
+        assert_eq!(game.death.map(|death| death.cause), Some(DeathCause::Hazard));
+    }
+}
@@ -0,0 +1,329 @@
+// Hand-made levels: a fixed layout of obstacles and portals on the cube plus
+// the rules of the run, in a format that round-trips through JSON so levels
+// can be shared as files. The editor in the web crate writes these; the
+// game plays them through `GameState::with_level`.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Face, GameConfig, GameState, Position, SpeedCurve};
+use crate::mode::GameMode;
+use crate::rng::fnv1a;
+use crate::topology;
+
+// Board sizes a level may use, the same the game offers.
+const GRID_SIZES: std::ops::RangeInclusive<i32> = 4..=20;
+
+// Stepping onto either end comes out of the other, heading the same way.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Portal {
+    pub a: Position,
+    pub b: Position,
+}
+
+impl Portal {
+    pub fn other_end(&self, pos: Position) -> Option<Position> {
+        if pos == self.a {
+            Some(self.b)
+        } else if pos == self.b {
+            Some(self.a)
+        } else {
+            None
+        }
+    }
+}
+
+// How food behaves on a level.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FoodTable {
+    // Every this many meals is a prize.
+    pub prize_every: u32,
+    // A meal brings out a shield 1 time in this many.
+    pub shield_chance: u32,
+    // Ticks before uneaten food moves elsewhere; `None` keeps it in place.
+    pub lifetime: Option<u64>,
+}
+
+impl Default for FoodTable {
+    fn default() -> Self {
+        let classic = GameConfig::new(10);
+        Self {
+            prize_every: classic.prize_every,
+            shield_chance: classic.shield_chance,
+            lifetime: None,
+        }
+    }
+}
+
+// What ends a run as a win. In JSON: `"endless"`, `{ "score": 50 }`,
+// `{ "survive": 60.0 }` (seconds of game time) or `{ "prizes": 3 }`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WinCondition {
+    #[default]
+    Endless,
+    Score(u32),
+    Survive(f64),
+    Prizes(u32),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LevelDefinition {
+    pub name: String,
+    pub grid_size: i32,
+    pub obstacles: Vec<Position>,
+    pub portals: Vec<Portal>,
+    pub food: FoodTable,
+    pub speed: SpeedCurve,
+    pub win: WinCondition,
+}
+
+impl Default for LevelDefinition {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            grid_size: 10,
+            obstacles: Vec::new(),
+            portals: Vec::new(),
+            food: FoodTable::default(),
+            speed: SpeedCurve::default(),
+            win: WinCondition::Endless,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LevelError {
+    BadBoard(i32),
+    OffBoard(Position),
+    // Two things on one cell, or something on the snake's starting cell.
+    Overlap(Position),
+    // A pace that never ticks, or ticks faster than its own floor.
+    BadSpeed,
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LevelError::BadBoard(size) => write!(f, "grid size {} is out of range", size),
+            LevelError::OffBoard(pos) => write!(f, "{:?} is off the board", pos),
+            LevelError::Overlap(pos) => write!(f, "{:?} is used twice", pos),
+            LevelError::BadSpeed => write!(f, "speed needs 0 < min_interval <= base_interval and ramp >= 0"),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+// Where every run starts, which a layout has to leave clear.
+pub fn start_cell(grid_size: i32) -> Position {
+    Position { face: Face::Front, u: grid_size / 2, v: grid_size / 2 }
+}
+
+impl LevelDefinition {
+    // Every cell the layout takes, obstacles then portal ends.
+    pub fn cells(&self) -> impl Iterator<Item = Position> + '_ {
+        self.obstacles
+            .iter()
+            .copied()
+            .chain(self.portals.iter().flat_map(|p| [p.a, p.b]))
+    }
+
+    // Checks a loaded level can be played: a board size the game offers, a
+    // pace that ticks, and every obstacle and portal end on its own cell of
+    // the board, clear of the start.
+    pub fn validate(&self) -> Result<(), LevelError> {
+        if !GRID_SIZES.contains(&self.grid_size) {
+            return Err(LevelError::BadBoard(self.grid_size));
+        }
+        if !self.speed.is_valid() {
+            return Err(LevelError::BadSpeed);
+        }
+        let on_board: HashSet<Position> = topology::cells(self.grid_size).collect();
+        let mut used = HashSet::from([start_cell(self.grid_size)]);
+        for cell in self.cells() {
+            if !on_board.contains(&cell) {
+                return Err(LevelError::OffBoard(cell));
+            }
+            if !used.insert(cell) {
+                return Err(LevelError::Overlap(cell));
+            }
+        }
+        Ok(())
+    }
+
+    // Identifies the level by what's played: layout and rules, not the name.
+    // Obstacles count in any order. Scores for a level are kept under this.
+    pub fn hash(&self) -> u64 {
+        let cell = |p: &Position| [p.face.bit() as i32, p.u, p.v];
+        let mut obstacles: Vec<[i32; 3]> = self.obstacles.iter().map(cell).collect();
+        obstacles.sort_unstable();
+        let mut bytes = Vec::new();
+        bytes.extend(self.grid_size.to_le_bytes());
+        for value in obstacles.into_iter().flatten() {
+            bytes.extend(value.to_le_bytes());
+        }
+        bytes.push(b'|');
+        for value in self.portals.iter().flat_map(|p| [cell(&p.a), cell(&p.b)]).flatten() {
+            bytes.extend(value.to_le_bytes());
+        }
+        bytes.extend(self.food.prize_every.to_le_bytes());
+        bytes.extend(self.food.shield_chance.to_le_bytes());
+        bytes.extend(self.food.lifetime.unwrap_or(u64::MAX).to_le_bytes());
+        for value in [self.speed.base_interval, self.speed.min_interval, self.speed.ramp] {
+            bytes.extend(value.to_bits().to_le_bytes());
+        }
+        let (tag, goal) = match self.win {
+            WinCondition::Endless => (0, 0),
+            WinCondition::Score(points) => (1, points as u64),
+            WinCondition::Survive(seconds) => (2, seconds.to_bits()),
+            WinCondition::Prizes(prizes) => (3, prizes as u64),
+        };
+        bytes.push(tag);
+        bytes.extend(goal.to_le_bytes());
+        fnv1a(bytes)
+    }
+
+    // Classic rules with the level's food, pace and goal. World events are
+    // left out so they can't scramble the layout.
+    pub fn config(&self) -> GameConfig {
+        let mut config = GameConfig::with_mode(self.grid_size, GameMode::Classic);
+        config.speed = self.speed;
+        config.prize_every = self.food.prize_every;
+        config.shield_chance = self.food.shield_chance;
+        config.food_lifetime = self.food.lifetime;
+        config.win = self.win;
+        config.world_events = false;
+        config
+    }
+}
+
+impl GameState {
+    // A run on `level`. Obstacles are walls, so they're drawn and collide
+    // like Tron trails.
+    pub fn with_level(level: &LevelDefinition, seed: u64) -> Self {
+        let mut game = GameState::with_config(level.config(), seed);
+        game.walls.extend(level.obstacles.iter().copied());
+        game.portals = level.portals.clone();
+        // The first food was placed before the layout existed.
+        if !game.cell_is_free_of_layout(game.food) {
+            game.spawn_food();
+        }
+        game
+    }
+
+    fn cell_is_free_of_layout(&self, pos: Position) -> bool {
+        !self.walls.contains(&pos) && self.portal_exit(pos).is_none()
+    }
+
+    // Where stepping onto `pos` comes out, if it's a portal.
+    pub fn portal_exit(&self, pos: Position) -> Option<Position> {
+        self.portals.iter().find_map(|portal| portal.other_end(pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{DeathCause, Direction};
+
+    use super::*;
+
+    fn level() -> LevelDefinition {
+        LevelDefinition {
+            name: "Test".to_string(),
+            obstacles: vec![Position { face: Face::Front, u: 5, v: 7 }],
+            portals: vec![Portal {
+                a: Position { face: Face::Front, u: 5, v: 6 },
+                b: Position { face: Face::Back, u: 2, v: 2 },
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_level_round_trips_through_json() {
+        let json = serde_json::to_string(&level()).unwrap();
+        let loaded: LevelDefinition = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, level());
+        assert!(loaded.validate().is_ok());
+
+        // Everything but the layout can be left out.
+        let sparse: LevelDefinition =
+            serde_json::from_str(r#"{ "obstacles": [{ "face": "top", "u": 1, "v": 2 }], "win": { "score": 30 } }"#).unwrap();
+        assert_eq!(sparse.grid_size, 10);
+        assert_eq!(sparse.win, WinCondition::Score(30));
+    }
+
+    #[test]
+    fn test_hash_follows_the_layout() {
+        let mut renamed = level();
+        renamed.name = "Other".to_string();
+        renamed.obstacles.insert(0, Position { face: Face::Top, u: 1, v: 1 });
+        let mut reordered = level();
+        reordered.obstacles.push(Position { face: Face::Top, u: 1, v: 1 });
+        assert_eq!(renamed.hash(), reordered.hash());
+
+        reordered.win = WinCondition::Score(10);
+        assert_ne!(renamed.hash(), reordered.hash());
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_layouts() {
+        let mut off = level();
+        off.obstacles.push(Position { face: Face::Top, u: 10, v: 0 });
+        assert!(matches!(off.validate(), Err(LevelError::OffBoard(_))));
+
+        let mut start = level();
+        start.obstacles.push(start_cell(10));
+        assert_eq!(start.validate(), Err(LevelError::Overlap(start_cell(10))));
+    }
+
+    #[test]
+    fn test_validate_rejects_speeds_that_never_tick() {
+        let curves = [(0.1, 0.0, 0.0), (0.05, 0.1, 0.0), (0.1, 0.05, -1.0), (0.1, f64::NAN, 0.0)];
+        for (base_interval, min_interval, ramp) in curves {
+            let mut stuck = level();
+            stuck.speed = SpeedCurve { base_interval, min_interval, ramp };
+            assert_eq!(stuck.validate(), Err(LevelError::BadSpeed));
+        }
+        assert_eq!(level().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_portals_carry_the_snake_through() {
+        let mut game = GameState::with_level(&level(), 1);
+        game.food = Position { face: Face::Left, u: 0, v: 0 };
+        // The start is (5, 5) heading up, so the next cell is portal end `a`.
+        game.update();
+        assert_eq!(game.snake.head(), Position { face: Face::Back, u: 2, v: 2 });
+        assert_eq!(game.snake.direction, Direction::Up);
+        game.update();
+        assert_eq!(game.snake.head(), Position { face: Face::Back, u: 2, v: 3 });
+    }
+
+    #[test]
+    fn test_obstacles_are_walls() {
+        let mut level = level();
+        level.portals.clear();
+        let mut game = GameState::with_level(&level, 1);
+        game.food = Position { face: Face::Left, u: 0, v: 0 };
+        game.update();
+        game.update();
+        assert!(game.game_over);
+        assert_eq!(game.death.map(|death| death.cause), Some(DeathCause::Wall));
+    }
+
+    #[test]
+    fn test_survive_win() {
+        let mut game = GameState::with_level(&LevelDefinition { win: WinCondition::Survive(0.3), ..Default::default() }, 1);
+        game.food = Position { face: Face::Left, u: 0, v: 0 };
+        game.update();
+        assert!(!game.finished);
+        game.update();
+        assert!(game.finished && game.game_over);
+    }
+}
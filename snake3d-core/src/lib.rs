@@ -0,0 +1,52 @@
+// Pure game logic for 3D Snake.
+// No wasm/web-sys dependencies so it can be unit-tested natively and reused
+// outside the browser (bots, replay verification, tooling).
+
+pub mod boss;
+pub mod campaign;
+pub mod clock;
+pub mod coop;
+pub mod director;
+pub mod dungeon;
+pub mod feast;
+pub mod fuzz;
+pub mod game;
+pub mod hazard;
+pub mod level;
+pub mod mode;
+pub mod path;
+pub mod replay;
+pub mod rewind;
+pub mod rhythm;
+pub mod rng;
+pub mod sim;
+pub mod storage;
+pub mod storm;
+pub mod topology;
+pub mod tron;
+pub mod turret;
+pub mod vote;
+pub mod world;
+
+pub use boss::{Boss, BossPhase};
+pub use campaign::{CampaignLevel, Objective};
+pub use clock::TickClock;
+pub use director::Director;
+pub use dungeon::{generate, is_solvable, LayoutParams};
+pub use feast::Feast;
+pub use game::{DeathCause, DeathInfo, Direction, Face, GameConfig, GameEvent, GameState, Position, Snake, SpeedCurve};
+pub use hazard::Hazard;
+pub use level::{FoodTable, LevelDefinition, LevelError, Portal, WinCondition};
+pub use mode::GameMode;
+pub use path::Route;
+pub use replay::{verify_replay, Replay, VerifiedResult};
+pub use rng::{seed_from_str, Rng};
+pub use sim::{SimResult, Simulator};
+pub use storage::{HighScoreStore, MemoryStore};
+pub use topology::TopologyError;
+pub use tron::Rival;
+pub use turret::{Projectile, Turret};
+pub use vote::VoteTally;
+pub use world::WorldEvent;
+#[cfg(not(target_arch = "wasm32"))]
+pub use storage::FileStore;
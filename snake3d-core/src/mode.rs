@@ -0,0 +1,172 @@
+// Game modes. Each mode can tweak rules on top of the classic game; the
+// name is what hosts pass in from JavaScript.
+
+use crate::coop::COOP_LIVES;
+use crate::game::SpeedCurve;
+use crate::rhythm::BEAT_SECONDS;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GameMode {
+    #[default]
+    Classic,
+    // Classic plus roaming hazards and turrets that multiply as the level rises.
+    Hazards,
+    // The trail is permanent and an AI rival lays its own.
+    Tron,
+    // Race to a target score against the clock.
+    Speedrun,
+    // Solo battle royale: the storm closes in ring by ring.
+    Survival,
+    // Moves on the beat of the music; on-beat turns pay out with the food.
+    Rhythm,
+    // A new generated layout of walls and portals every level.
+    Dungeon,
+    // Classic against a string of bosses (see `boss.rs`).
+    Boss,
+    // Two snakes, one score, shared lives (see `coop.rs`).
+    Coop,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 9] = [
+        GameMode::Classic,
+        GameMode::Hazards,
+        GameMode::Tron,
+        GameMode::Speedrun,
+        GameMode::Survival,
+        GameMode::Rhythm,
+        GameMode::Dungeon,
+        GameMode::Boss,
+        GameMode::Coop,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GameMode::Classic => "classic",
+            GameMode::Hazards => "hazards",
+            GameMode::Tron => "tron",
+            GameMode::Speedrun => "speedrun",
+            GameMode::Survival => "survival",
+            GameMode::Rhythm => "rhythm",
+            GameMode::Dungeon => "dungeon",
+            GameMode::Boss => "boss",
+            GameMode::Coop => "coop",
+        }
+    }
+
+    // Default pacing for the mode; hosts can still override it.
+    pub fn speed(self) -> SpeedCurve {
+        match self {
+            GameMode::Classic
+            | GameMode::Hazards
+            | GameMode::Tron
+            | GameMode::Speedrun
+            | GameMode::Survival
+            | GameMode::Dungeon
+            | GameMode::Boss
+            | GameMode::Coop => SpeedCurve::default(),
+            // One move per beat, and the tempo never changes.
+            GameMode::Rhythm => SpeedCurve {
+                base_interval: BEAT_SECONDS,
+                min_interval: BEAT_SECONDS,
+                ramp: 0.0,
+            },
+        }
+    }
+
+    pub fn hazards(self) -> bool {
+        self == GameMode::Hazards
+    }
+
+    pub fn turrets(self) -> bool {
+        self == GameMode::Hazards
+    }
+
+    // Score that ends the run as a win.
+    pub fn target_score(self) -> Option<u32> {
+        match self {
+            GameMode::Speedrun => Some(100),
+            _ => None,
+        }
+    }
+
+    // Vacated cells turn into walls.
+    pub fn permanent_trail(self) -> bool {
+        self == GameMode::Tron
+    }
+
+    // Ticks before uneaten food relocates. Classic lets it sit forever.
+    pub fn food_lifetime(self) -> Option<u64> {
+        match self {
+            GameMode::Classic
+            | GameMode::Tron
+            | GameMode::Speedrun
+            | GameMode::Survival
+            | GameMode::Rhythm
+            | GameMode::Dungeon
+            | GameMode::Boss
+            | GameMode::Coop => None,
+            GameMode::Hazards => Some(80),
+        }
+    }
+
+    // Outer rings close over time (see `storm.rs`).
+    pub fn storm(self) -> bool {
+        self == GameMode::Survival
+    }
+
+    // Walls and portals regenerate every level (see `dungeon.rs`).
+    pub fn dungeon(self) -> bool {
+        self == GameMode::Dungeon
+    }
+
+    // Bosses to beat (see `boss.rs`).
+    pub fn boss(self) -> bool {
+        self == GameMode::Boss
+    }
+
+    // A second snake shares the run (see `coop.rs`).
+    pub fn coop(self) -> bool {
+        self == GameMode::Coop
+    }
+
+    // Crashes a run survives, plus one.
+    pub fn lives(self) -> u32 {
+        match self {
+            GameMode::Coop => COOP_LIVES,
+            _ => 1,
+        }
+    }
+
+    // Bonus for turning on the beat (see `rhythm.rs`).
+    pub fn rhythm(self) -> bool {
+        self == GameMode::Rhythm
+    }
+
+    // Rewinds allowed per run after losing (see `rewind.rs`). Only the
+    // casual classic game gets one.
+    pub fn rewinds(self) -> u32 {
+        match self {
+            GameMode::Classic => 1,
+            _ => 0,
+        }
+    }
+
+    // Modes whose scores are raced against others, where assists that point
+    // the way (the food hint) stay off. Casual classic and co-op allow them.
+    pub fn competitive(self) -> bool {
+        !matches!(self, GameMode::Classic | GameMode::Coop)
+    }
+
+    // Random food rushes, quakes and blackouts (see `world.rs`). Left out
+    // where they'd wreck a wall layout or a fair clock.
+    pub fn world_events(self) -> bool {
+        matches!(self, GameMode::Classic | GameMode::Hazards)
+    }
+
+    pub fn from_name(name: &str) -> Option<GameMode> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.name().eq_ignore_ascii_case(name))
+    }
+}
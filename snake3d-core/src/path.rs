@@ -0,0 +1,170 @@
+// Shortest routes over the cube's surface. A step is one of the snake's own
+// moves - up, down, left or right, over face edges and through portals - so
+// a route is one the snake could follow. Every step costs the same, which
+// makes a breadth-first search the shortest-path search; it's cheap at these
+// board sizes (6 faces of at most 20x20 cells). Used for the hint arrow and
+// level solvability, and open to the rival, bots and any other steering.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::game::{Direction, GameState, Position};
+
+const DIRECTIONS: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Route {
+    // Cells passed through, ending on the goal; the start isn't included.
+    pub cells: Vec<Position>,
+    // Way to leave the start, as seen from the start's face.
+    pub first: Direction,
+}
+
+// Where one move in `dir` from `pos` ends up, portals included.
+fn step(board: &GameState, pos: Position, dir: Direction) -> Position {
+    let (next, _) = board.calculate_next_position(pos, dir);
+    board.portal_exit(next).unwrap_or(next)
+}
+
+// The shortest route from `from` to `to` through cells `blocked` lets by.
+// The goal itself is never blocked. `None` when `from` is `to` or nothing
+// gets through.
+pub fn search(board: &GameState, from: Position, to: Position, blocked: impl Fn(Position) -> bool) -> Option<Route> {
+    // Each cell reached, with the cell and move it was reached by.
+    let mut came_from: HashMap<Position, (Position, Direction)> = HashMap::new();
+    let mut queue = VecDeque::from([from]);
+    while let Some(pos) = queue.pop_front() {
+        for dir in DIRECTIONS {
+            let next = step(board, pos, dir);
+            if next == from || came_from.contains_key(&next) || (next != to && blocked(next)) {
+                continue;
+            }
+            came_from.insert(next, (pos, dir));
+            if next == to {
+                return Some(unwind(&came_from, from, to));
+            }
+            queue.push_back(next);
+        }
+    }
+    None
+}
+
+fn unwind(came_from: &HashMap<Position, (Position, Direction)>, from: Position, to: Position) -> Route {
+    let mut cells = vec![to];
+    let mut at = to;
+    loop {
+        let (previous, dir) = came_from[&at];
+        if previous == from {
+            cells.reverse();
+            return Route { cells, first: dir };
+        }
+        cells.push(previous);
+        at = previous;
+    }
+}
+
+// Every cell reachable from `from`, itself included.
+pub fn reachable(board: &GameState, from: Position, blocked: impl Fn(Position) -> bool) -> HashSet<Position> {
+    let mut seen = HashSet::from([from]);
+    let mut queue = VecDeque::from([from]);
+    while let Some(pos) = queue.pop_front() {
+        for dir in DIRECTIONS {
+            let next = step(board, pos, dir);
+            if !blocked(next) && seen.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    seen
+}
+
+impl GameState {
+    // Cells a route on this board stays off: anything the snake would die
+    // on, and both snakes' bodies except the tail, which moves out of the way.
+    pub fn path_blocked(&self, pos: Position) -> bool {
+        let tail = self.snake.body.back().copied();
+        (self.snake.contains(pos) && Some(pos) != tail)
+            || self.partner.as_ref().is_some_and(|p| p.contains(pos))
+            || self.obstacle_at(pos)
+    }
+
+    pub fn route(&self, from: Position, to: Position) -> Option<Route> {
+        search(self, from, to, |pos| self.path_blocked(pos))
+    }
+
+    // Moves from `from` to `to` by the shortest clear route.
+    pub fn distance(&self, from: Position, to: Position) -> Option<usize> {
+        if from == to {
+            return Some(0);
+        }
+        self.route(from, to).map(|route| route.cells.len())
+    }
+
+    // The way to go from `from` to get to `to` soonest.
+    pub fn next_step(&self, from: Position, to: Position) -> Option<Direction> {
+        self.route(from, to).map(|route| route.first)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Face;
+    use crate::level::Portal;
+
+    fn at(face: Face, u: i32, v: i32) -> Position {
+        Position { face, u, v }
+    }
+
+    // Steps between two cells of an empty board.
+    fn open_distance(game: &GameState, from: Position, to: Position) -> Option<usize> {
+        search(game, from, to, |_| false).map(|route| route.cells.len())
+    }
+
+    #[test]
+    fn test_crosses_face_edges() {
+        let game = GameState::new(10, 1);
+        let route = search(&game, at(Face::Front, 5, 9), at(Face::Top, 5, 1), |_| false).unwrap();
+        assert_eq!(route.first, Direction::Up);
+        assert_eq!(route.cells, vec![at(Face::Top, 5, 0), at(Face::Top, 5, 1)]);
+    }
+
+    #[test]
+    fn test_goes_around_what_is_blocked() {
+        let game = GameState::new(10, 1);
+        let (from, to) = (at(Face::Front, 2, 5), at(Face::Front, 4, 5));
+        assert_eq!(open_distance(&game, from, to), Some(2));
+        let wall: HashSet<Position> = (3..=7).map(|v| at(Face::Front, 3, v)).collect();
+        let route = search(&game, from, to, |pos| wall.contains(&pos)).unwrap();
+        // Three down past the end of the wall, two across and three back up.
+        assert_eq!(route.cells.len(), 8);
+        assert!(route.cells.iter().all(|pos| !wall.contains(pos)));
+    }
+
+    #[test]
+    fn test_takes_portals() {
+        let mut game = GameState::new(10, 1);
+        let (from, to) = (at(Face::Front, 5, 5), at(Face::Back, 5, 5));
+        let around = open_distance(&game, from, to).unwrap();
+        game.portals.push(Portal { a: at(Face::Front, 6, 5), b: at(Face::Back, 4, 5) });
+        assert_eq!(open_distance(&game, from, to), Some(2));
+        assert!(around > 2);
+    }
+
+    #[test]
+    fn test_nothing_gets_through_a_closed_ring() {
+        let game = GameState::new(10, 1);
+        let from = at(Face::Front, 5, 5);
+        let ring = [at(Face::Front, 4, 5), at(Face::Front, 6, 5), at(Face::Front, 5, 4), at(Face::Front, 5, 6)];
+        assert_eq!(search(&game, from, at(Face::Back, 0, 0), |pos| ring.contains(&pos)), None);
+        assert_eq!(reachable(&game, from, |pos| ring.contains(&pos)).len(), 1);
+    }
+
+    #[test]
+    fn test_next_step_heads_for_the_food() {
+        let game = GameState::new(10, 1);
+        let head = game.snake.head();
+        let dir = game.next_step(head, game.food).unwrap();
+        let next = step(&game, head, dir);
+        assert_eq!(game.distance(next, game.food), Some(game.distance(head, game.food).unwrap() - 1));
+    }
+}
@@ -0,0 +1,331 @@
+// Replays: the seed, board and mode of a run plus every turn and the tick it
+// was made on. Since the game is deterministic per seed that's enough to
+// play the run back through the simulator.
+//
+// Stored in a compact binary form so thousands of ghosts stay cheap:
+//
+//   "S3R" version seed grid mode count deltas... directions...
+//
+// where seed, grid, count and each tick delta are LEB128 varints, mode is
+// an index into `GameMode::ALL` and directions are packed four to a byte,
+// two bits each. Serde sees a replay as that byte string.
+
+use std::fmt;
+
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::game::{Direction, GameConfig, GameEvent, GameState};
+use crate::mode::GameMode;
+use crate::rhythm::MAX_BEAT_BONUS;
+use crate::sim::{SimResult, Simulator};
+
+const MAGIC: &[u8; 3] = b"S3R";
+const VERSION: u8 = 1;
+
+// Bounds on what `verify_replay` takes on, so a bogus replay can't keep it
+// busy: a run's turns, its length in ticks (hours at top speed) and the
+// board sizes the game offers.
+pub const MAX_INPUTS: usize = 50_000;
+pub const MAX_TICKS: u64 = 250_000;
+const GRID_SIZES: std::ops::RangeInclusive<i32> = 4..=20;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerifiedResult {
+    // The replay ends the run on the claimed score.
+    Valid { score: u32, ticks: u64 },
+    ScoreMismatch { claimed: u32, actual: u32 },
+    // More turns or ticks than the limits allow.
+    TooLong,
+    // A board size the game doesn't offer.
+    BadBoard,
+    // Turns out of tick order.
+    Unordered,
+    // Still alive once the tick budget ran out.
+    Unfinished,
+}
+
+impl VerifiedResult {
+    pub fn is_valid(self) -> bool {
+        matches!(self, VerifiedResult::Valid { .. })
+    }
+}
+
+// Replays a finished run and checks it ends on `claimed_score`. The game
+// checks its own runs with this before reporting them, and a score server
+// can run the same check on submissions.
+pub fn verify_replay(replay: &Replay, claimed_score: u32) -> VerifiedResult {
+    if replay.inputs.len() > MAX_INPUTS || replay.inputs.last().is_some_and(|(tick, _)| *tick >= MAX_TICKS) {
+        return VerifiedResult::TooLong;
+    }
+    if !GRID_SIZES.contains(&replay.grid_size) {
+        return VerifiedResult::BadBoard;
+    }
+    if replay.inputs.windows(2).any(|pair| pair[0].0 > pair[1].0) {
+        return VerifiedResult::Unordered;
+    }
+    let result = replay.simulate(MAX_TICKS);
+    if !result.game_over {
+        return VerifiedResult::Unfinished;
+    }
+    // Beat bonuses hang on timing a replay doesn't carry, so a rhythm run may
+    // claim up to the most it could have earned on top.
+    let slack = if replay.mode.rhythm() {
+        let meals = result
+            .events
+            .iter()
+            .filter(|(_, e)| matches!(e, GameEvent::Eat | GameEvent::EatPrize | GameEvent::AllFacesBonus))
+            .count() as u32;
+        meals * MAX_BEAT_BONUS
+    } else {
+        0
+    };
+    if claimed_score < result.score || claimed_score > result.score + slack {
+        return VerifiedResult::ScoreMismatch { claimed: claimed_score, actual: result.score };
+    }
+    VerifiedResult::Valid { score: claimed_score, ticks: result.ticks }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Replay {
+    pub seed: u64,
+    pub grid_size: i32,
+    pub mode: GameMode,
+    // Turns in tick order; each is applied before that tick runs.
+    pub inputs: Vec<(u64, Direction)>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, grid_size: i32, mode: GameMode) -> Self {
+        Self { seed, grid_size, mode, inputs: Vec::new() }
+    }
+
+    // Notes a turn made before tick `tick` (the game's `ticks` at the time).
+    pub fn record(&mut self, tick: u64, dir: Direction) {
+        self.inputs.push((tick, dir));
+    }
+
+    // Plays the run back for at most `max_ticks`.
+    pub fn simulate(&self, max_ticks: u64) -> SimResult {
+        let config = GameConfig::with_mode(self.grid_size, self.mode);
+        let mut sim = Simulator::from_state(GameState::with_config(config, self.seed));
+        let mut next = 0;
+        let inputs = &self.inputs;
+        sim.run(
+            |game: &GameState| {
+                // Several turns on one tick: the game kept the last one that
+                // wasn't a reversal.
+                let mut dir = None;
+                while next < inputs.len() && inputs[next].0 <= game.ticks {
+                    if inputs[next].1 != game.snake.direction.opposite() {
+                        dir = Some(inputs[next].1);
+                    }
+                    next += 1;
+                }
+                dir
+            },
+            max_ticks,
+        )
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(16 + self.inputs.len() * 2);
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        write_varint(&mut out, self.seed);
+        write_varint(&mut out, self.grid_size.max(0) as u64);
+        out.push(GameMode::ALL.iter().position(|m| *m == self.mode).unwrap_or(0) as u8);
+        write_varint(&mut out, self.inputs.len() as u64);
+        let mut last = 0;
+        for (tick, _) in &self.inputs {
+            write_varint(&mut out, tick - last.min(*tick));
+            last = *tick;
+        }
+        for chunk in self.inputs.chunks(4) {
+            let byte = chunk
+                .iter()
+                .enumerate()
+                .fold(0u8, |byte, (i, (_, dir))| byte | direction_bits(*dir) << (i * 2));
+            out.push(byte);
+        }
+        out
+    }
+
+    // None for anything that isn't a replay this version can read.
+    pub fn decode(bytes: &[u8]) -> Option<Replay> {
+        let rest = bytes.strip_prefix(MAGIC)?;
+        let (&version, mut rest) = rest.split_first()?;
+        if version != VERSION {
+            return None;
+        }
+        let seed = read_varint(&mut rest)?;
+        let grid_size = i32::try_from(read_varint(&mut rest)?).ok()?;
+        let (&mode, mut rest) = rest.split_first()?;
+        let mode = *GameMode::ALL.get(mode as usize)?;
+        let count = usize::try_from(read_varint(&mut rest)?).ok()?;
+        // Every input takes at least a byte for its delta.
+        if count > rest.len() {
+            return None;
+        }
+        let mut ticks = Vec::with_capacity(count);
+        let mut tick = 0u64;
+        for _ in 0..count {
+            tick = tick.checked_add(read_varint(&mut rest)?)?;
+            ticks.push(tick);
+        }
+        if rest.len() != count.div_ceil(4) {
+            return None;
+        }
+        let inputs = ticks
+            .into_iter()
+            .enumerate()
+            .map(|(i, tick)| (tick, bits_direction(rest[i / 4] >> ((i % 4) * 2))))
+            .collect();
+        Some(Replay { seed, grid_size, mode, inputs })
+    }
+}
+
+fn direction_bits(dir: Direction) -> u8 {
+    match dir {
+        Direction::Up => 0,
+        Direction::Down => 1,
+        Direction::Left => 2,
+        Direction::Right => 3,
+    }
+}
+
+fn bits_direction(bits: u8) -> Direction {
+    match bits & 0b11 {
+        0 => Direction::Up,
+        1 => Direction::Down,
+        2 => Direction::Left,
+        _ => Direction::Right,
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = bytes.split_first()?;
+        *bytes = rest;
+        value |= u64::from(byte & 0x7f).checked_shl(shift)?;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+impl Serialize for Replay {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.encode())
+    }
+}
+
+impl<'de> Deserialize<'de> for Replay {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_bytes(ReplayVisitor)
+    }
+}
+
+struct ReplayVisitor;
+
+impl<'de> Visitor<'de> for ReplayVisitor {
+    type Value = Replay;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an encoded replay")
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Replay, E> {
+        Replay::decode(bytes).ok_or_else(|| E::custom("invalid replay"))
+    }
+
+    // Formats without a byte type (JSON) hand over a sequence of numbers.
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Replay, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element::<u8>()? {
+            bytes.push(byte);
+        }
+        self.visit_bytes(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zigzag() -> Replay {
+        let mut replay = Replay::new(42, 10, GameMode::Classic);
+        let turns = [Direction::Right, Direction::Up, Direction::Left, Direction::Up, Direction::Right];
+        for (i, dir) in turns.into_iter().cycle().take(100).enumerate() {
+            replay.record(i as u64 * 3, dir);
+        }
+        replay
+    }
+
+    #[test]
+    fn test_round_trip_is_compact() {
+        let replay = zigzag();
+        let bytes = replay.encode();
+        // Magic and version, a byte each for seed, grid, mode and count, then
+        // a byte per delta and a quarter byte per direction.
+        assert_eq!(bytes.len(), 4 + 4 + 100 + 25);
+        assert_eq!(Replay::decode(&bytes), Some(replay));
+        assert_eq!(Replay::decode(&bytes[..bytes.len() - 1]), None);
+        assert_eq!(Replay::decode(b"nope"), None);
+    }
+
+    // Turns a tight square into its own trail.
+    fn crash() -> Replay {
+        let mut replay = Replay::new(3, 10, GameMode::Tron);
+        for (tick, dir) in [(2, Direction::Right), (3, Direction::Down), (4, Direction::Left)] {
+            replay.record(tick, dir);
+        }
+        replay
+    }
+
+    #[test]
+    fn test_verify_checks_score_and_limits() {
+        let replay = crash();
+        let actual = replay.simulate(MAX_TICKS).score;
+        assert!(verify_replay(&replay, actual).is_valid());
+        assert_eq!(
+            verify_replay(&replay, actual + 1),
+            VerifiedResult::ScoreMismatch { claimed: actual + 1, actual }
+        );
+
+        let mut unordered = crash();
+        unordered.inputs.swap(0, 2);
+        assert_eq!(verify_replay(&unordered, actual), VerifiedResult::Unordered);
+        let mut long = crash();
+        long.record(MAX_TICKS, Direction::Up);
+        assert_eq!(verify_replay(&long, actual), VerifiedResult::TooLong);
+        let tiny = Replay { grid_size: 1, ..crash() };
+        assert_eq!(verify_replay(&tiny, actual), VerifiedResult::BadBoard);
+    }
+
+    #[test]
+    fn test_replay_reproduces_the_run() {
+        let mut replay = Replay::new(7, 10, GameMode::Classic);
+        let mut sim = Simulator::new(10, 7);
+        let original = sim.run(
+            |game: &GameState| {
+                let dir = if game.snake.head().v % 3 == 0 { Direction::Right } else { Direction::Up };
+                replay.record(game.ticks, dir);
+                Some(dir)
+            },
+            500,
+        );
+        let decoded = Replay::decode(&replay.encode()).unwrap();
+        assert_eq!(decoded.simulate(500), original);
+    }
+}
@@ -0,0 +1,105 @@
+// Rewind: the game keeps the states of its last `HISTORY_TICKS` ticks, and a
+// run that just ended can be taken back a few seconds and carried on from
+// there, as often as the mode allows (once, in classic).
+
+use std::collections::VecDeque;
+
+use crate::game::GameState;
+
+pub const HISTORY_TICKS: usize = 120;
+const REWIND_SECONDS: f64 = 3.0;
+
+impl GameState {
+    // Notes the state before a tick. Called from `update`.
+    pub(crate) fn snapshot(&mut self) {
+        if self.config.rewinds == 0 {
+            return;
+        }
+        // The copy goes in without a history of its own.
+        let mut history = std::mem::take(&mut self.history);
+        if history.len() == HISTORY_TICKS {
+            history.pop_front();
+        }
+        history.push_back(self.clone());
+        self.history = history;
+    }
+
+    pub fn can_rewind(&self) -> bool {
+        self.game_over && !self.finished && self.rewinds_left > 0 && !self.history.is_empty()
+    }
+
+    // Goes back `REWIND_SECONDS` (or as far as the history reaches) from a
+    // lost run. Returns false when there's no rewind to use.
+    pub fn rewind(&mut self) -> bool {
+        if !self.can_rewind() {
+            return false;
+        }
+        let ticks = ((REWIND_SECONDS / self.move_interval()).round() as usize).clamp(1, self.history.len());
+        let mut history: VecDeque<GameState> = std::mem::take(&mut self.history);
+        history.truncate(history.len() - ticks + 1);
+        let earlier = match history.pop_back() {
+            Some(state) => state,
+            None => return false,
+        };
+        let rewinds_left = self.rewinds_left - 1;
+        let high_score = self.high_score;
+        *self = earlier;
+        self.history = history;
+        self.rewinds_left = rewinds_left;
+        self.high_score = self.high_score.max(high_score);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{Direction, Face, GameConfig, Position};
+    use crate::mode::GameMode;
+
+    use super::*;
+
+    // Right, down and left: back into the snake's own neck.
+    fn crash(game: &mut GameState) {
+        for dir in [Direction::Right, Direction::Down, Direction::Left] {
+            game.steer(dir);
+            game.update();
+        }
+    }
+
+    // A five long snake heading up, crashed.
+    fn crashed(mode: GameMode) -> GameState {
+        let mut game = GameState::with_config(GameConfig::with_mode(10, mode), 4);
+        let at = |v| Position { face: Face::Front, u: 5, v };
+        game.snake.set_body((1..=5).rev().map(at));
+        game.snake.direction = Direction::Up;
+        game.snake.next_direction = Direction::Up;
+        game.food = Position { face: Face::Back, u: 0, v: 0 };
+        game.shield = None;
+        crash(&mut game);
+        game
+    }
+
+    #[test]
+    fn test_rewind_once_per_run() {
+        let mut game = crashed(GameMode::Classic);
+        assert!(game.game_over);
+        let ticks = game.ticks;
+        assert!(game.rewind());
+        assert!(!game.game_over);
+        assert!(game.ticks < ticks);
+        assert_eq!(game.rewinds_left, 0);
+
+        crash(&mut game);
+        assert!(game.game_over);
+        assert!(!game.can_rewind());
+        assert!(!game.rewind());
+    }
+
+    #[test]
+    fn test_no_rewind_outside_classic() {
+        let mut game = crashed(GameMode::Speedrun);
+        assert!(game.game_over);
+        assert!(game.history.is_empty());
+        assert!(!game.rewind());
+    }
+}
@@ -0,0 +1,73 @@
+// Rhythm mode: the snake moves once per beat of the music, and turns taken
+// close to a beat are counted and paid out as bonus points with the next food.
+// The caller keeps the beat clock and says where in the beat each input
+// landed.
+
+use crate::game::{Direction, GameState};
+
+// Eighth notes at 120 bpm.
+pub const BEAT_SECONDS: f64 = 0.25;
+// How close to a beat, as a fraction of one, a turn still counts.
+const ON_BEAT_WINDOW: f64 = 0.15;
+pub(crate) const MAX_BEAT_BONUS: u32 = 3;
+
+// `phase` is how far into the current beat, from 0 right on it to 1 at the next.
+pub fn on_beat(phase: f64) -> bool {
+    phase <= ON_BEAT_WINDOW || phase >= 1.0 - ON_BEAT_WINDOW
+}
+
+impl GameState {
+    // Steers like `steer`, counting the turn if it's on the beat. Returns
+    // whether it counted.
+    pub fn steer_on_beat(&mut self, dir: Direction, phase: f64) -> bool {
+        let turn = dir != self.snake.direction && dir != self.snake.direction.opposite();
+        let fresh = dir != self.snake.next_direction;
+        self.steer(dir);
+        if self.config.rhythm && turn && fresh && on_beat(phase) {
+            self.beat_turns += 1;
+            return true;
+        }
+        false
+    }
+
+    pub(crate) fn take_beat_bonus(&mut self) -> u32 {
+        std::mem::take(&mut self.beat_turns).min(MAX_BEAT_BONUS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{GameConfig, Position};
+    use crate::mode::GameMode;
+
+    use super::*;
+
+    fn rhythm_game() -> GameState {
+        GameState::with_config(GameConfig::with_mode(10, GameMode::Rhythm), 5)
+    }
+
+    #[test]
+    fn test_only_on_beat_turns_count() {
+        let mut game = rhythm_game();
+        assert!(!game.steer_on_beat(Direction::Left, 0.5));
+        // Already queued, and straight ahead, don't count either.
+        assert!(!game.steer_on_beat(Direction::Left, 0.0));
+        assert!(!game.steer_on_beat(Direction::Up, 0.0));
+        assert!(game.steer_on_beat(Direction::Right, 0.9));
+        assert_eq!(game.beat_turns, 1);
+
+        let mut classic = GameState::new(10, 5);
+        assert!(!classic.steer_on_beat(Direction::Left, 0.0));
+    }
+
+    #[test]
+    fn test_beat_turns_pay_out_with_food() {
+        let mut game = rhythm_game();
+        game.beat_turns = 5;
+        let head = game.snake.head();
+        game.food = Position { v: head.v + 1, ..head };
+        game.update();
+        assert_eq!(game.score, 1 + MAX_BEAT_BONUS);
+        assert_eq!(game.beat_turns, 0);
+    }
+}
@@ -0,0 +1,59 @@
+// Small deterministic PRNG (SplitMix64).
+// The game needs reproducible randomness for seeds and replays, and the core
+// crate must not depend on platform entropy sources like `getrandom`.
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform value in 0..n. Returns 0 when n is 0.
+    pub fn below(&mut self, n: u32) -> u32 {
+        if n == 0 {
+            return 0;
+        }
+        (((self.next_u64() >> 32) * n as u64) >> 32) as u32
+    }
+}
+
+// Seed for a text such as a shared link's `?seed=abc`. Numbers are taken as
+// they are; anything else is hashed (FNV-1a), so the same text always gives
+// the same board.
+pub fn seed_from_str(text: &str) -> u64 {
+    if let Ok(seed) = text.parse() {
+        return seed;
+    }
+    fnv1a(text.bytes())
+}
+
+// 64-bit FNV-1a: stable across builds and platforms, unlike std's hasher.
+pub(crate) fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    bytes.into_iter().fold(0xCBF2_9CE4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01B3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_from_str() {
+        assert_eq!(seed_from_str("42"), 42);
+        assert_eq!(seed_from_str("abc"), seed_from_str("abc"));
+        assert_ne!(seed_from_str("abc"), seed_from_str("abd"));
+    }
+}
@@ -0,0 +1,75 @@
+// Persistence for the best score. The game only needs load-once/save-on-record,
+// so backends stay tiny: localStorage in the browser build, a file natively.
+
+pub trait HighScoreStore {
+    // Missing or unreadable data counts as no high score yet.
+    fn load(&self) -> u32;
+    fn save(&mut self, high_score: u32);
+}
+
+// Keeps the score for the lifetime of the value. Useful for tests and bots.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryStore(pub u32);
+
+impl HighScoreStore for MemoryStore {
+    fn load(&self) -> u32 {
+        self.0
+    }
+
+    fn save(&mut self, high_score: u32) {
+        self.0 = high_score;
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use file::FileStore;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod file {
+    use std::path::PathBuf;
+
+    use super::HighScoreStore;
+
+    // Stores the score as plain text in a single file.
+    #[derive(Clone, Debug)]
+    pub struct FileStore {
+        path: PathBuf,
+    }
+
+    impl FileStore {
+        pub fn new(path: impl Into<PathBuf>) -> Self {
+            Self { path: path.into() }
+        }
+    }
+
+    impl HighScoreStore for FileStore {
+        fn load(&self) -> u32 {
+            std::fs::read_to_string(&self.path)
+                .ok()
+                .and_then(|s| s.trim().parse().ok())
+                .unwrap_or(0)
+        }
+
+        fn save(&mut self, high_score: u32) {
+            let _ = std::fs::write(&self.path, high_score.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_store_round_trip() {
+        let path = std::env::temp_dir().join(format!("snake3d-high-score-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut store = FileStore::new(&path);
+        assert_eq!(store.load(), 0);
+        store.save(42);
+        assert_eq!(FileStore::new(&path).load(), 42);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,114 @@
+// Survival mode's storm: every so often the outermost open ring of every
+// face closes and becomes deadly, shrinking the playable surface towards an
+// island in the middle of each face.
+
+use crate::game::{DeathCause, DeathInfo, GameEvent, GameState, Position};
+
+// Seconds between closing rings, and how long the next ring is marked before
+// it closes.
+const STORM_SECONDS: f64 = 20.0;
+const WARNING_SECONDS: f64 = 4.0;
+
+impl GameState {
+    // How far `pos` is from the nearest edge of its face; 0 is the outer ring.
+    pub fn ring_of(&self, pos: Position) -> i32 {
+        let n = self.config.grid_size;
+        pos.u.min(pos.v).min(n - 1 - pos.u).min(n - 1 - pos.v)
+    }
+
+    pub fn storm_at(&self, pos: Position) -> bool {
+        self.ring_of(pos) < self.storm_rings
+    }
+
+    // Rings that can close. A 4x4 island stays open on each face, so there's
+    // always somewhere to put food.
+    fn max_storm_rings(&self) -> i32 {
+        (self.config.grid_size / 2 - 2).max(0)
+    }
+
+    pub(crate) fn schedule_storm(&mut self) {
+        if !self.config.storm || self.storm_rings >= self.max_storm_rings() {
+            self.next_storm = None;
+            return;
+        }
+        let ticks = (STORM_SECONDS / self.move_interval()).round() as u64;
+        self.next_storm = Some(self.ticks + ticks);
+    }
+
+    // The ring that closes next, while it's being telegraphed.
+    pub fn storm_warning(&self) -> Option<i32> {
+        let next = self.next_storm?;
+        let warning = (WARNING_SECONDS / self.move_interval()).round() as u64;
+        (self.ticks + warning >= next).then_some(self.storm_rings)
+    }
+
+    // Closes the next ring when it's due. A head caught inside is fatal,
+    // and anything else left in the storm is cleared or moved out of it.
+    pub(crate) fn step_storm(&mut self) -> GameEvent {
+        match self.next_storm {
+            Some(tick) if self.ticks >= tick => {}
+            _ => return GameEvent::None,
+        }
+        self.storm_rings += 1;
+        self.schedule_storm();
+
+        if self.storm_at(self.snake.head()) {
+            return self.die(DeathInfo::new(DeathCause::Storm, self.snake.head()));
+        }
+        if self.shield.is_some_and(|pos| self.storm_at(pos)) {
+            self.shield = None;
+        }
+        if self.feast.as_ref().is_some_and(|f| f.cells.iter().any(|c| self.storm_at(*c))) {
+            self.feast = None;
+        }
+        if self.storm_at(self.food) {
+            // Keeps a prize and its deadline, like a quake does.
+            self.food = self.random_free_cell();
+        }
+        GameEvent::StormClosed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{Direction, Face, GameConfig};
+    use crate::mode::GameMode;
+
+    use super::*;
+
+    fn survival_game() -> GameState {
+        GameState::with_config(GameConfig::with_mode(10, GameMode::Survival), 3)
+    }
+
+    #[test]
+    fn test_rings_close_on_schedule() {
+        let mut game = survival_game();
+        let first = game.next_storm.unwrap();
+        game.food = Position { face: Face::Back, u: 0, v: 0 };
+        game.ticks = first - 1;
+        assert_eq!(game.storm_warning(), Some(0));
+        assert_eq!(game.step_storm(), GameEvent::None);
+
+        game.ticks = first;
+        assert_eq!(game.step_storm(), GameEvent::StormClosed);
+        assert!(game.storm_at(Position { face: Face::Top, u: 9, v: 4 }));
+        assert!(!game.storm_at(Position { face: Face::Top, u: 8, v: 4 }));
+        assert!(!game.storm_at(game.food));
+        assert_eq!(game.storm_warning(), None);
+
+        // Three rings on a 10 grid leave a 4x4 island and the storm stops.
+        game.storm_rings = 3;
+        game.schedule_storm();
+        assert_eq!(game.next_storm, None);
+    }
+
+    #[test]
+    fn test_entering_storm_is_fatal() {
+        let mut game = survival_game();
+        game.storm_rings = 1;
+        game.food = Position { face: Face::Back, u: 5, v: 5 };
+        game.snake.set_body([Position { face: Face::Front, u: 5, v: 8 }]);
+        game.steer(Direction::Up);
+        assert_eq!(game.update(), GameEvent::GameOver);
+    }
+}
@@ -0,0 +1,204 @@
+// Checks on how a board's faces are stitched together. The cube's edges are
+// wired by hand in `calculate_next_position`; `verify` walks every edge cell
+// in every direction and reports the first crossing that doesn't line up.
+// It takes the step function rather than a `GameState` so other board shapes
+// can be checked the same way.
+
+use std::fmt;
+
+use crate::game::{Direction, Face, GameState, Position};
+
+const DIRECTIONS: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TopologyError {
+    pub from: Position,
+    pub dir: Direction,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for TopologyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} moving {:?}: {}", self.from, self.dir, self.reason)
+    }
+}
+
+impl std::error::Error for TopologyError {}
+
+// Every cell on a board of `grid_size` cells a side.
+pub fn cells(grid_size: i32) -> impl Iterator<Item = Position> {
+    Face::ALL.into_iter().flat_map(move |face| {
+        (0..grid_size).flat_map(move |u| (0..grid_size).map(move |v| Position { face, u, v }))
+    })
+}
+
+fn on_board(pos: Position, grid_size: i32) -> bool {
+    (0..grid_size).contains(&pos.u) && (0..grid_size).contains(&pos.v)
+}
+
+// Checks, for every cell and direction, that a step lands on the board and
+// that turning around and stepping back returns to the same cell heading the
+// opposite way. For the cells along each edge it also checks that they all
+// cross onto the same face, onto distinct cells, and that each face borders
+// four different faces.
+pub fn verify<F>(grid_size: i32, step: F) -> Result<(), TopologyError>
+where
+    F: Fn(Position, Direction) -> (Position, Direction),
+{
+    for from in cells(grid_size) {
+        for dir in DIRECTIONS {
+            let fail = |reason| Err(TopologyError { from, dir, reason });
+            let (to, heading) = step(from, dir);
+            if !on_board(to, grid_size) {
+                return fail("lands off the board");
+            }
+            let (back, back_heading) = step(to, heading.opposite());
+            if back != from {
+                return fail("reversing doesn't return to the start");
+            }
+            if back_heading != dir.opposite() {
+                return fail("reversing doesn't come back facing the other way");
+            }
+        }
+    }
+
+    for face in Face::ALL {
+        let mut neighbors = Vec::with_capacity(4);
+        for dir in DIRECTIONS {
+            let edge: Vec<Position> = cells(grid_size)
+                .filter(|p| p.face == face && step(*p, dir).0.face != face)
+                .collect();
+            let first = match edge.first() {
+                Some(first) => *first,
+                None => return Err(TopologyError { from: Position { face, u: 0, v: 0 }, dir, reason: "face has no edge this way" }),
+            };
+            let fail = |reason| Err(TopologyError { from: first, dir, reason });
+            if edge.len() != grid_size as usize {
+                return fail("edge isn't a full row of cells");
+            }
+            let landed: Vec<Position> = edge.iter().map(|p| step(*p, dir).0).collect();
+            let neighbor = landed[0].face;
+            if landed.iter().any(|p| p.face != neighbor) {
+                return fail("edge splits across several faces");
+            }
+            if landed.iter().enumerate().any(|(i, p)| landed[..i].contains(p)) {
+                return fail("two cells cross onto the same cell");
+            }
+            if neighbors.contains(&neighbor) {
+                return fail("face borders the same face twice");
+            }
+            neighbors.push(neighbor);
+        }
+    }
+    Ok(())
+}
+
+// Checks that going straight from every cell in every direction comes back
+// to that cell, with the same heading, after exactly `length` steps and not
+// before. On the cube that's 4·N: around one belt of four faces.
+pub fn verify_loops<F>(grid_size: i32, length: usize, step: F) -> Result<(), TopologyError>
+where
+    F: Fn(Position, Direction) -> (Position, Direction),
+{
+    for from in cells(grid_size) {
+        for dir in DIRECTIONS {
+            let (mut pos, mut heading) = (from, dir);
+            for taken in 1..=length {
+                (pos, heading) = step(pos, heading);
+                if pos == from && heading == dir && taken < length {
+                    return Err(TopologyError { from, dir, reason: "straight line loops back early" });
+                }
+            }
+            if pos != from || heading != dir {
+                return Err(TopologyError { from, dir, reason: "straight line doesn't loop back" });
+            }
+        }
+    }
+    Ok(())
+}
+
+impl GameState {
+    // `verify` for this game's cube.
+    pub fn verify_topology(&self) -> Result<(), TopologyError> {
+        verify(self.config.grid_size, |pos, dir| self.calculate_next_position(pos, dir))
+    }
+
+    // `verify_loops` for this game's cube.
+    pub fn verify_loops(&self) -> Result<(), TopologyError> {
+        let n = self.config.grid_size;
+        verify_loops(n, 4 * n.max(0) as usize, |pos, dir| self.calculate_next_position(pos, dir))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_cube_topology_is_consistent() {
+        for n in 1..=12 {
+            assert_eq!(GameState::new(n, 0).verify_topology(), Ok(()), "grid size {}", n);
+        }
+    }
+
+    #[test]
+    fn test_straight_lines_loop_around_the_cube() {
+        for n in 1..=12 {
+            assert_eq!(GameState::new(n, 0).verify_loops(), Ok(()), "grid size {}", n);
+        }
+        // The right length is the only one that passes.
+        let game = GameState::new(5, 0);
+        let step = |pos, dir| game.calculate_next_position(pos, dir);
+        assert!(verify_loops(5, 19, step).is_err());
+        assert!(verify_loops(5, 40, step).is_err());
+    }
+
+    #[test]
+    fn test_broken_edge_is_reported() {
+        let game = GameState::new(6, 0);
+        // Front's top edge leads onto Top, but Top leads back to Bottom.
+        let err = verify(6, |pos, dir| {
+            let (to, heading) = game.calculate_next_position(pos, dir);
+            if pos.face == Face::Top && to.face == Face::Front {
+                return (Position { face: Face::Bottom, ..to }, heading);
+            }
+            (to, heading)
+        })
+        .unwrap_err();
+        assert_eq!(err.from.face, Face::Front);
+        assert_eq!(err.dir, Direction::Up);
+    }
+
+    fn direction() -> impl Strategy<Value = Direction> {
+        prop::sample::select(DIRECTIONS.to_vec())
+    }
+
+    proptest! {
+        // Any walk retraced backwards ends where it began.
+        #[test]
+        fn prop_walks_retrace(
+            n in 2i32..20,
+            face in prop::sample::select(Face::ALL.to_vec()),
+            u in 0i32..20,
+            v in 0i32..20,
+            turns in prop::collection::vec(direction(), 1..200),
+        ) {
+            let game = GameState::new(n, 0);
+            let start = Position { face, u: u % n, v: v % n };
+            let mut pos = start;
+            let mut headings = Vec::with_capacity(turns.len());
+            for dir in turns {
+                let (to, heading) = game.calculate_next_position(pos, dir);
+                prop_assert!(on_board(to, n));
+                pos = to;
+                headings.push(heading);
+            }
+            for heading in headings.into_iter().rev() {
+                pos = game.calculate_next_position(pos, heading.opposite()).0;
+            }
+            prop_assert_eq!(pos, start);
+        }
+    }
+}
@@ -0,0 +1,152 @@
+// Tron mode: every cell the snake or its rival leaves becomes a permanent
+// wall. The rival is a single light-cycle head steered by a small AI; when it
+// crashes the player scores a bonus and a new rival enters elsewhere.
+
+use std::collections::{HashSet, VecDeque};
+
+use crate::game::{Direction, GameEvent, GameState, Position};
+
+pub const RIVAL_BONUS: u32 = 25;
+// How far the rival's flood fill looks when comparing moves.
+const LOOKAHEAD_CELLS: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rival {
+    pub pos: Position,
+    pub dir: Direction,
+}
+
+impl GameState {
+    pub fn wall_at(&self, pos: Position) -> bool {
+        self.walls.contains(&pos)
+    }
+
+    // Cells the rival can't enter.
+    fn rival_blocked(&self, pos: Position) -> bool {
+        self.wall_at(pos) || self.snake.contains(pos) || self.obstacle_at(pos)
+    }
+
+    pub(crate) fn spawn_rival(&mut self) {
+        if !self.config.mode.permanent_trail() {
+            return;
+        }
+        let head_face = self.snake.head().face;
+        loop {
+            let pos = self.random_free_cell();
+            if pos.face != head_face {
+                let dir = self.random_direction();
+                self.rival = Some(Rival { pos, dir });
+                return;
+            }
+        }
+    }
+
+    // Counts open cells reachable from `start`, up to the lookahead limit.
+    fn open_space(&self, start: Position) -> usize {
+        let mut seen = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(pos) = queue.pop_front() {
+            if seen.len() >= LOOKAHEAD_CELLS {
+                break;
+            }
+            for dir in [Direction::Up, Direction::Down, Direction::Left, Direction::Right] {
+                let (next, _) = self.calculate_next_position(pos, dir);
+                if !self.rival_blocked(next) && seen.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        seen.len()
+    }
+
+    // Moves the rival one cell, leaving a wall behind. It keeps straight
+    // unless turning opens up more room, and heads for food when it's close.
+    pub(crate) fn step_rival(&mut self) -> GameEvent {
+        let rival = match self.rival {
+            Some(rival) => rival,
+            None => return GameEvent::None,
+        };
+
+        let options = [rival.dir, turn(rival.dir, false), turn(rival.dir, true)];
+        let best = options
+            .into_iter()
+            .map(|d| self.calculate_next_position(rival.pos, d))
+            .filter(|(pos, _)| !self.rival_blocked(*pos) && *pos != rival.pos)
+            .max_by_key(|(pos, _)| (self.open_space(*pos), *pos == self.food));
+
+        self.walls.insert(rival.pos);
+        match best {
+            Some((pos, dir)) => {
+                self.rival = Some(Rival { pos, dir });
+                if pos == self.food {
+                    self.spawn_food();
+                }
+                GameEvent::None
+            }
+            None => {
+                // Boxed in: the rival crashes and the player gets the bonus.
+                self.score += RIVAL_BONUS;
+                if self.score > self.high_score {
+                    self.high_score = self.score;
+                }
+                self.rival = None;
+                self.spawn_rival();
+                GameEvent::RivalCrashed
+            }
+        }
+    }
+}
+
+fn turn(dir: Direction, right: bool) -> Direction {
+    let left = match dir {
+        Direction::Up => Direction::Left,
+        Direction::Left => Direction::Down,
+        Direction::Down => Direction::Right,
+        Direction::Right => Direction::Up,
+    };
+    if right { left.opposite() } else { left }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{Face, GameConfig};
+    use crate::mode::GameMode;
+
+    use super::*;
+
+    fn tron_game() -> GameState {
+        GameState::with_config(GameConfig::with_mode(10, GameMode::Tron), 23)
+    }
+
+    #[test]
+    fn test_trail_becomes_wall() {
+        let mut game = tron_game();
+        game.rival = None;
+        let start = game.snake.head();
+        game.food = Position { face: Face::Back, u: 0, v: 0 };
+        game.update();
+        assert!(game.wall_at(start));
+
+        // Turning back across the trail is fatal.
+        game.steer(Direction::Right);
+        game.update();
+        game.steer(Direction::Down);
+        game.update();
+        game.steer(Direction::Left);
+        assert_eq!(game.update(), GameEvent::GameOver);
+    }
+
+    #[test]
+    fn test_boxed_in_rival_crashes() {
+        let mut game = tron_game();
+        let pos = Position { face: Face::Back, u: 5, v: 5 };
+        game.rival = Some(Rival { pos, dir: Direction::Up });
+        for dir in [Direction::Up, Direction::Left, Direction::Right, Direction::Down] {
+            let (next, _) = game.calculate_next_position(pos, dir);
+            game.walls.insert(next);
+        }
+        assert_eq!(game.step_rival(), GameEvent::RivalCrashed);
+        assert_eq!(game.score, RIVAL_BONUS);
+        assert!(game.rival.is_some_and(|r| r.pos != pos));
+    }
+}
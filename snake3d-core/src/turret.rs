@@ -0,0 +1,150 @@
+// Turrets: stationary blocks that periodically fire a projectile along their
+// row or column. Shots travel one cell per tick and wrap across cube edges
+// like the snake does, so a shot can come back around from behind.
+
+use crate::game::{DeathCause, DeathInfo, Direction, GameEvent, GameState, Position};
+
+// Ticks between shots.
+const FIRE_PERIOD: u32 = 12;
+// A turret is telegraphed as charging for this many ticks before it fires.
+pub const WARNING_TICKS: u32 = 4;
+const MAX_TURRETS: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Turret {
+    pub pos: Position,
+    pub dir: Direction,
+    // Ticks until the next shot.
+    pub charge: u32,
+}
+
+impl Turret {
+    pub fn charging(&self) -> bool {
+        self.charge <= WARNING_TICKS
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Projectile {
+    pub pos: Position,
+    pub dir: Direction,
+    // Ticks left before it fizzles out.
+    pub ttl: u32,
+}
+
+impl GameState {
+    // One turret from level 3, one more every three levels after that.
+    fn turret_target(&self) -> usize {
+        if !self.config.turrets {
+            return 0;
+        }
+        (self.level() as usize / 3).min(MAX_TURRETS)
+    }
+
+    // At most one turret per face, never on the snake's face.
+    pub(crate) fn spawn_turrets(&mut self) {
+        let head_face = self.snake.head().face;
+        while self.turrets.len() < self.turret_target() {
+            let pos = self.random_free_cell();
+            if pos.face == head_face || self.turrets.iter().any(|t| t.pos.face == pos.face) {
+                continue;
+            }
+            let dir = self.random_direction();
+            self.turrets.push(Turret { pos, dir, charge: FIRE_PERIOD });
+        }
+    }
+
+    // Advances projectiles, then counts turrets down and fires the ready ones.
+    pub(crate) fn step_turrets(&mut self) -> GameEvent {
+        let mut event = GameEvent::None;
+
+        let mut i = 0;
+        while i < self.projectiles.len() {
+            let shot = self.projectiles[i];
+            let (pos, dir) = self.calculate_next_position(shot.pos, shot.dir);
+            if shot.ttl == 0 || self.turrets.iter().any(|t| t.pos == pos) {
+                self.projectiles.remove(i);
+                continue;
+            }
+            self.projectiles[i] = Projectile { pos, dir, ttl: shot.ttl - 1 };
+            if let Some(hit) = self.projectile_hit(i) {
+                if hit == GameEvent::GameOver {
+                    return hit;
+                }
+                event = hit;
+                continue;
+            }
+            i += 1;
+        }
+
+        for t in 0..self.turrets.len() {
+            self.turrets[t].charge -= 1;
+            if self.turrets[t].charge > 0 {
+                continue;
+            }
+            let turret = self.turrets[t];
+            self.turrets[t].charge = FIRE_PERIOD;
+            let (pos, dir) = self.calculate_next_position(turret.pos, turret.dir);
+            // A full lap around the cube.
+            let ttl = self.config.grid_size as u32 * 4;
+            self.projectiles.push(Projectile { pos, dir, ttl });
+            if let Some(hit) = self.projectile_hit(self.projectiles.len() - 1) {
+                if hit == GameEvent::GameOver {
+                    return hit;
+                }
+                event = hit;
+            }
+        }
+
+        event
+    }
+
+    // Checks projectile `i` against the snakes. A shield absorbs the hit and
+    // removes the projectile.
+    fn projectile_hit(&mut self, i: usize) -> Option<GameEvent> {
+        let pos = self.projectiles[i].pos;
+        if !self.snake.contains(pos) {
+            let segment = self.partner_segment(pos)?;
+            self.projectiles.remove(i);
+            return Some(self.partner_crash(DeathInfo { segment: Some(segment), ..DeathInfo::new(DeathCause::Turret, pos) }));
+        }
+        if self.shielded {
+            self.shielded = false;
+            self.projectiles.remove(i);
+            return Some(GameEvent::ShieldBreak);
+        }
+        let segment = self.snake.body.iter().position(|p| *p == pos);
+        Some(self.die(DeathInfo { segment, ..DeathInfo::new(DeathCause::Turret, pos) }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{Face, GameConfig};
+    use crate::mode::GameMode;
+
+    use super::*;
+
+    #[test]
+    fn test_turret_fires_and_shot_kills() {
+        let config = GameConfig::with_mode(10, GameMode::Hazards);
+        let mut game = GameState::with_config(config, 21);
+        let head = game.snake.head();
+        game.food = Position { face: Face::Back, u: 0, v: 0 };
+        game.shield = None;
+        // Three cells to the right of where the head will be, firing left.
+        game.turrets.push(Turret {
+            pos: Position { u: head.u + 3, v: head.v + 1, ..head },
+            dir: Direction::Left,
+            charge: 1,
+        });
+
+        // Fires into the cell two to the right of the new head...
+        assert_eq!(game.update(), GameEvent::None);
+        assert_eq!(game.projectiles.len(), 1);
+        assert!(game.turrets[0].charge > WARNING_TICKS);
+        // ...and the shot meets the head as the snake passes.
+        game.steer(Direction::Right);
+        assert_eq!(game.update(), GameEvent::GameOver);
+    }
+}
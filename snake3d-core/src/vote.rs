@@ -0,0 +1,94 @@
+// Steering by vote, for party play where an audience (a stream's chat, say)
+// picks the turns. Votes collect over a window of game time; when it closes
+// the most voted direction is taken, and a tie keeps the snake going straight.
+
+use crate::game::Direction;
+
+const DIRECTIONS: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+// Seconds of game time each vote runs for.
+pub const VOTE_SECONDS: f64 = 1.0;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VoteTally {
+    // Up, down, left, right.
+    counts: [u32; 4],
+    opened_at: f64,
+}
+
+impl Direction {
+    // "up", "Down", "!left": the forms a chat command takes.
+    pub fn from_name(name: &str) -> Option<Direction> {
+        match name.trim().trim_start_matches('!').to_ascii_lowercase().as_str() {
+            "up" | "u" => Some(Direction::Up),
+            "down" | "d" => Some(Direction::Down),
+            "left" | "l" => Some(Direction::Left),
+            "right" | "r" => Some(Direction::Right),
+            _ => None,
+        }
+    }
+}
+
+impl VoteTally {
+    pub fn add(&mut self, dir: Direction) {
+        if let Some(i) = DIRECTIONS.iter().position(|d| *d == dir) {
+            self.counts[i] += 1;
+        }
+    }
+
+    // Votes so far in this window, as up, down, left, right.
+    pub fn counts(&self) -> [u32; 4] {
+        self.counts
+    }
+
+    pub fn winner(&self) -> Option<Direction> {
+        let best = *self.counts.iter().max()?;
+        if best == 0 || self.counts.iter().filter(|c| **c == best).count() > 1 {
+            return None;
+        }
+        self.counts.iter().position(|c| *c == best).map(|i| DIRECTIONS[i])
+    }
+
+    // Once the window has run `VOTE_SECONDS` by `now` (game time), closes
+    // it, opens the next and returns the winner. A clock that went back, as
+    // on a restart, just opens a fresh window.
+    pub fn close(&mut self, now: f64) -> Option<Direction> {
+        if now < self.opened_at {
+            *self = VoteTally { opened_at: now, ..Default::default() };
+            return None;
+        }
+        if now - self.opened_at < VOTE_SECONDS {
+            return None;
+        }
+        let winner = self.winner();
+        *self = VoteTally { opened_at: now, ..Default::default() };
+        winner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_most_votes_win_when_the_window_closes() {
+        let mut votes = VoteTally::default();
+        for name in ["!left", "up", "Left", "l", "right"] {
+            votes.add(Direction::from_name(name).unwrap());
+        }
+        assert_eq!(votes.counts(), [1, 0, 3, 1]);
+        assert_eq!(votes.close(0.5), None);
+        assert_eq!(votes.close(1.0), Some(Direction::Left));
+        assert_eq!(votes.counts(), [0; 4]);
+        assert_eq!(Direction::from_name("jump"), None);
+    }
+
+    #[test]
+    fn test_tie_goes_straight() {
+        let mut votes = VoteTally::default();
+        votes.add(Direction::Up);
+        votes.add(Direction::Right);
+        assert_eq!(votes.close(2.0), None);
+        assert_eq!(votes.counts(), [0; 4]);
+    }
+}
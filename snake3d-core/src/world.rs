@@ -0,0 +1,159 @@
+// Random world events: every half minute or so something shakes up the
+// board. A food rush scatters extra food, a quake moves food and obstacles
+// around, and a blackout dims the lights for a while.
+
+use crate::game::{Face, GameEvent, GameState, Position};
+
+// Seconds from the start of a run to the first event, and the gap between
+// later ones before jitter.
+const FIRST_EVENT_SECONDS: f64 = 30.0;
+const EVENT_JITTER_SECONDS: u32 = 20;
+pub const RUSH_FOODS: usize = 5;
+pub const BLACKOUT_SECONDS: f64 = 10.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WorldEvent {
+    FoodRush,
+    Quake,
+    Blackout,
+}
+
+impl WorldEvent {
+    pub const ALL: [WorldEvent; 3] = [WorldEvent::FoodRush, WorldEvent::Quake, WorldEvent::Blackout];
+}
+
+impl GameState {
+    // Converts seconds to ticks at the current pace.
+    fn seconds_to_ticks(&self, seconds: f64) -> u64 {
+        (seconds / self.move_interval()).round() as u64
+    }
+
+    // Sets the tick the next event fires on. The first one is fixed so a
+    // new game doesn't draw from the RNG.
+    pub(crate) fn schedule_world_event(&mut self) {
+        if !self.config.world_events {
+            return;
+        }
+        let seconds = if self.next_world_event.is_none() {
+            FIRST_EVENT_SECONDS
+        } else {
+            FIRST_EVENT_SECONDS + self.rng.below(EVENT_JITTER_SECONDS) as f64
+        };
+        self.next_world_event = Some(self.ticks + self.seconds_to_ticks(seconds));
+    }
+
+    pub fn rush_food_at(&self, pos: Position) -> bool {
+        self.rush_food.contains(&pos)
+    }
+
+    // The lights are out.
+    pub fn blackout(&self) -> bool {
+        self.blackout_until.is_some_and(|until| self.ticks < until)
+    }
+
+    // Fires the scheduled event once its tick comes around.
+    pub(crate) fn step_world_events(&mut self) -> GameEvent {
+        match self.next_world_event {
+            Some(tick) if self.ticks >= tick => {}
+            _ => return GameEvent::None,
+        }
+        let event = WorldEvent::ALL[self.rng.below(WorldEvent::ALL.len() as u32) as usize];
+        match event {
+            WorldEvent::FoodRush => {
+                self.rush_food.clear();
+                for _ in 0..RUSH_FOODS {
+                    let pos = self.random_free_cell();
+                    self.rush_food.push(pos);
+                }
+            }
+            WorldEvent::Quake => self.quake(),
+            WorldEvent::Blackout => {
+                self.blackout_until = Some(self.ticks + self.seconds_to_ticks(BLACKOUT_SECONDS));
+            }
+        }
+        self.schedule_world_event();
+        GameEvent::World(event)
+    }
+
+    // Everything on the board but the snake gets a new cell. Nothing lands
+    // on the snake's face, and shots in flight are lost in the shaking.
+    fn quake(&mut self) {
+        let head_face = self.snake.head().face;
+        self.projectiles.clear();
+        for i in 0..self.hazards.len() {
+            self.hazards[i].pos = self.free_cell_off(head_face);
+        }
+        for i in 0..self.turrets.len() {
+            self.turrets[i].pos = self.free_cell_off(head_face);
+        }
+        for i in 0..self.rush_food.len() {
+            self.rush_food[i] = self.free_cell_off(head_face);
+        }
+        // Unlike `spawn_food` this keeps a prize and its deadline.
+        self.food = self.free_cell_off(head_face);
+    }
+
+    fn free_cell_off(&mut self, face: Face) -> Position {
+        loop {
+            let pos = self.random_free_cell();
+            if pos.face != face {
+                return pos;
+            }
+        }
+    }
+
+    // The head just moved onto rush food: worth a regular meal, but it
+    // doesn't count towards the next prize.
+    pub(crate) fn eat_rush_food(&mut self, pos: Position) -> GameEvent {
+        self.rush_food.retain(|p| *p != pos);
+        self.score += 1;
+        if self.score > self.high_score {
+            self.high_score = self.score;
+        }
+        GameEvent::Eat
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::GameConfig;
+    use crate::mode::GameMode;
+
+    use super::*;
+
+    #[test]
+    fn test_events_fire_on_schedule() {
+        let mut game = GameState::new(10, 5);
+        let first = game.next_world_event.unwrap();
+        game.ticks = first - 1;
+        assert_eq!(game.step_world_events(), GameEvent::None);
+
+        game.ticks = first;
+        let event = match game.step_world_events() {
+            GameEvent::World(event) => event,
+            other => panic!("expected a world event, got {:?}", other),
+        };
+        assert!(game.next_world_event.unwrap() > first);
+        match event {
+            WorldEvent::FoodRush => assert_eq!(game.rush_food.len(), RUSH_FOODS),
+            WorldEvent::Blackout => assert!(game.blackout()),
+            WorldEvent::Quake => assert_ne!(game.food.face, game.snake.head().face),
+        }
+
+        let tron = GameState::with_config(GameConfig::with_mode(10, GameMode::Tron), 5);
+        assert_eq!(tron.next_world_event, None);
+    }
+
+    #[test]
+    fn test_rush_food_grows_snake() {
+        let mut game = GameState::new(10, 5);
+        let head = game.snake.head();
+        game.food = Position { face: Face::Back, u: 0, v: 0 };
+        game.shield = None;
+        game.rush_food = vec![Position { v: head.v + 1, ..head }];
+        assert_eq!(game.update(), GameEvent::Eat);
+        assert_eq!(game.score, 1);
+        assert_eq!(game.snake.body.len(), 2);
+        assert!(game.rush_food.is_empty());
+    }
+}
@@ -0,0 +1,74 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::dom::Dom;
+use crate::error;
+use crate::i18n::t;
+
+const STORAGE_KEY: &str = "snake3d_achievements";
+// How long the unlock toast stays up.
+const TOAST_MS: i32 = 3000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Achievement {
+    // Visit all six faces between two pickups.
+    GrandTour,
+}
+
+impl Achievement {
+    pub fn id(self) -> &'static str {
+        match self {
+            Achievement::GrandTour => "grand_tour",
+        }
+    }
+
+    pub fn title_key(self) -> &'static str {
+        match self {
+            Achievement::GrandTour => "achievement_grand_tour",
+        }
+    }
+}
+
+fn unlocked_ids() -> Vec<String> {
+    error::storage()
+        .ok()
+        .and_then(|s| s.get_item(STORAGE_KEY).ok().flatten())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+pub fn is_unlocked(achievement: Achievement) -> bool {
+    unlocked_ids().iter().any(|id| id == achievement.id())
+}
+
+// Records the achievement and shows a toast the first time it's earned.
+// Returns whether this was that first time.
+pub fn unlock(dom: &Dom, achievement: Achievement) -> bool {
+    let mut ids = unlocked_ids();
+    if ids.iter().any(|id| id == achievement.id()) {
+        return false;
+    }
+    ids.push(achievement.id().to_string());
+    if let (Ok(storage), Ok(json)) = (error::storage(), serde_json::to_string(&ids)) {
+        let _ = storage.set_item(STORAGE_KEY, &json);
+    }
+    show_toast(dom, &format!("{} {}", t("achievement_unlocked"), t(achievement.title_key())));
+    true
+}
+
+pub fn show_toast(dom: &Dom, text: &str) {
+    let toast = match dom.get("toast") {
+        Some(toast) => toast,
+        None => return,
+    };
+    toast.set_text_content(Some(text));
+    toast.class_list().remove_1("hidden").unwrap_or(());
+
+    let hide = Closure::once(move || {
+        toast.class_list().add_1("hidden").unwrap_or(());
+    });
+    if let Ok(window) = error::window() {
+        let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(hide.as_ref().unchecked_ref(), TOAST_MS);
+    }
+    hide.forget();
+}
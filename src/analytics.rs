@@ -0,0 +1,109 @@
+use serde::Serialize;
+use snake3d_core::GameState;
+
+use crate::error;
+use crate::profiler::FrameStats;
+
+// Runs held back before a batch is sent. Whatever is left goes out when the
+// page is hidden.
+const BATCH_RUNS: usize = 5;
+
+// One finished run, with nothing that identifies the player: no name, seed
+// or locale.
+#[derive(Serialize)]
+struct RunReport {
+    mode: &'static str,
+    grid: i32,
+    score: u32,
+    ticks: u64,
+    seconds: f64,
+    // `None` for runs that reached the mode's target.
+    cause: Option<&'static str>,
+    // The fatal cell, and the body segment struck if it was the snake's own
+    // body or a shot.
+    face: Option<&'static str>,
+    cell: Option<[i32; 2]>,
+    segment: Option<usize>,
+    device: &'static str,
+    frame_p50: f64,
+    frame_p95: f64,
+}
+
+// Gameplay statistics for balancing, sent to the host's `analyticsEndpoint`
+// only while the player has turned on the setting.
+#[derive(Default)]
+pub struct Analytics {
+    endpoint: Option<String>,
+    batch: Vec<RunReport>,
+}
+
+impl Analytics {
+    // Builds without the `analytics` feature never collect anything.
+    pub fn new(endpoint: Option<String>) -> Self {
+        let endpoint = endpoint.filter(|_| cfg!(feature = "analytics"));
+        Self { endpoint, batch: Vec::new() }
+    }
+
+    // Queues a report on the run that just ended.
+    pub fn record(&mut self, game: &GameState, frames: &FrameStats) {
+        if self.endpoint.is_none() {
+            return;
+        }
+        self.batch.push(RunReport {
+            mode: game.config.mode.name(),
+            grid: game.config.grid_size,
+            score: game.score,
+            ticks: game.ticks,
+            seconds: game.elapsed,
+            cause: game.death.map(|death| death.cause.name()),
+            face: game.death.map(|death| death.at.face.name()),
+            cell: game.death.map(|death| [death.at.u, death.at.v]),
+            segment: game.death.and_then(|death| death.segment),
+            device: device_class(),
+            frame_p50: frames.frame.p50,
+            frame_p95: frames.frame.p95,
+        });
+        if self.batch.len() >= BATCH_RUNS {
+            self.flush();
+        }
+    }
+
+    // Drops anything queued, for when the player opts out.
+    pub fn clear(&mut self) {
+        self.batch.clear();
+    }
+
+    // Sends the queued reports with `sendBeacon`, which outlives the page
+    // being closed.
+    #[cfg(feature = "analytics")]
+    pub fn flush(&mut self) {
+        let endpoint = match &self.endpoint {
+            Some(endpoint) if !self.batch.is_empty() => endpoint,
+            _ => return,
+        };
+        let json = match serde_json::to_string(&self.batch) {
+            Ok(json) => json,
+            Err(_) => return,
+        };
+        let sent = error::window()
+            .ok()
+            .and_then(|w| w.navigator().send_beacon_with_opt_str(endpoint, Some(&json)).ok())
+            .unwrap_or(false);
+        if !sent {
+            log::warn!("Analytics batch of {} runs was dropped", self.batch.len());
+        }
+        self.batch.clear();
+    }
+
+    #[cfg(not(feature = "analytics"))]
+    pub fn flush(&mut self) {}
+}
+
+fn device_class() -> &'static str {
+    let touch = error::window().map(|w| w.navigator().max_touch_points() > 0).unwrap_or(false);
+    if touch {
+        "touch"
+    } else {
+        "desktop"
+    }
+}
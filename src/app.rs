@@ -0,0 +1,1141 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use three_d::*;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use snake3d_core::{
+    verify_replay, DeathCause, DeathInfo, Direction, Director, Face, GameConfig, GameEvent, GameMode, GameState, HighScoreStore, LevelDefinition,
+    Position, Replay, SpeedCurve, TickClock, VoteTally, WorldEvent,
+};
+
+use crate::achievements::{self, Achievement};
+use crate::analytics::Analytics;
+use crate::audio::AudioPlayer;
+use crate::bests;
+use crate::chat;
+use crate::clips::Cue;
+use crate::campaign;
+use crate::capture::Capture;
+use crate::community;
+use crate::dom::Dom;
+#[cfg(feature = "editor")]
+use crate::editor::Editor;
+use crate::error;
+use crate::events::{self, HostEvent, Listeners};
+use crate::gui::Gui;
+use crate::i18n::t;
+use crate::models::Models;
+#[cfg(not(feature = "minimal"))]
+use crate::music::Sequencer;
+use crate::narrator;
+use crate::profiler::{self, Profiler};
+use crate::renderer::{self, GameRenderer};
+use crate::resolution::DynamicResolution;
+use crate::seasons::Season;
+use crate::settings::{ControlScheme, Settings, Trail};
+use crate::shop;
+#[cfg(not(feature = "minimal"))]
+use crate::speedrun::{self, SpeedrunHud};
+use crate::storage::LocalStorageStore;
+use crate::transition::TransitionKind;
+use crate::unlocks;
+
+// How long world event and new record banners stay up.
+const BANNER_MS: i32 = 2500;
+
+// State shared between the render loop and everything outside it
+// (DOM event listeners and the JS-facing `SnakeGame` methods).
+#[derive(Default)]
+pub struct Controls {
+    pub paused: bool,
+    pub mode: GameMode,
+    // Host override for the mode's pacing.
+    pub speed: Option<SpeedCurve>,
+    pub restart_requested: bool,
+    pub mobile_input: Option<Direction>,
+    // Score of the last finished run, used by the submit button.
+    pub pending_score: u32,
+    // Host callbacks registered through `game.on`.
+    pub listeners: Listeners,
+    pub settings: Settings,
+    // Set by the settings panel; the loop applies and clears it.
+    pub settings_changed: bool,
+    // Capture the next rendered frame as a share card.
+    pub share_requested: bool,
+    // Save the last seconds of play as a GIF, with Record Clips on.
+    pub clip_requested: bool,
+    pub profiler: Profiler,
+    pub debug_overlay: bool,
+    // Set when the page was hidden; the loop pauses the run and clears it.
+    pub page_was_hidden: bool,
+    // Pause and game over menus are drawn in the canvas (see `gui.rs`)
+    // rather than by the page.
+    pub canvas_ui: bool,
+    // This game's elements on the host page.
+    pub dom: Dom,
+    // Every run plays this seed, when a link asked for one.
+    pub seed: Option<u64>,
+    pub analytics: Analytics,
+    // Open vote on the next turn, in party play (see `chat.rs`).
+    pub votes: Option<VoteTally>,
+    // Seasonal event running now, if any (see `seasons.rs`).
+    pub season: Option<Season>,
+    #[cfg(feature = "editor")]
+    pub editor: Editor,
+    // Runs play this level instead of the mode's open board, while set.
+    pub level: Option<LevelDefinition>,
+    // Levels from the host's `levelsUrl` list.
+    pub community: Vec<LevelDefinition>,
+    // Campaign level being played, alongside `level`.
+    pub campaign: Option<usize>,
+    // Host models as they arrive (see `models.rs`); the loop rebuilds the
+    // renderer and clears the flag.
+    pub models: Models,
+    pub models_changed: bool,
+}
+
+// What the menus (see `gui.rs`) ask the app to do.
+pub enum MenuAction {
+    Play,
+    #[cfg(feature = "ui")]
+    Restart,
+    #[cfg(feature = "ui")]
+    MainMenu,
+    // Take back the end of a lost run (see `snake3d_core::rewind`).
+    #[cfg(feature = "ui")]
+    Rewind,
+    // Open the level editor, or play its level.
+    #[cfg(feature = "editor")]
+    Edit,
+    #[cfg(feature = "editor")]
+    TestLevel,
+    #[cfg(feature = "ui")]
+    PlayLevel(LevelDefinition),
+    // Play campaign level n (see `campaign.rs`).
+    #[cfg(feature = "ui")]
+    PlayCampaign(usize),
+}
+
+// Top level flow: the main menu over the turning cube, then the game.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum AppState {
+    Menu,
+    Playing,
+    // In the level editor, over a still preview of the level.
+    #[cfg(feature = "editor")]
+    Editing,
+}
+
+pub struct App {
+    state: AppState,
+    game: GameState,
+    context: Context,
+    renderer: GameRenderer,
+    audio: AudioPlayer,
+    controls: Rc<RefCell<Controls>>,
+    dom: Dom,
+    settings: Settings,
+    high_scores: Box<dyn HighScoreStore>,
+    // Paces ticks against frame time, and keeps stalls from skipping cells.
+    ticks: TickClock,
+    // The last seconds of play, with Record Clips on.
+    capture: Capture,
+    // Running game time in seconds, which tick sounds are scheduled against.
+    clock: f64,
+    // Snake body before the most recent tick, for render interpolation.
+    prev_body: Vec<Position>,
+    #[cfg(not(feature = "minimal"))]
+    speedrun: SpeedrunHud,
+    resolution: DynamicResolution,
+    gui: Gui,
+    // Beat clock of a rhythm mode run, while the audio clock runs.
+    #[cfg(not(feature = "minimal"))]
+    sequencer: Option<Sequencer>,
+    // Plays while a boss is out (see `snake3d_core::boss`).
+    #[cfg(not(feature = "minimal"))]
+    boss_music: Option<Sequencer>,
+    // The season the renderer was built for.
+    season: Option<Season>,
+    // The models the renderer was built with.
+    models: Models,
+    // Level the editor preview was built from.
+    preview: Option<LevelDefinition>,
+    // Whole seconds left on the prize, for the countdown tick.
+    prize_second: Option<u32>,
+    // Turns of the current run, checked against its score when it ends.
+    replay: Option<Replay>,
+    // Adaptive difficulty, carried from run to run. `directed` is whether it
+    // paces the current run; `base_speed` is the run's speed before it.
+    director: Director,
+    directed: bool,
+    base_speed: SpeedCurve,
+    // Best score for the run's mode and board from before it started; `None`
+    // on level runs.
+    personal_best: Option<u32>,
+    // The food hint or the director helped this run, so it stays off the
+    // leaderboard, the high score, personal bests and campaign progress.
+    assisted: bool,
+    // The run's coins, lifetime progress, analytics report and game-over
+    // event went out. A rewound run that ends again doesn't send them twice.
+    settled: bool,
+    has_logged: bool,
+}
+
+impl App {
+    pub fn new(context: Context, controls: Rc<RefCell<Controls>>) -> Self {
+        let (mode, speed, seed, settings, canvas_ui, dom, season) = {
+            let controls = controls.borrow();
+            (controls.mode, controls.speed, controls.seed, controls.settings.clone(), controls.canvas_ui, controls.dom.clone(), controls.season)
+        };
+        settings.apply_to_document();
+        let mut audio = AudioPlayer::new();
+        audio.set_volume(settings.effective_sfx_volume());
+        audio.set_announcer(settings.announcer);
+        audio.set_sound_pack(settings.sound_pack);
+        if let Err(e) = audio.suspend_when_hidden() {
+            log::warn!("Audio keeps playing in background tabs: {:?}", e);
+        }
+        let high_scores: Box<dyn HighScoreStore> = Box::new(LocalStorageStore);
+        let directed = settings.director && !mode.competitive();
+        let (mut game, replay) = new_game(settings.grid_size, mode, speed, seed, None, directed);
+        game.high_score = high_scores.load();
+        let base_speed = game.config.speed;
+        let personal_best = Some(bests::load(mode, settings.grid_size));
+        let assisted = directed || (settings.food_hint && !mode.competitive());
+        let gui = Gui::new(&context, canvas_ui);
+        Self {
+            state: AppState::Menu,
+            prev_body: game.snake.body.iter().copied().collect(),
+            game,
+            renderer: GameRenderer::new(context.clone(), &settings, season, &Models::default()),
+            season,
+            models: Models::default(),
+            preview: None,
+            context,
+            audio,
+            controls,
+            dom,
+            settings,
+            high_scores,
+            ticks: TickClock::default(),
+            capture: Capture::default(),
+            clock: 0.0,
+            #[cfg(not(feature = "minimal"))]
+            speedrun: SpeedrunHud::new(),
+            resolution: DynamicResolution::default(),
+            gui,
+            #[cfg(not(feature = "minimal"))]
+            sequencer: None,
+            #[cfg(not(feature = "minimal"))]
+            boss_music: None,
+            prize_second: None,
+            replay,
+            director: Director::default(),
+            directed,
+            base_speed,
+            personal_best,
+            assisted,
+            settled: false,
+            has_logged: false,
+        }
+    }
+
+    fn restart(&mut self) {
+        let high_score = self.game.high_score;
+        let previous_score = self.game.score;
+        let (mode, speed, seed, level) = {
+            let controls = self.controls.borrow();
+            (controls.mode, controls.speed, controls.seed, controls.level.clone())
+        };
+        self.directed = self.settings.director && !mode.competitive();
+        (self.game, self.replay) = new_game(self.settings.grid_size, mode, speed, seed, level.as_ref(), self.directed);
+        self.game.high_score = high_score;
+        self.base_speed = self.game.config.speed;
+        self.personal_best = level.is_none().then(|| bests::load(mode, self.settings.grid_size));
+        self.assisted = self.directed || (self.settings.food_hint && !mode.competitive());
+        if self.directed {
+            self.director.apply(&mut self.game.config, self.base_speed);
+        }
+        self.settled = false;
+        self.preview = None;
+        self.fit_renderer();
+        if previous_score != 0 {
+            self.emit(HostEvent::Score(0));
+        }
+        self.ticks.reset();
+        self.capture.clear();
+        self.prev_body = self.game.snake.body.iter().copied().collect();
+        #[cfg(not(feature = "minimal"))]
+        {
+            self.speedrun.reset();
+            self.sequencer = None;
+            self.boss_music = None;
+        }
+        self.renderer.start_transition(TransitionKind::Fade);
+    }
+
+    // Picks a lost run back up a few seconds before the end, if the mode
+    // still allows it. The replay drops the turns that were taken back, so
+    // it still matches the run.
+    fn rewind(&mut self) {
+        let previous_score = self.game.score;
+        if !self.game.rewind() {
+            return;
+        }
+        if self.game.score != previous_score {
+            self.emit(HostEvent::Score(self.game.score));
+        }
+        if let Some(replay) = &mut self.replay {
+            let ticks = self.game.ticks;
+            replay.inputs.retain(|(tick, _)| *tick <= ticks);
+        }
+        self.ticks.reset();
+        self.prev_body = self.game.snake.body.iter().copied().collect();
+        self.renderer.start_transition(TransitionKind::Fade);
+    }
+
+    fn end_run(&mut self) {
+        self.renderer.start_transition(TransitionKind::Wipe);
+        // The check a score server would make, so a desync shows up here first.
+        if let Some(replay) = &self.replay {
+            let verdict = verify_replay(replay, self.game.score);
+            if !verdict.is_valid() {
+                log::warn!("Run doesn't replay to its score: {:?}", verdict);
+            }
+        }
+        // Update pending score for submit
+        {
+            let mut controls = self.controls.borrow_mut();
+            controls.pending_score = self.game.score;
+            if controls.settings.analytics && !self.settled {
+                let frames = controls.profiler.stats();
+                controls.analytics.record(&self.game, &frames);
+            }
+        }
+        if self.assisted {
+            if let Some(entry) = self.dom.get("name-entry") {
+                entry.class_list().add_1("hidden").unwrap_or(());
+            }
+        } else {
+            crate::leaderboard::prepare_name_entry(&self.dom, self.game.score);
+        }
+        if let Some(clip_btn) = self.dom.get("clip-btn") {
+            clip_btn.class_list().toggle_with_force("hidden", !self.settings.record_clips).unwrap_or(false);
+        }
+        // One toast for everything the run brought in.
+        let mut notices = Vec::new();
+        if !self.settled {
+            let coins = shop::award(self.game.score);
+            if coins > 0 {
+                notices.push(t("coins_earned").replace("{coins}", &coins.to_string()));
+            }
+            for cosmetic in unlocks::add_to_lifetime(self.game.score) {
+                notices.push(format!("{} {}", t("cosmetic_unlocked"), t(cosmetic.title_key())));
+            }
+        }
+        if self.assisted {
+            notices.push(t("unranked").to_string());
+        } else if let Some(name) = crate::leaderboard::beaten_friend(self.game.score) {
+            notices.push(t("beat_friend").replace("{name}", &name));
+        }
+        let (level, campaign_index) = {
+            let controls = self.controls.borrow();
+            (controls.level.clone(), controls.campaign)
+        };
+        match (campaign_index, level) {
+            (Some(i), _) => {
+                let stars = campaign::levels().get(i).map_or(0, |level| level.stars_for(&self.game));
+                if !self.assisted && campaign::record(i, stars) {
+                    notices.push(format!("{} {}", t("campaign_stars"), "★".repeat(stars as usize)));
+                }
+            }
+            (None, Some(level)) => {
+                if !self.assisted && community::record(&level, self.game.score) {
+                    notices.push(t("level_best").to_string());
+                }
+            }
+            (None, None) => {
+                let config = self.game.config;
+                if !self.assisted && bests::save_if_best(config.mode, config.grid_size, self.game.score) {
+                    notices.push(t("new_pb").to_string());
+                    self.emit(HostEvent::PersonalBest(self.game.score));
+                }
+            }
+        }
+        if !notices.is_empty() {
+            achievements::show_toast(&self.dom, &notices.join(" · "));
+        }
+        if !self.settled {
+            self.emit(HostEvent::GameOver(self.game.score));
+        }
+        self.settled = true;
+    }
+
+    // Calls the host's listeners for `event`, with the borrow released so
+    // they can use the JS API.
+    fn emit(&self, event: HostEvent) {
+        let callbacks = self.controls.borrow().listeners.for_event(event);
+        events::dispatch(callbacks, event);
+    }
+
+    fn tick(&mut self) {
+        let now = self.game.elapsed;
+        let voted = self.controls.borrow_mut().votes.as_mut().and_then(|votes| votes.close(now));
+        if let Some(dir) = voted {
+            self.steer(dir, None);
+        }
+
+        self.prev_body.clear();
+        self.prev_body.extend(self.game.snake.body.iter().copied());
+
+        let old_food_pos = self.game.food;
+        let before = narrator::Before {
+            food: self.game.food,
+            is_prize: self.game.is_prize,
+            level: self.game.level(),
+            new_record: self.game.new_record,
+        };
+        let old_rival_pos = self.game.rival.map(|r| r.pos);
+        let previous_high = self.game.high_score;
+        let previous_level = self.game.level();
+        let previous_score = self.game.score;
+        let old_tail = self.game.snake.body.back().copied();
+        let old_direction = self.game.snake.direction;
+        let event = self.game.update();
+        if self.assisted {
+            self.game.high_score = previous_high;
+            self.game.new_record = false;
+        }
+        if self.directed {
+            let turned = self.game.snake.direction != old_direction;
+            self.director.observe(&self.game, event, turned);
+            self.director.apply(&mut self.game.config, self.base_speed);
+        }
+        if self.settings.trail == Trail::Sparks {
+            if let Some(tail) = old_tail.filter(|tail| !self.game.snake.contains(*tail)) {
+                self.renderer.spawn_trail(tail);
+            }
+        }
+        if self.game.high_score > previous_high {
+            self.high_scores.save(self.game.high_score);
+        }
+        if self.game.score != previous_score {
+            self.emit(HostEvent::Score(self.game.score));
+        }
+        if self.game.level() > previous_level {
+            self.emit(HostEvent::LevelUp(self.game.level()));
+        }
+
+        // Tick once per second while a prize is counting down.
+        let prize_second = self
+            .game
+            .prize_ticks_left()
+            .map(|ticks| (ticks as f64 * self.game.move_interval()).ceil() as u32);
+        if prize_second.is_some() && self.prize_second.is_some() && prize_second != self.prize_second {
+            self.audio.play_tick();
+        }
+        self.prize_second = prize_second;
+        let new_record = self.game.new_record && !before.new_record;
+        self.announce(event, new_record, previous_level);
+        if let Some(text) = narrator::describe(self.settings.narration, event, &self.game, &before) {
+            narrator::say(&self.dom, &text);
+        }
+        match event {
+            GameEvent::Eat => {
+                // The head is on whatever was eaten, rush food included.
+                self.audio.play_eat();
+                self.renderer.spawn_particles(self.game.snake.head(), false);
+                self.renderer.cue_head(Cue::Chomp);
+            }
+            GameEvent::EatPrize => {
+                self.audio.play_prize();
+                self.renderer.spawn_particles(old_food_pos, true);
+                self.renderer.cue_head(Cue::Chomp);
+            }
+            GameEvent::GameOver => {
+                self.audio.play_game_over();
+                self.renderer.cue_head(Cue::Crash);
+                if let Some(death) = self.game.death {
+                    self.renderer.spawn_crash(death.at);
+                }
+                self.end_run();
+            }
+            GameEvent::Finished => {
+                self.audio.play_fanfare();
+                #[cfg(not(feature = "minimal"))]
+                speedrun::save_if_best(&self.game.splits);
+                self.end_run();
+            }
+            GameEvent::AllFacesBonus => {
+                self.audio.play_fanfare();
+                self.renderer.spawn_rainbow(old_food_pos);
+                if achievements::unlock(&self.dom, Achievement::GrandTour) {
+                    self.emit(HostEvent::Achievement(Achievement::GrandTour.id()));
+                }
+            }
+            GameEvent::FeastBite => {
+                self.audio.play_eat();
+                self.renderer.spawn_crumbs(self.game.snake.head());
+                self.renderer.cue_head(Cue::Chomp);
+            }
+            GameEvent::RivalCrashed => {
+                self.audio.play_prize();
+                if let Some(pos) = old_rival_pos {
+                    self.renderer.spawn_crash(pos);
+                }
+            }
+            GameEvent::PrizeExpired => {
+                self.audio.play_sound(220.0, 0.2);
+            }
+            GameEvent::ShieldPickup => {
+                self.audio.play_shield();
+            }
+            GameEvent::ShieldBreak => {
+                self.audio.play_shatter();
+                self.renderer.spawn_shatter(self.game.snake.head());
+            }
+            GameEvent::StormClosed => {
+                self.audio.play_rumble();
+            }
+            GameEvent::BossHit => {
+                self.audio.play_prize();
+                self.renderer.spawn_crash(self.game.snake.head());
+            }
+            GameEvent::LifeLost => {
+                self.audio.play_shatter();
+                self.renderer.spawn_shatter(self.game.snake.head());
+            }
+            GameEvent::BossDefeated => {
+                self.audio.play_fanfare();
+                self.renderer.celebrate();
+                show_banner(&self.dom, "world-banner", t("boss_defeated"));
+            }
+            GameEvent::World(world_event) => {
+                self.audio.play_alarm();
+                show_banner(&self.dom, "world-banner", t(world_event_key(world_event)));
+            }
+            GameEvent::None => {}
+        }
+        // Beating the record comes on top of whatever the tick's event was.
+        if new_record {
+            self.audio.play_fanfare();
+            self.renderer.celebrate();
+            show_banner(&self.dom, "record-banner", t("new_record"));
+        }
+    }
+
+    // Spoken cues for the milestones of a tick. A new record outranks a
+    // level up, which outranks a combo.
+    fn announce(&self, event: GameEvent, new_record: bool, previous_level: u32) {
+        let eat = matches!(event, GameEvent::Eat | GameEvent::EatPrize | GameEvent::AllFacesBonus | GameEvent::FeastBite);
+        let streak = self.game.streak;
+        let text = if new_record {
+            t("new_record").to_string()
+        } else if event == GameEvent::GameOver {
+            t("game_over").to_string()
+        } else if self.game.level() > previous_level {
+            t("announce_level").replace("{level}", &self.game.level().to_string())
+        } else if eat && (streak == 3 || (streak >= 5 && streak.is_multiple_of(5))) {
+            t("announce_combo").replace("{combo}", &streak.to_string())
+        } else {
+            return;
+        };
+        self.audio.announce(&text);
+    }
+
+    // Rhythm mode ticks on the music's beats, and the track only plays
+    // while the game runs; so does a boss's. Returns the audio time while
+    // the beat clock runs and how far into the beat it is.
+    #[cfg(not(feature = "minimal"))]
+    fn play_music(&mut self, paused: bool) -> (Option<f64>, Option<f64>) {
+        let beat_clock = match self.audio.current_time() {
+            Some(now) if self.game.config.rhythm && !paused && !self.game.game_over => Some(now),
+            _ => None,
+        };
+        let beat_phase = match beat_clock {
+            Some(now) => {
+                let sequencer = self.sequencer.get_or_insert_with(|| Sequencer::new(now, self.season));
+                sequencer.schedule(&self.audio, now);
+                Some(sequencer.phase(now))
+            }
+            None => {
+                self.sequencer = None;
+                None
+            }
+        };
+        match self.audio.current_time() {
+            Some(now) if self.game.boss.is_some() && !paused && !self.game.game_over => {
+                self.boss_music.get_or_insert_with(|| Sequencer::boss(now)).schedule(&self.audio, now);
+            }
+            _ => self.boss_music = None,
+        }
+        (beat_clock, beat_phase)
+    }
+
+    // On-beat turns in rhythm mode get a click back.
+    fn steer(&mut self, dir: Direction, beat_phase: Option<f64>) {
+        if let Some(replay) = &mut self.replay {
+            replay.record(self.game.ticks, dir);
+        }
+        match beat_phase {
+            Some(phase) => {
+                if self.game.steer_on_beat(dir, phase) {
+                    self.audio.play_tick();
+                }
+            }
+            None => self.game.steer(dir),
+        }
+    }
+
+    fn apply_settings(&mut self, settings: Settings, season: Option<Season>) {
+        self.audio.set_volume(settings.effective_sfx_volume());
+        self.audio.set_announcer(settings.announcer);
+        self.audio.set_sound_pack(settings.sound_pack);
+
+        settings.apply_to_document();
+
+        let rebuild = self.season != season
+            || settings.grid_size != self.settings.grid_size
+            || settings.theme != self.settings.theme
+            || settings.skin != self.settings.skin
+            || settings.high_contrast != self.settings.high_contrast
+            || settings.graphics != self.settings.graphics
+            || settings.ambient_occlusion != self.settings.ambient_occlusion;
+        let grid_changed = settings.grid_size != self.settings.grid_size;
+        self.settings = settings;
+        self.season = season;
+
+        if rebuild {
+            self.rebuild_renderer();
+        }
+        self.renderer.set_projection(self.settings.projection);
+        self.renderer.set_far_side_view(self.settings.far_side_view);
+        self.renderer.set_food_hint(self.settings.food_hint);
+        // Turning the hint on partway through still marks the run.
+        self.assisted |= self.settings.food_hint && !self.game.config.mode.competitive();
+        self.renderer.set_reduced_motion(self.settings.effective_reduced_motion());
+        if grid_changed {
+            self.restart();
+        }
+    }
+
+    #[cfg(feature = "ui")]
+    fn play_level(&mut self, level: LevelDefinition) {
+        {
+            let mut controls = self.controls.borrow_mut();
+            controls.level = Some(level);
+            controls.campaign = None;
+        }
+        self.state = AppState::Playing;
+        self.renderer.set_cursor(None);
+        self.restart();
+    }
+
+    // The board is sized for the run on it, which a level may set apart
+    // from the settings.
+    fn rebuild_renderer(&mut self) {
+        let settings = Settings { grid_size: self.game.config.grid_size, ..self.settings.clone() };
+        self.renderer = GameRenderer::new(self.context.clone(), &settings, self.season, &self.models);
+        self.renderer.set_render_scale(self.resolution.scale());
+    }
+
+    fn fit_renderer(&mut self) {
+        if self.renderer.grid_size() != self.game.config.grid_size {
+            self.rebuild_renderer();
+        }
+    }
+
+    // Shows the editor's level as it stands, rebuilt whenever it changes,
+    // and applies the editing keys: arrows (or WASD) move the cursor, O
+    // places or removes an obstacle, P places portal ends.
+    #[cfg(feature = "editor")]
+    fn edit(&mut self, events: &[Event]) {
+        let mut controls = self.controls.borrow_mut();
+        let editor = &mut controls.editor;
+        if self.preview.as_ref() != Some(&editor.level) {
+            self.game = GameState::with_level(&editor.level, 0);
+            self.prev_body = self.game.snake.body.iter().copied().collect();
+            self.preview = Some(editor.level.clone());
+        }
+        for event in events {
+            if let Event::KeyPress { kind, handled: false, .. } = event {
+                match kind {
+                    Key::O => editor.toggle_obstacle(),
+                    Key::P => editor.place_portal(),
+                    _ => {
+                        if let Some(dir) = key_direction(*kind, self.settings.control_scheme) {
+                            editor.move_cursor(dir, &self.game);
+                        }
+                    }
+                }
+            }
+        }
+        let cursor = editor.cursor;
+        drop(controls);
+        self.fit_renderer();
+        self.renderer.set_cursor(Some(cursor));
+    }
+
+    fn record_timings(&mut self, frame_ms: f64, update_ms: f64, render_ms: f64) {
+        if self.resolution.record(frame_ms) {
+            log::info!("Render scale: {:.1}", self.resolution.scale());
+            self.renderer.set_render_scale(self.resolution.scale());
+        }
+        let upload_ms = self.renderer.upload_ms();
+        let mut controls = self.controls.borrow_mut();
+        controls.profiler.record(frame_ms, update_ms, render_ms - upload_ms, upload_ms);
+        if controls.debug_overlay && controls.profiler.overlay_due() {
+            profiler::update_overlay(&self.dom, &controls.profiler.stats());
+        }
+    }
+
+    pub fn frame(&mut self, frame_input: FrameInput) -> FrameOutput {
+        if !self.has_logged {
+            log::info!("Viewport: {:?}", frame_input.viewport);
+            self.has_logged = true;
+        }
+        let frame_start = profiler::now();
+        let mut events = frame_input.events.clone(); // Clone events to pass to camera and handle locally
+
+        // Check for restart request from UI, or a mode switch from the host page
+        let (restart_requested, mode_changed, paused) = {
+            let mut controls = self.controls.borrow_mut();
+            let restart = std::mem::take(&mut controls.restart_requested);
+            // Level runs are always classic.
+            let mode_changed = controls.level.is_none() && controls.mode != self.game.config.mode;
+            (restart, mode_changed, controls.paused)
+        };
+        if (restart_requested && self.game.game_over) || mode_changed {
+            self.restart();
+        }
+
+        // A season starting or ending (the server's calendar can arrive
+        // late) rebuilds the renderer just like a theme change.
+        let new_settings = {
+            let mut controls = self.controls.borrow_mut();
+            if std::mem::take(&mut controls.settings_changed) || controls.season != self.season {
+                Some((controls.settings.clone(), controls.season))
+            } else {
+                None
+            }
+        };
+        if let Some((settings, season)) = new_settings {
+            self.apply_settings(settings, season);
+        }
+        let models = {
+            let mut controls = self.controls.borrow_mut();
+            std::mem::take(&mut controls.models_changed).then(|| controls.models.clone())
+        };
+        if let Some(models) = models {
+            self.models = models;
+            self.rebuild_renderer();
+        }
+
+        // Coming back to the tab: the run waits in the pause menu rather than
+        // carrying on, and doesn't try to catch up on the time away.
+        if std::mem::take(&mut self.controls.borrow_mut().page_was_hidden) {
+            self.ticks.reset();
+            if self.state == AppState::Playing && !self.game.game_over {
+                self.gui.pause();
+            }
+        }
+
+        // Menus get first pick of the input and pause the game while open.
+        match self.gui.update(&mut events, &frame_input, &self.game, &self.controls) {
+            Some(MenuAction::Play) => {
+                self.state = AppState::Playing;
+                self.restart();
+            }
+            #[cfg(feature = "ui")]
+            Some(MenuAction::Restart) => self.restart(),
+            #[cfg(feature = "ui")]
+            Some(MenuAction::Rewind) => self.rewind(),
+            #[cfg(feature = "ui")]
+            Some(MenuAction::MainMenu) => {
+                self.controls.borrow_mut().campaign = None;
+                #[cfg(feature = "editor")]
+                let editing = self.state == AppState::Editing;
+                #[cfg(not(feature = "editor"))]
+                let editing = false;
+                let left_level = editing || self.controls.borrow_mut().level.take().is_some();
+                self.state = AppState::Menu;
+                self.renderer.set_cursor(None);
+                if left_level {
+                    self.restart();
+                } else {
+                    self.renderer.start_transition(TransitionKind::Fade);
+                }
+            }
+            #[cfg(feature = "editor")]
+            Some(MenuAction::Edit) => {
+                self.state = AppState::Editing;
+                self.renderer.start_transition(TransitionKind::Fade);
+            }
+            #[cfg(feature = "editor")]
+            Some(MenuAction::TestLevel) => {
+                let level = self.controls.borrow().editor.level.clone();
+                self.play_level(level);
+            }
+            #[cfg(feature = "ui")]
+            Some(MenuAction::PlayLevel(level)) => self.play_level(level),
+            #[cfg(feature = "ui")]
+            Some(MenuAction::PlayCampaign(i)) => {
+                if let Some(level) = campaign::levels().get(i) {
+                    self.play_level(level.definition());
+                    self.controls.borrow_mut().campaign = Some(i);
+                }
+            }
+            None => {}
+        }
+        #[cfg(feature = "editor")]
+        if self.state == AppState::Editing {
+            self.edit(&events);
+        }
+        self.renderer.set_showcase(self.state == AppState::Menu);
+        let paused = paused || self.gui.is_open();
+
+        #[cfg(not(feature = "minimal"))]
+        let (beat_clock, beat_phase) = self.play_music(paused);
+        #[cfg(feature = "minimal")]
+        let (beat_clock, beat_phase): (Option<f64>, Option<f64>) = (None, None);
+
+        // Handle Input
+        // Check mobile input
+        let mobile_dir = self.controls.borrow_mut().mobile_input.take();
+        if let Some(dir) = mobile_dir {
+            self.audio.resume_context(); // Resume on mobile interaction too
+            if !paused {
+                self.steer(dir, beat_phase);
+            }
+        }
+
+        for event in &events {
+            if let Event::KeyPress { kind, handled, .. } = event {
+                // Resume audio context on first interaction
+                self.audio.resume_context();
+                // Typed into an in-canvas menu.
+                if paused || *handled {
+                    continue;
+                }
+
+                // In co-op the arrows steer the first snake and WASD the second.
+                #[cfg(not(feature = "minimal"))]
+                if self.game.partner.is_some() {
+                    if let Some(dir) = key_direction(*kind, ControlScheme::Wasd) {
+                        self.game.steer_partner(dir);
+                        continue;
+                    }
+                }
+                let scheme = if self.game.partner.is_some() { ControlScheme::Arrows } else { self.settings.control_scheme };
+                if let Some(dir) = key_direction(*kind, scheme) {
+                    self.steer(dir, beat_phase);
+                    continue;
+                }
+
+                if *kind == Key::R && self.game.game_over {
+                    self.restart();
+                }
+                if *kind == Key::Backspace {
+                    self.rewind();
+                }
+            }
+        }
+
+        // Update Camera
+        self.renderer.update_camera(&mut events);
+        self.renderer.resize(frame_input.viewport.width, frame_input.viewport.height);
+
+        // Update Game Logic
+        // Fixed timestep: run as many whole ticks as the elapsed time covers,
+        // carrying the remainder over to the next frame.
+        #[cfg(not(feature = "minimal"))]
+        let beats = match (beat_clock, &mut self.sequencer) {
+            (Some(now), Some(sequencer)) => Some(sequencer.take_beats(now)),
+            _ => None,
+        };
+        #[cfg(feature = "minimal")]
+        let beats: Option<Vec<f64>> = None;
+        // Each tick's sounds are scheduled for when it was due rather than
+        // when the frame got to it.
+        if let Some(beats) = beats {
+            for when in beats.into_iter().take(self.ticks.max_steps as usize) {
+                if self.game.game_over {
+                    break;
+                }
+                self.audio.set_event_time(Some(when));
+                self.tick();
+            }
+        } else if !paused && !self.game.game_over {
+            self.ticks.advance(frame_input.elapsed_time / 1000.0); // elapsed_time is ms
+            self.clock += frame_input.elapsed_time / 1000.0;
+            while !self.game.game_over && self.ticks.tick(self.game.move_interval()) {
+                self.audio.sync_to_game_time(self.clock - self.ticks.banked());
+                self.tick();
+            }
+        }
+        self.audio.set_event_time(None);
+        // How far we are between the last tick and the next one.
+        let alpha = if self.game.game_over {
+            1.0
+        } else if let Some(phase) = beat_phase {
+            phase as f32
+        } else {
+            self.ticks.alpha(self.game.move_interval())
+        };
+
+        self.renderer.set_beat(beat_phase.map(|phase| phase as f32));
+        self.renderer.set_music_level(if beat_clock.is_some() { self.audio.music_level() } else { 0.0 });
+
+        // Update UI
+        if let Err(e) = update_ui(&self.dom, &self.game)
+            .and_then(|_| bests::update_hud(&self.dom, self.personal_best, self.game.score, self.game.game_over))
+        {
+            log::error!("Failed to update UI: {}", e);
+        }
+        #[cfg(not(feature = "minimal"))]
+        if let Err(e) = self.speedrun.update(&self.dom, &self.game) {
+            log::error!("Failed to update UI: {}", e);
+        }
+        chat::update_vote_bar(&self.dom, self.controls.borrow().votes.as_ref());
+
+        // Render
+        let render_start = profiler::now();
+        let screen = frame_input.screen();
+        self.renderer.render(&self.game, &self.prev_body, alpha, &screen, frame_input.elapsed_time / 1000.0);
+        screen.write(|| self.gui.render());
+        self.renderer.render_transition(&screen);
+        let render_end = profiler::now();
+        self.record_timings(frame_input.elapsed_time, render_start - frame_start, render_end - render_start);
+
+        if std::mem::take(&mut self.controls.borrow_mut().share_requested) {
+            let (pixels, width, height) = self.renderer.read_pixels(&screen);
+            if let Err(e) = crate::share::share_card(pixels, width, height, self.game.score) {
+                log::error!("Share failed: {:?}", e);
+            }
+        }
+
+        // Clips follow the run, up to its end.
+        let recording = self.settings.record_clips && self.state == AppState::Playing && !self.game.game_over;
+        let now = frame_input.accumulated_time / 1000.0;
+        if recording && self.capture.due(now) {
+            let (pixels, width, height) = self.renderer.read_pixels(&screen);
+            self.capture.record(&pixels, width, height, now);
+        }
+        if std::mem::take(&mut self.controls.borrow_mut().clip_requested) {
+            match self.capture.gif() {
+                Some(gif) => {
+                    if let Err(e) = crate::share::share_clip(&gif, self.game.score) {
+                        log::error!("Saving the clip failed: {:?}", e);
+                    }
+                }
+                None => log::warn!("No clip to save; Record Clips is off or nothing was played"),
+            }
+        }
+
+        FrameOutput::default()
+    }
+}
+
+fn key_direction(key: Key, scheme: ControlScheme) -> Option<Direction> {
+    let arrows = scheme != ControlScheme::Wasd;
+    let wasd = scheme != ControlScheme::Arrows;
+    match key {
+        Key::ArrowUp if arrows => Some(Direction::Up),
+        Key::ArrowDown if arrows => Some(Direction::Down),
+        Key::ArrowLeft if arrows => Some(Direction::Left),
+        Key::ArrowRight if arrows => Some(Direction::Right),
+        Key::W if wasd => Some(Direction::Up),
+        Key::S if wasd => Some(Direction::Down),
+        Key::A if wasd => Some(Direction::Left),
+        Key::D if wasd => Some(Direction::Right),
+        _ => None,
+    }
+}
+
+// A fresh run and its replay. Runs with host pacing get no replay, since
+// the pace moves prize and event timings and replays don't record it; the
+// same goes for runs the director paces.
+fn new_game(
+    grid_size: i32,
+    mode: GameMode,
+    speed: Option<SpeedCurve>,
+    seed: Option<u64>,
+    level: Option<&LevelDefinition>,
+    directed: bool,
+) -> (GameState, Option<Replay>) {
+    let seed = seed.unwrap_or_else(random_seed);
+    // Replays don't carry a layout either.
+    if let Some(level) = level {
+        return (GameState::with_level(level, seed), None);
+    }
+    let mut config = GameConfig::with_mode(grid_size, mode);
+    if let Some(speed) = speed {
+        config.speed = speed;
+    }
+    // Nor the second player's turns.
+    let replay = (speed.is_none() && !directed && !mode.coop()).then(|| Replay::new(seed, grid_size, mode));
+    (GameState::with_config(config, seed), replay)
+}
+
+fn random_seed() -> u64 {
+    let mut buf = [0u8; 8];
+    getrandom::getrandom(&mut buf).unwrap_or(());
+    u64::from_le_bytes(buf)
+}
+
+fn world_event_key(event: WorldEvent) -> &'static str {
+    match event {
+        WorldEvent::FoodRush => "event_food_rush",
+        WorldEvent::Quake => "event_quake",
+        WorldEvent::Blackout => "event_blackout",
+    }
+}
+
+fn show_banner(dom: &Dom, id: &str, text: &str) {
+    let banner = match dom.get(id) {
+        Some(banner) => banner,
+        None => return,
+    };
+    banner.set_text_content(Some(text));
+    banner.class_list().remove_1("hidden").unwrap_or(());
+
+    let hide = Closure::once(move || {
+        banner.class_list().add_1("hidden").unwrap_or(());
+    });
+    if let Ok(window) = error::window() {
+        let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(hide.as_ref().unchecked_ref(), BANNER_MS);
+    }
+    hide.forget();
+}
+
+// Minimal builds (see Cargo.toml) carry classic solo play only.
+pub fn mode_built(mode: GameMode) -> bool {
+    !cfg!(feature = "minimal") || mode == GameMode::Classic
+}
+
+pub fn face_key(face: Face) -> &'static str {
+    match face {
+        Face::Front => "face_front",
+        Face::Back => "face_back",
+        Face::Left => "face_left",
+        Face::Right => "face_right",
+        Face::Top => "face_top",
+        Face::Bottom => "face_bottom",
+    }
+}
+
+// What ended a lost run, for the end screen and the screen reader: "Ran into
+// its own body, segment 4 on the Left face at (3, 7)".
+pub fn death_text(death: &DeathInfo) -> String {
+    let mut text = t(match death.cause {
+        DeathCause::Snake => "death_snake",
+        DeathCause::Hazard => "death_hazard",
+        DeathCause::Turret => "death_turret",
+        DeathCause::Wall => "death_wall",
+        DeathCause::Storm => "death_storm",
+        DeathCause::Boss => "death_boss",
+    })
+    .to_string();
+    // Segment 0 is the head itself, which needs no number.
+    if let Some(segment) = death.segment.filter(|&segment| segment > 0) {
+        text = t("death_segment").replace("{cause}", &text).replace("{segment}", &segment.to_string());
+    }
+    t("death_at")
+        .replace("{cause}", &text)
+        .replace("{face}", t(face_key(death.at.face)))
+        .replace("{u}", &death.at.u.to_string())
+        .replace("{v}", &death.at.v.to_string())
+}
+
+// Where `target` lies as seen from the face the camera is on: an arrow
+// towards the edge it's over, a dot for this face and a ring for the far side.
+fn compass_arrow(from: Face, target: Face) -> &'static str {
+    let (axis_u, axis_v) = renderer::face_axes(from);
+    let (target_u, target_v) = renderer::face_axes(target);
+    let normal = target_u.cross(target_v);
+    if normal.dot(axis_v) > 0.5 {
+        "↑"
+    } else if normal.dot(axis_v) < -0.5 {
+        "↓"
+    } else if normal.dot(axis_u) > 0.5 {
+        "→"
+    } else if normal.dot(axis_u) < -0.5 {
+        "←"
+    } else if from == target {
+        "●"
+    } else {
+        "○"
+    }
+}
+
+fn update_ui(dom: &Dom, game: &GameState) -> error::Result<()> {
+    // The camera follows the head, so its face is the one being looked at.
+    let head_face = game.snake.head().face;
+    if let Some(face_el) = dom.get("compass-face") {
+        face_el.set_text_content(Some(t(face_key(head_face))));
+    }
+    if let Some(food_el) = dom.get("compass-food") {
+        food_el.set_text_content(Some(compass_arrow(head_face, game.food.face)));
+    }
+
+    if let Some(score_el) = dom.get("score") {
+        score_el.set_inner_html(&game.score.to_string());
+    }
+
+    if let Some(high_score_el) = dom.get("high-score") {
+        high_score_el.set_inner_html(&game.high_score.to_string());
+        if let Some(container) = dom.get("high-score-container") {
+             container.class_list().remove_1("hidden").unwrap_or(());
+        }
+    }
+
+    // Shared lives, in co-op.
+    if let Some(container) = dom.get("lives-container") {
+        container.class_list().toggle_with_force("hidden", game.partner.is_none())?;
+        if let Some(lives_el) = dom.get("lives") {
+            lives_el.set_text_content(Some(&game.lives.to_string()));
+        }
+    }
+
+    // Streak counter, with a bar that drains until the streak lapses.
+    if let Some(container) = dom.get("streak-container") {
+        let active = game.streak > 1 && !game.game_over;
+        container.class_list().toggle_with_force("hidden", !active)?;
+        if active {
+            if let Some(streak_el) = dom.get("streak") {
+                streak_el.set_text_content(Some(&format!("x{}", game.streak)));
+            }
+            if let Some(fill) = dom.get("streak-bar-fill") {
+                fill.set_attribute("style", &format!("width: {:.0}%", game.streak_remaining() * 100.0))?;
+            }
+        }
+    }
+
+    if let Some(game_over_el) = dom.get("game-over") {
+        let class_list = game_over_el.class_list();
+        if game.game_over {
+            class_list.remove_1("hidden")?;
+            if let Some(hint) = dom.get("rewind-hint") {
+                hint.class_list().toggle_with_force("hidden", !game.can_rewind())?;
+            }
+            if let Some(final_score_el) = dom.get("final-score") {
+                #[cfg(not(feature = "minimal"))]
+                let text = if game.finished {
+                    t("finish_time").replace("{time}", &speedrun::format_time(game.elapsed))
+                } else {
+                    t("final_score").replace("{score}", &game.score.to_string())
+                };
+                #[cfg(feature = "minimal")]
+                let text = t("final_score").replace("{score}", &game.score.to_string());
+                final_score_el.set_inner_html(&text);
+            }
+            if let Some(death_el) = dom.get("death-cause") {
+                death_el.set_text_content(game.death.as_ref().map(death_text).as_deref());
+            }
+        } else {
+            class_list.add_1("hidden")?;
+        }
+    }
+    Ok(())
+}
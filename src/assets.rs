@@ -0,0 +1,66 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+// Static assets compiled into the wasm binary, so the game runs offline and
+// doesn't care which path the host serves it from. Add new files here.
+const EMBEDDED: &[(&str, &[u8])] = &[
+    ("themes.json", include_bytes!("../assets/themes.json")),
+    ("seasons.json", include_bytes!("../assets/seasons.json")),
+    ("campaign.json", include_bytes!("../assets/campaign.json")),
+];
+
+thread_local! {
+    // Decoded assets, keyed by file name. Decoding happens on first use.
+    static CACHE: RefCell<HashMap<&'static str, Rc<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+pub fn bytes(name: &str) -> Option<&'static [u8]> {
+    EMBEDDED.iter().find(|(n, _)| *n == name).map(|(_, b)| *b)
+}
+
+// Returns the decoded asset, decoding and caching it on first request.
+fn cached<T: 'static>(name: &'static str, decode: impl FnOnce(&'static [u8]) -> Option<T>) -> Option<Rc<T>> {
+    let hit = CACHE.with(|c| c.borrow().get(name).cloned());
+    if let Some(hit) = hit {
+        return hit.downcast::<T>().ok();
+    }
+
+    let value = Rc::new(decode(bytes(name)?)?);
+    CACHE.with(|c| c.borrow_mut().insert(name, value.clone() as Rc<dyn Any>));
+    Some(value)
+}
+
+pub fn json<T: DeserializeOwned + 'static>(name: &'static str) -> Option<Rc<T>> {
+    cached(name, |bytes| match serde_json::from_slice(bytes) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            log::error!("Failed to decode embedded asset {}: {}", name, e);
+            None
+        }
+    })
+}
+
+// One entry of `themes.json`. Colors are RGBA bytes, `clear` and the two
+// background `nebula` colors are RGB in 0..1.
+#[derive(Deserialize, Clone, Debug)]
+pub struct ThemeDef {
+    pub board: [u8; 4],
+    pub board_emissive: [u8; 4],
+    pub grid: [u8; 4],
+    pub grid_emissive: [u8; 4],
+    pub snake: [u8; 4],
+    pub snake_emissive: [u8; 4],
+    pub food: [u8; 4],
+    pub food_emissive: [u8; 4],
+    pub clear: [f32; 3],
+    pub nebula: [[f32; 3]; 2],
+}
+
+pub fn theme(name: &str) -> Option<ThemeDef> {
+    json::<HashMap<String, ThemeDef>>("themes.json")?.get(name).cloned()
+}
@@ -0,0 +1,135 @@
+use three_d::*;
+
+use crate::seasons::Season;
+
+// Every pickup on the board - food, prizes, rush food and the shield - is
+// drawn from one instanced sphere with one material. What each one looks
+// like comes from its tile in a small texture atlas, picked per instance
+// with a UV transform, so adding an item adds a tile rather than a draw call.
+
+// Texels along each side of a tile.
+const TILE: u32 = 32;
+// Tiles along each side of the atlas.
+const GRID: u32 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tile {
+    Food,
+    Prize,
+    Shield,
+    Pumpkin,
+}
+
+impl Tile {
+    // Where the tile sits in the atlas, as a transform from a mesh's 0..1 UVs.
+    pub fn uv_transform(self) -> Mat3 {
+        let index = self as u32;
+        let scale = 1.0 / GRID as f32;
+        let (col, row) = ((index % GRID) as f32, (index / GRID) as f32);
+        Mat3::new(scale, 0.0, 0.0, 0.0, scale, 0.0, col * scale, row * scale, 1.0)
+    }
+}
+
+// The tile regular food uses this season.
+pub fn food_tile(season: Option<Season>) -> Tile {
+    if season == Some(Season::Halloween) {
+        Tile::Pumpkin
+    } else {
+        Tile::Food
+    }
+}
+
+// Paints the albedo and emissive atlases. Food takes the theme's colors,
+// so a new theme means new atlases.
+pub fn build(food: Srgba, food_emissive: Srgba) -> (CpuTexture, CpuTexture) {
+    let albedo = paint("pickups", |tile, u, v| texel(tile, food, u, v));
+    let emissive = paint("pickups_emissive", |tile, _, _| {
+        let c = glow(tile, food_emissive);
+        [c.r, c.g, c.b, 255]
+    });
+    (albedo, emissive)
+}
+
+fn paint(name: &str, texel: impl Fn(Tile, f32, f32) -> [u8; 4]) -> CpuTexture {
+    let size = TILE * GRID;
+    let mut data = vec![[0u8; 4]; (size * size) as usize];
+    for tile in [Tile::Food, Tile::Prize, Tile::Shield, Tile::Pumpkin] {
+        let index = tile as u32;
+        let (x0, y0) = ((index % GRID) * TILE, (index / GRID) * TILE);
+        for y in 0..TILE {
+            for x in 0..TILE {
+                // Tile-local coordinates, 0..1 around the sphere and pole to pole.
+                let (u, v) = ((x as f32 + 0.5) / TILE as f32, (y as f32 + 0.5) / TILE as f32);
+                data[((y0 + y) * size + x0 + x) as usize] = texel(tile, u, v);
+            }
+        }
+    }
+    CpuTexture {
+        name: name.to_string(),
+        data: TextureData::RgbaU8(data),
+        width: size,
+        height: size,
+        min_filter: Interpolation::Linear,
+        mag_filter: Interpolation::Linear,
+        // Mipmaps would bleed neighbouring tiles together.
+        mip_map_filter: None,
+        wrap_s: Wrapping::ClampToEdge,
+        wrap_t: Wrapping::ClampToEdge,
+    }
+}
+
+// Each kind glows in a flat color of its own.
+fn glow(tile: Tile, food_emissive: Srgba) -> Srgba {
+    match tile {
+        Tile::Food | Tile::Pumpkin => food_emissive,
+        Tile::Prize => Srgba::new_opaque(100, 80, 0),
+        Tile::Shield => Srgba::new_opaque(0, 90, 110),
+    }
+}
+
+fn texel(tile: Tile, food: Srgba, u: f32, v: f32) -> [u8; 4] {
+    let shade = |c: Srgba, k: f32| {
+        let f = |x: u8| (x as f32 * k).clamp(0.0, 255.0) as u8;
+        [f(c.r), f(c.g), f(c.b), 255]
+    };
+    match tile {
+        // A lighter cap at the top, like a bit of shine.
+        Tile::Food => shade(food, if v < 0.2 { 1.3 } else { 1.0 }),
+        // Gold with two darker bands around the middle.
+        Tile::Prize => {
+            let band = (v - 0.4).abs() < 0.05 || (v - 0.6).abs() < 0.05;
+            shade(Srgba::new_opaque(255, 215, 0), if band { 0.7 } else { 1.0 })
+        }
+        // Cyan with a bright lattice.
+        Tile::Shield => {
+            let line = (u * 8.0).fract() < 0.15 || (v * 6.0).fract() < 0.15;
+            if line { [200, 250, 255, 255] } else { shade(Srgba::new_opaque(0, 220, 255), 0.8) }
+        }
+        // Orange ribs and a green stalk.
+        Tile::Pumpkin => {
+            if v < 0.12 {
+                [40, 140, 30, 255]
+            } else {
+                let rib = 0.75 + 0.25 * (u * std::f32::consts::TAU * 8.0).cos().abs();
+                shade(food, rib)
+            }
+        }
+    }
+}
+
+// Spherical UVs for a mesh around the origin, which the built-in sphere
+// doesn't come with: u runs around the z axis, v from pole to pole.
+pub fn sphere_uvs(mesh: &mut CpuMesh) {
+    let positions = mesh.positions.to_f32();
+    mesh.uvs = Some(
+        positions
+            .iter()
+            .map(|p| {
+                let p = p.normalize();
+                let u = p.y.atan2(p.x) / std::f32::consts::TAU + 0.5;
+                let v = p.z.clamp(-1.0, 1.0).acos() / std::f32::consts::PI;
+                vec2(u, v)
+            })
+            .collect(),
+    );
+}
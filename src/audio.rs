@@ -1,13 +1,99 @@
-use web_sys::{AudioContext, OscillatorType};
+use web_sys::{AnalyserNode, AudioContext, AudioNode, OscillatorType};
+
+use std::cell::Cell;
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+
+use crate::error;
+use crate::i18n;
+use crate::settings::SoundPack;
+
+// Gameplay sounds are scheduled this far behind the tick that caused them,
+// so a tick that ran late in its frame still lands on time.
+const TICK_LATENCY: f64 = 0.05;
+// Past this the mapping from game time has drifted (pause, stall) and is
+// taken afresh.
+const MAX_AHEAD: f64 = 0.25;
 
 pub struct AudioPlayer {
     context: Option<AudioContext>,
+    volume: f32,
+    // Spoken cues through the browser's speech synthesis.
+    announcer: bool,
+    sound_pack: SoundPack,
+    // Music goes through this on its way out, so visuals can follow it.
+    analyser: Option<AnalyserNode>,
+    spectrum: Vec<u8>,
+    // Audio time minus game time, once the two clocks have been lined up.
+    clock_offset: Option<f64>,
+    // When sounds played now should start; `None` is right away.
+    event_time: Option<f64>,
 }
 
 impl AudioPlayer {
     pub fn new() -> Self {
         let context = AudioContext::new().ok();
-        Self { context }
+        let analyser = context.as_ref().and_then(|ctx| {
+            let analyser = ctx.create_analyser().ok()?;
+            analyser.set_fft_size(64);
+            analyser.set_smoothing_time_constant(0.6);
+            analyser.connect_with_audio_node(&ctx.destination()).ok()?;
+            Some(analyser)
+        });
+        let spectrum = vec![0; analyser.as_ref().map_or(0, |a| a.frequency_bin_count() as usize)];
+        Self {
+            context,
+            volume: 1.0,
+            announcer: false,
+            sound_pack: SoundPack::Classic,
+            analyser,
+            spectrum,
+            clock_offset: None,
+            event_time: None,
+        }
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+    }
+
+    pub fn set_announcer(&mut self, announcer: bool) {
+        self.announcer = announcer;
+    }
+
+    pub fn set_sound_pack(&mut self, sound_pack: SoundPack) {
+        self.sound_pack = sound_pack;
+    }
+
+    // Chiptune plays every effect as a square wave an octave up. Music
+    // keeps its own voice.
+    fn effect_voice(&self, freq: f32) -> (OscillatorType, f32) {
+        match self.sound_pack {
+            SoundPack::Classic => (OscillatorType::Sine, freq),
+            SoundPack::Chiptune => (OscillatorType::Square, freq * 2.0),
+        }
+    }
+
+    // Speaks `text` in the current language. A newer cue cuts off the one
+    // still playing rather than queueing behind it.
+    pub fn announce(&self, text: &str) {
+        if !self.announcer || self.volume <= 0.0 {
+            return;
+        }
+        let speech = match web_sys::window().and_then(|w| w.speech_synthesis().ok()) {
+            Some(speech) => speech,
+            None => return,
+        };
+        let utterance = match web_sys::SpeechSynthesisUtterance::new_with_text(text) {
+            Ok(utterance) => utterance,
+            Err(_) => return,
+        };
+        utterance.set_lang(i18n::language().code());
+        utterance.set_volume(self.volume);
+        utterance.set_rate(1.2);
+        speech.cancel();
+        speech.speak(&utterance);
     }
 
     pub fn resume_context(&self) {
@@ -19,6 +105,9 @@ impl AudioPlayer {
     }
 
     pub fn play_sound(&self, freq: f32, duration: f64) {
+        if self.volume <= 0.0 {
+            return;
+        }
         if let Some(ctx) = &self.context {
             // Create oscillator and gain node
             let oscillator = match ctx.create_oscillator() {
@@ -35,12 +124,13 @@ impl AudioPlayer {
             let _ = gain_node.connect_with_audio_node(&ctx.destination());
 
             // Set frequency
+            let (wave, freq) = self.effect_voice(freq);
             oscillator.frequency().set_value(freq);
-            oscillator.set_type(OscillatorType::Sine);
+            oscillator.set_type(wave);
 
             // Volume envelope
-            let now = ctx.current_time();
-            let _ = gain_node.gain().set_value_at_time(0.1, now);
+            let now = self.start_time(ctx);
+            let _ = gain_node.gain().set_value_at_time(0.1 * self.volume, now);
             let _ = gain_node.gain().exponential_ramp_to_value_at_time(0.001, now + duration);
 
             // Start and stop
@@ -59,7 +149,7 @@ impl AudioPlayer {
 
     pub fn play_prize(&self) {
         if let Some(ctx) = &self.context {
-            let now = ctx.current_time();
+            let now = self.start_time(ctx);
             self.play_tone(ctx, 600.0, now, 0.1);
             self.play_tone(ctx, 900.0, now + 0.1, 0.1);
             self.play_tone(ctx, 1200.0, now + 0.2, 0.2);
@@ -68,14 +158,160 @@ impl AudioPlayer {
 
     pub fn play_game_over(&self) {
         if let Some(ctx) = &self.context {
-            let now = ctx.current_time();
+            let now = self.start_time(ctx);
             self.play_tone(ctx, 300.0, now, 0.2);
             self.play_tone(ctx, 200.0, now + 0.2, 0.2);
             self.play_tone(ctx, 100.0, now + 0.4, 0.4);
         }
     }
 
+    // Rising arpeggio for the all-faces bonus.
+    pub fn play_fanfare(&self) {
+        if let Some(ctx) = &self.context {
+            let now = self.start_time(ctx);
+            for (i, freq) in [523.0, 659.0, 784.0, 1047.0, 1319.0].into_iter().enumerate() {
+                self.play_tone(ctx, freq, now + i as f64 * 0.08, 0.12);
+            }
+        }
+    }
+
+    // Short click for the prize countdown.
+    pub fn play_tick(&self) {
+        self.play_sound(1500.0, 0.03);
+    }
+
+    pub fn play_shield(&self) {
+        if let Some(ctx) = &self.context {
+            let now = self.start_time(ctx);
+            self.play_tone(ctx, 400.0, now, 0.08);
+            self.play_tone(ctx, 800.0, now + 0.08, 0.15);
+        }
+    }
+
+    pub fn play_shatter(&self) {
+        if let Some(ctx) = &self.context {
+            let now = self.start_time(ctx);
+            self.play_tone(ctx, 1600.0, now, 0.05);
+            self.play_tone(ctx, 1100.0, now + 0.04, 0.05);
+            self.play_tone(ctx, 700.0, now + 0.08, 0.15);
+        }
+    }
+
+    // Two-tone siren announcing a world event.
+    pub fn play_alarm(&self) {
+        if let Some(ctx) = &self.context {
+            let now = self.start_time(ctx);
+            for i in 0..3 {
+                self.play_tone(ctx, 660.0, now + i as f64 * 0.3, 0.15);
+                self.play_tone(ctx, 440.0, now + i as f64 * 0.3 + 0.15, 0.15);
+            }
+        }
+    }
+
+    // Low rumble as a storm ring closes.
+    pub fn play_rumble(&self) {
+        if let Some(ctx) = &self.context {
+            let now = self.start_time(ctx);
+            self.play_tone(ctx, 80.0, now, 0.4);
+            self.play_tone(ctx, 55.0, now + 0.2, 0.6);
+        }
+    }
+
+    // Suspends the audio clock, and with it every queued sound and the music,
+    // while the page is hidden, and picks up again when it's back. Only a
+    // context that was running gets resumed, as a locked one needs a gesture.
+    pub fn suspend_when_hidden(&self) -> Result<(), wasm_bindgen::JsValue> {
+        let ctx = match &self.context {
+            Some(ctx) => ctx.clone(),
+            None => return Ok(()),
+        };
+        let document = error::document()?;
+        let was_running = Cell::new(false);
+        let closure = Closure::wrap(Box::new(move || {
+            let hidden = error::document().map(|d| d.hidden()).unwrap_or(false);
+            if hidden {
+                was_running.set(ctx.state() == web_sys::AudioContextState::Running);
+                let _ = ctx.suspend();
+            } else if was_running.get() {
+                let _ = ctx.resume();
+            }
+        }) as Box<dyn FnMut()>);
+        document.add_event_listener_with_callback("visibilitychange", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+        Ok(())
+    }
+
+    // Audio clock in seconds, while it's running. Music is timed against
+    // this rather than frame time.
+    pub fn current_time(&self) -> Option<f64> {
+        let ctx = self.context.as_ref()?;
+        (ctx.state() == web_sys::AudioContextState::Running).then(|| ctx.current_time())
+    }
+
+    // Sounds played from here on belong to the tick at `game_time` (seconds
+    // on the game's clock) and start at the matching audio time.
+    pub fn sync_to_game_time(&mut self, game_time: f64) {
+        let now = match self.current_time() {
+            Some(now) => now,
+            None => {
+                self.event_time = None;
+                return;
+            }
+        };
+        let offset = match self.clock_offset {
+            Some(offset) if game_time + offset >= now && game_time + offset <= now + MAX_AHEAD => offset,
+            _ => now + TICK_LATENCY - game_time,
+        };
+        self.clock_offset = Some(offset);
+        self.event_time = Some(game_time + offset);
+    }
+
+    // Sounds played from here on start at `when` on the audio clock, or
+    // right away for `None`.
+    pub fn set_event_time(&mut self, when: Option<f64>) {
+        self.event_time = when;
+    }
+
+    fn start_time(&self, ctx: &AudioContext) -> f64 {
+        let now = ctx.current_time();
+        self.event_time.map_or(now, |when| when.max(now))
+    }
+
+    // Queues a music note at `when` on the audio clock.
+    #[cfg(not(feature = "minimal"))]
+    pub fn schedule_note(&self, freq: f32, when: f64, duration: f64) {
+        if let Some(ctx) = &self.context {
+            match &self.analyser {
+                Some(analyser) => self.play_tone_to(ctx, analyser, freq, when, duration),
+                None => self.play_tone(ctx, freq, when, duration),
+            }
+        }
+    }
+
+    // How loud the music is right now, 0 to 1, weighted to the low end
+    // where the beat sits.
+    pub fn music_level(&mut self) -> f32 {
+        let analyser = match &self.analyser {
+            Some(analyser) => analyser,
+            None => return 0.0,
+        };
+        analyser.get_byte_frequency_data(&mut self.spectrum);
+        let low = &self.spectrum[..self.spectrum.len() / 4];
+        if low.is_empty() {
+            return 0.0;
+        }
+        low.iter().map(|&b| b as f32).sum::<f32>() / (low.len() as f32 * 255.0)
+    }
+
     fn play_tone(&self, ctx: &AudioContext, freq: f32, start_time: f64, duration: f64) {
+        let freq = self.effect_voice(freq).1;
+        self.play_tone_to(ctx, &ctx.destination(), freq, start_time, duration);
+    }
+
+    fn play_tone_to(&self, ctx: &AudioContext, output: &AudioNode, freq: f32, start_time: f64, duration: f64) {
+         if self.volume <= 0.0 {
+             return;
+         }
          let oscillator = match ctx.create_oscillator() {
                 Ok(o) => o,
                 Err(_) => return,
@@ -86,12 +322,12 @@ impl AudioPlayer {
             };
 
             let _ = oscillator.connect_with_audio_node(&gain_node);
-            let _ = gain_node.connect_with_audio_node(&ctx.destination());
+            let _ = gain_node.connect_with_audio_node(output);
 
             oscillator.frequency().set_value(freq);
             oscillator.set_type(OscillatorType::Square);
 
-            let _ = gain_node.gain().set_value_at_time(0.1, start_time);
+            let _ = gain_node.gain().set_value_at_time(0.1 * self.volume, start_time);
             let _ = gain_node.gain().exponential_ramp_to_value_at_time(0.001, start_time + duration);
 
             let _ = oscillator.start_with_when(start_time);
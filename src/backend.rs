@@ -0,0 +1,23 @@
+// Which graphics API draws the game. three-d 0.16 can only render through
+// WebGL2, so that is what draws today. Builds with the `webgpu` feature
+// probe the browser for WebGPU at startup and report it, ready for the
+// switch once three-d grows a WebGPU renderer; until then they fall back
+// to WebGL2 like everyone else.
+
+pub const WEBGL2: &str = "webgl2";
+
+// Whether the browser exposes `navigator.gpu`.
+pub fn webgpu_available() -> bool {
+    web_sys::window()
+        .map(|w| js_sys::Reflect::has(&w.navigator(), &"gpu".into()).unwrap_or(false))
+        .unwrap_or(false)
+}
+
+// The backend to draw with.
+pub fn select() -> &'static str {
+    #[cfg(feature = "webgpu")]
+    if webgpu_available() {
+        log::info!("WebGPU is available, but the renderer has no WebGPU path yet; using WebGL2");
+    }
+    WEBGL2
+}
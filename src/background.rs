@@ -0,0 +1,105 @@
+use three_d::*;
+
+// Animated backdrop drawn behind the scene: a slowly drifting two-color
+// nebula over the theme's clear color, with two layers of twinkling stars.
+// Both shift a little as the camera moves for a sense of depth.
+
+const SHADER: &str = "
+uniform float time;
+uniform vec2 drift;
+uniform vec2 resolution;
+uniform vec3 clearColor;
+uniform vec3 nebulaA;
+uniform vec3 nebulaB;
+
+in vec2 uvs;
+layout (location = 0) out vec4 outColor;
+
+float hash(vec2 p) {
+    return fract(sin(dot(p, vec2(127.1, 311.7))) * 43758.5453);
+}
+
+float noise(vec2 p) {
+    vec2 i = floor(p);
+    vec2 f = fract(p);
+    vec2 u = f * f * (3.0 - 2.0 * f);
+    return mix(mix(hash(i), hash(i + vec2(1.0, 0.0)), u.x),
+               mix(hash(i + vec2(0.0, 1.0)), hash(i + vec2(1.0, 1.0)), u.x), u.y);
+}
+
+// One star in some grid cells, `density` being the fraction that get one.
+float stars(vec2 p, float density) {
+    vec2 cell = floor(p);
+    float h = hash(cell);
+    if (h > density) {
+        return 0.0;
+    }
+    vec2 center = vec2(hash(cell + 3.1), hash(cell + 7.7)) * 0.6 + 0.2;
+    float twinkle = 0.6 + 0.4 * sin(time * 2.0 + h * 60.0);
+    return smoothstep(0.08, 0.0, length(fract(p) - center)) * twinkle;
+}
+
+void main() {
+    vec2 p = uvs * vec2(resolution.x / resolution.y, 1.0);
+    float n = noise(p * 2.0 + drift * 0.2 + vec2(time * 0.02, 0.0)) * 0.6
+            + noise(p * 5.0 - drift * 0.3 + vec2(0.0, time * 0.03)) * 0.4;
+    vec3 color = clearColor + mix(nebulaA, nebulaB, n) * smoothstep(0.3, 0.9, n);
+    color += vec3(stars(p * 40.0 + drift * 2.0, 0.08));
+    color += vec3(stars(p * 80.0 + drift * 4.0, 0.05)) * 0.6;
+    outColor = vec4(color, 1.0);
+}
+";
+
+pub struct Background {
+    pub clear: Vec3,
+    pub nebula: [Vec3; 2],
+    pub time: f32,
+}
+
+impl Effect for Background {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        _color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> String {
+        SHADER.to_string()
+    }
+
+    fn id(&self, _color_texture: Option<ColorTexture>, _depth_texture: Option<DepthTexture>) -> u16 {
+        0b1u16 << 14 | 0b1u16 << 12
+    }
+
+    fn fragment_attributes(&self) -> FragmentAttributes {
+        FragmentAttributes { uv: true, ..FragmentAttributes::NONE }
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        camera: &Camera,
+        _lights: &[&dyn Light],
+        _color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) {
+        // Camera direction as two angles; the star layers slide with it.
+        let pos = camera.position().normalize();
+        let drift = vec2(pos.x.atan2(pos.z), pos.y.asin());
+        let viewport = camera.viewport();
+        program.use_uniform("time", self.time);
+        program.use_uniform("drift", drift);
+        program.use_uniform("resolution", vec2(viewport.width as f32, viewport.height as f32));
+        program.use_uniform("clearColor", self.clear);
+        program.use_uniform("nebulaA", self.nebula[0]);
+        program.use_uniform("nebulaB", self.nebula[1]);
+    }
+
+    // Drawn first and never occludes anything.
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+
+use snake3d_core::GameMode;
+
+use crate::dom::Dom;
+use crate::error;
+
+// Personal bests, one per mode and board size, as a JSON object keyed
+// "classic-10". Level runs keep their own (see `community.rs`).
+const BESTS_KEY: &str = "snake3d_personal_bests";
+
+// The HUD starts comparing the score with the best once it's this far along.
+const CLOSE: f64 = 0.8;
+
+fn key(mode: GameMode, grid_size: i32) -> String {
+    format!("{}-{}", mode.name(), grid_size)
+}
+
+fn load_all() -> HashMap<String, u32> {
+    error::storage()
+        .ok()
+        .and_then(|s| s.get_item(BESTS_KEY).ok().flatten())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+pub fn load(mode: GameMode, grid_size: i32) -> u32 {
+    load_all().get(&key(mode, grid_size)).copied().unwrap_or(0)
+}
+
+// Stores `score` if it beats the saved best for the mode and board size.
+pub fn save_if_best(mode: GameMode, grid_size: i32, score: u32) -> bool {
+    let mut bests = load_all();
+    let best = bests.entry(key(mode, grid_size)).or_insert(0);
+    if score <= *best {
+        return false;
+    }
+    *best = score;
+    if let (Ok(storage), Ok(json)) = (error::storage(), serde_json::to_string(&bests)) {
+        let _ = storage.set_item(BESTS_KEY, &json);
+    }
+    true
+}
+
+// "PB: 84" under the score, and once the run gets close the gap to it,
+// flashing: "-6", then "+2" past it.
+pub fn update_hud(dom: &Dom, best: Option<u32>, score: u32, game_over: bool) -> error::Result<()> {
+    let best = best.filter(|&best| best > 0);
+    if let Some(container) = dom.get("pb-container") {
+        container.class_list().toggle_with_force("hidden", best.is_none())?;
+    }
+    let Some(best) = best else { return Ok(()) };
+    if let Some(pb_el) = dom.get("pb") {
+        pb_el.set_text_content(Some(&best.to_string()));
+    }
+    if let Some(delta_el) = dom.get("pb-delta") {
+        let close = !game_over && score as f64 >= best as f64 * CLOSE;
+        delta_el.class_list().toggle_with_force("hidden", !close)?;
+        delta_el.class_list().toggle_with_force("ahead", score > best)?;
+        if close {
+            delta_el.set_text_content(Some(&format!("{:+}", score as i64 - best as i64)));
+        }
+    }
+    Ok(())
+}
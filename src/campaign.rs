@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use snake3d_core::CampaignLevel;
+
+use crate::assets;
+use crate::error;
+
+// Best stars per campaign level, keyed by its index.
+const PROGRESS_KEY: &str = "snake3d_campaign";
+
+// The campaign ships with the game (assets/campaign.json), in order.
+pub fn levels() -> Rc<Vec<CampaignLevel>> {
+    assets::json::<Vec<CampaignLevel>>("campaign.json").unwrap_or_default()
+}
+
+fn load_progress() -> HashMap<usize, u8> {
+    error::storage()
+        .ok()
+        .and_then(|s| s.get_item(PROGRESS_KEY).ok().flatten())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "ui")]
+pub fn stars(index: usize) -> u8 {
+    load_progress().get(&index).copied().unwrap_or(0)
+}
+
+// A level opens once the one before it has a star.
+#[cfg(feature = "ui")]
+pub fn unlocked(index: usize) -> bool {
+    index == 0 || stars(index - 1) > 0
+}
+
+// Keeps `stars` if it beats this level's best. Returns true if it did.
+pub fn record(index: usize, stars: u8) -> bool {
+    let mut progress = load_progress();
+    let best = progress.entry(index).or_insert(0);
+    if stars <= *best {
+        return false;
+    }
+    *best = stars;
+    if let (Ok(storage), Ok(json)) = (error::storage(), serde_json::to_string(&progress)) {
+        let _ = storage.set_item(PROGRESS_KEY, &json);
+    }
+    true
+}
@@ -0,0 +1,71 @@
+use std::collections::VecDeque;
+
+use crate::gif;
+
+// The last few seconds of play, kept as small GIF-ready frames so a run's
+// end (or any moment, on demand) can be saved as a clip. Frames are read
+// back from the screen a few times a second, scaled down and reduced to the
+// GIF palette straight away, which keeps the buffer to a few megabytes. It
+// costs a readback per captured frame, so it's off unless the player turns
+// on Record Clips.
+
+const SECONDS: f64 = 4.0;
+const FPS: f64 = 10.0;
+// Clip width in pixels; the height follows the screen's shape.
+const WIDTH: u32 = 240;
+
+#[derive(Default)]
+pub struct Capture {
+    frames: VecDeque<Vec<u8>>,
+    size: (u16, u16),
+    last_at: Option<f64>,
+}
+
+impl Capture {
+    // Whether a frame is due at `time`, in seconds.
+    pub fn due(&self, time: f64) -> bool {
+        self.last_at.is_none_or(|at| time - at >= 1.0 / FPS)
+    }
+
+    // Keeps a frame read back from the screen (top row first, RGBA).
+    pub fn record(&mut self, pixels: &[[u8; 4]], width: u32, height: u32, time: f64) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        let out_width = WIDTH.min(width);
+        let out_height = (height * out_width / width).max(1);
+        let size = (out_width as u16, out_height as u16);
+        // A resize starts the clip over.
+        if size != self.size {
+            self.frames.clear();
+            self.size = size;
+        }
+        let mut frame = Vec::with_capacity((out_width * out_height) as usize);
+        for y in 0..out_height {
+            let row = (y * height / out_height) * width;
+            for x in 0..out_width {
+                let [r, g, b, _] = pixels[(row + x * width / out_width) as usize];
+                frame.push(gif::index(r, g, b));
+            }
+        }
+        if self.frames.len() == (SECONDS * FPS) as usize {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame);
+        self.last_at = Some(time);
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.last_at = None;
+    }
+
+    // The frames held, as an animated GIF.
+    pub fn gif(&mut self) -> Option<Vec<u8>> {
+        if self.frames.is_empty() {
+            return None;
+        }
+        let (width, height) = self.size;
+        Some(gif::encode(width, height, self.frames.make_contiguous(), (100.0 / FPS) as u16))
+    }
+}
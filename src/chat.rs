@@ -0,0 +1,91 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use snake3d_core::{Direction, VoteTally};
+#[cfg(feature = "vote-socket")]
+use wasm_bindgen::{prelude::*, JsCast};
+
+use crate::app::Controls;
+use crate::dom::Dom;
+#[cfg(feature = "vote-socket")]
+use crate::error;
+
+// Wait before reconnecting to a chat bridge that dropped.
+#[cfg(feature = "vote-socket")]
+const RECONNECT_MS: i32 = 5000;
+
+// Adds a vote from any source: the chat socket below or `game.vote()`.
+// Messages that aren't a direction are ignored, so a bridge can pass chat
+// through unfiltered.
+pub fn vote(controls: &Rc<RefCell<Controls>>, text: &str) -> bool {
+    let dir = match Direction::from_name(text) {
+        Some(dir) => dir,
+        None => return false,
+    };
+    match controls.borrow_mut().votes.as_mut() {
+        Some(votes) => {
+            votes.add(dir);
+            true
+        }
+        None => false,
+    }
+}
+
+// Takes votes from a WebSocket, one per text message ("up", "!left", ...),
+// reconnecting whenever it closes.
+#[cfg(feature = "vote-socket")]
+pub fn connect(url: String, controls: Rc<RefCell<Controls>>) -> Result<(), JsValue> {
+    let socket = web_sys::WebSocket::new(&url)?;
+
+    let on_message = {
+        let controls = controls.clone();
+        Closure::wrap(Box::new(move |event: web_sys::MessageEvent| {
+            if let Some(text) = event.data().as_string() {
+                vote(&controls, &text);
+            }
+        }) as Box<dyn FnMut(_)>)
+    };
+    socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    on_message.forget();
+
+    let on_close = Closure::once(move || {
+        log::warn!("Vote socket closed; reconnecting in {}s", RECONNECT_MS / 1000);
+        let retry = Closure::once(move || {
+            if let Err(e) = connect(url, controls) {
+                log::error!("Vote socket failed: {:?}", e);
+            }
+        });
+        if let Ok(window) = error::window() {
+            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(retry.as_ref().unchecked_ref(), RECONNECT_MS);
+        }
+        retry.forget();
+    });
+    socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+    on_close.forget();
+    Ok(())
+}
+
+// Fills the `#vote-bar` segments (`#vote-up`, ...) in proportion to this
+// window's votes.
+pub fn update_vote_bar(dom: &Dom, votes: Option<&VoteTally>) {
+    let bar = match dom.get("vote-bar") {
+        Some(bar) => bar,
+        None => return,
+    };
+    let votes = match votes {
+        Some(votes) => votes,
+        None => {
+            bar.class_list().add_1("hidden").unwrap_or(());
+            return;
+        }
+    };
+    bar.class_list().remove_1("hidden").unwrap_or(());
+    let counts = votes.counts();
+    let total = counts.iter().sum::<u32>().max(1);
+    for (id, count) in ["vote-up", "vote-down", "vote-left", "vote-right"].into_iter().zip(counts) {
+        if let Some(segment) = dom.get(id) {
+            let _ = segment.set_attribute("style", &format!("flex-grow: {}", count as f64 / total as f64));
+            segment.set_text_content(Some(&count.to_string()));
+        }
+    }
+}
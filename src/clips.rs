@@ -0,0 +1,76 @@
+use three_d::*;
+
+// Keyframe animations that come in a host's glTF models (see `models.rs`).
+// A model loops its "idle" clip, or its first one if none goes by that name,
+// and game events play a named clip once over it: the head's "chomp" when the
+// snake eats and "crash" when a run ends, the food's and the prize's "spawn"
+// when one appears. Clips run on the renderer's clock, alongside its own
+// animations, and under reduced motion models hold still in their idle pose.
+// Only node animation plays; skins are drawn in their bind pose.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cue {
+    Chomp,
+    Crash,
+    Spawn,
+}
+
+impl Cue {
+    fn clip(self) -> &'static str {
+        match self {
+            Cue::Chomp => "chomp",
+            Cue::Crash => "crash",
+            Cue::Spawn => "spawn",
+        }
+    }
+}
+
+pub struct Clips {
+    // Every clip's name and length in seconds.
+    lengths: Vec<(Option<String>, f32)>,
+    idle: Option<Option<String>>,
+    // The clip playing once and when it started.
+    playing: Option<(Option<String>, f64)>,
+}
+
+impl Clips {
+    pub fn new(model: &CpuModel) -> Self {
+        let mut lengths: Vec<(Option<String>, f32)> = Vec::new();
+        for animation in model.geometries.iter().flat_map(|primitive| &primitive.animations) {
+            let length = animation.key_frames.iter().filter_map(|(_, frames)| frames.loop_time).fold(0.0, f32::max);
+            match lengths.iter_mut().find(|(name, _)| *name == animation.name) {
+                Some((_, longest)) => *longest = longest.max(length),
+                None => lengths.push((animation.name.clone(), length)),
+            }
+        }
+        let idle = lengths
+            .iter()
+            .find(|(name, _)| name.as_deref() == Some("idle"))
+            .or(lengths.first())
+            .map(|(name, _)| name.clone());
+        Self { lengths, idle, playing: None }
+    }
+
+    // Plays the cue's clip once from `time`, if the model has one.
+    pub fn play(&mut self, cue: Cue, time: f64) {
+        let name = Some(cue.clip().to_string());
+        if self.lengths.iter().any(|(clip, _)| *clip == name) {
+            self.playing = Some((name, time));
+        }
+    }
+
+    // The clip to show at `time` and how far into it, if there are any.
+    pub fn at(&mut self, time: f64) -> Option<(Option<&str>, f32)> {
+        let finished = self.playing.as_ref().is_some_and(|(name, start)| {
+            let length = self.lengths.iter().find(|(clip, _)| clip == name).map_or(0.0, |(_, length)| *length);
+            time - start >= length as f64
+        });
+        if finished {
+            self.playing = None;
+        }
+        match &self.playing {
+            Some((name, start)) => Some((name.as_deref(), (time - start) as f32)),
+            None => self.idle.as_ref().map(|name| (name.as_deref(), time as f32)),
+        }
+    }
+}
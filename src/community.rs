@@ -0,0 +1,67 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use snake3d_core::LevelDefinition;
+use wasm_bindgen::prelude::*;
+
+use crate::app::Controls;
+use crate::error;
+use crate::loader;
+
+// Best score per level, keyed by the level's hash in hex, so an edited
+// level starts a fresh board.
+const BEST_KEY: &str = "snake3d_level_best";
+
+// Community levels come from a list the host serves (`levelsUrl`): a JSON
+// array of level definitions, the format the editor exports. Uploading and
+// rating need a level server, which doesn't exist yet; until then a host
+// publishes levels as a static file.
+pub fn fetch(url: &str, controls: Rc<RefCell<Controls>>) -> Result<(), JsValue> {
+    loader::fetch_text(url, "Community level list", move |text| {
+        let levels: Vec<LevelDefinition> = match serde_json::from_str(&text) {
+            Ok(levels) => levels,
+            Err(_) => {
+                log::warn!("Community level list didn't parse");
+                return;
+            }
+        };
+        let count = levels.len();
+        let playable: Vec<LevelDefinition> = levels.into_iter().filter(|level| level.validate().is_ok()).collect();
+        if playable.len() < count {
+            log::warn!("Skipped {} community levels that can't be played", count - playable.len());
+        }
+        controls.borrow_mut().community = playable;
+    })
+}
+
+fn key(level: &LevelDefinition) -> String {
+    format!("{:016x}", level.hash())
+}
+
+fn load_bests() -> HashMap<String, u32> {
+    error::storage()
+        .ok()
+        .and_then(|s| s.get_item(BEST_KEY).ok().flatten())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "ui")]
+pub fn best(level: &LevelDefinition) -> Option<u32> {
+    load_bests().get(&key(level)).copied()
+}
+
+// Keeps `score` if it beats this level's best. Returns true if it did.
+pub fn record(level: &LevelDefinition, score: u32) -> bool {
+    let mut bests = load_bests();
+    let best = bests.entry(key(level)).or_insert(0);
+    if score <= *best {
+        return false;
+    }
+    *best = score;
+    if let (Ok(storage), Ok(json)) = (error::storage(), serde_json::to_string(&bests)) {
+        let _ = storage.set_item(BEST_KEY, &json);
+    }
+    true
+}
@@ -0,0 +1,31 @@
+use wasm_bindgen::JsCast;
+
+use crate::error;
+
+// The host page's elements for one game. A page running several games
+// gives each an `idPrefix`, so with "left-" this game's score goes into
+// `#left-score` and its settings panel is `#left-settings-modal`. Without a
+// prefix the ids are the ones in `index.html`.
+#[derive(Clone, Debug, Default)]
+pub struct Dom {
+    prefix: String,
+}
+
+impl Dom {
+    pub fn new(prefix: &str) -> Self {
+        Self { prefix: prefix.to_string() }
+    }
+
+    pub fn id(&self, id: &str) -> String {
+        format!("{}{}", self.prefix, id)
+    }
+
+    pub fn get(&self, id: &str) -> Option<web_sys::Element> {
+        error::document().ok()?.get_element_by_id(&self.id(id))
+    }
+
+    // Like `error::element`, for elements the game can't do without.
+    pub fn element<T: JsCast>(&self, id: &str) -> error::Result<T> {
+        error::element(&error::document()?, &self.id(id))
+    }
+}
@@ -0,0 +1,105 @@
+use snake3d_core::level::start_cell;
+use snake3d_core::{Direction, GameState, LevelDefinition, Portal, Position};
+
+// The level editor: a cursor moved cell by cell over the cube (across edges
+// like the snake) that drops obstacles and portals into a `LevelDefinition`.
+// The layout goes in and out as JSON, through the editor window's text box
+// or `game.exportLevel()` / `game.importLevel(json)`.
+pub struct Editor {
+    pub level: LevelDefinition,
+    pub cursor: Position,
+    // First end of a portal waiting for its second.
+    pub pending_portal: Option<Position>,
+    // Contents of the editor window's JSON box.
+    pub text: String,
+}
+
+impl Default for Editor {
+    fn default() -> Self {
+        let level = LevelDefinition::default();
+        Self {
+            cursor: start_cell(level.grid_size),
+            level,
+            pending_portal: None,
+            text: String::new(),
+        }
+    }
+}
+
+impl Editor {
+    // `board` supplies the cube's edges; the preview of this level will do.
+    pub fn move_cursor(&mut self, dir: Direction, board: &GameState) {
+        self.cursor = board.calculate_next_position(self.cursor, dir).0;
+    }
+
+    fn portal_at(&self, pos: Position) -> Option<usize> {
+        self.level.portals.iter().position(|p| p.other_end(pos).is_some())
+    }
+
+    // Adds an obstacle under the cursor, or removes whatever is there.
+    pub fn toggle_obstacle(&mut self) {
+        let cursor = self.cursor;
+        if let Some(i) = self.portal_at(cursor) {
+            self.level.portals.remove(i);
+        } else if let Some(i) = self.level.obstacles.iter().position(|p| *p == cursor) {
+            self.level.obstacles.remove(i);
+        } else if cursor != start_cell(self.level.grid_size) && self.pending_portal != Some(cursor) {
+            self.level.obstacles.push(cursor);
+        }
+    }
+
+    // The first press marks one end of a portal, the second joins it to the
+    // cell under the cursor. Pressing again on the marked end lets go of it.
+    pub fn place_portal(&mut self) {
+        let cursor = self.cursor;
+        if self.pending_portal == Some(cursor) {
+            self.pending_portal = None;
+            return;
+        }
+        let taken = cursor == start_cell(self.level.grid_size) || self.level.cells().any(|c| c == cursor);
+        if taken {
+            return;
+        }
+        match self.pending_portal.take() {
+            Some(a) => self.level.portals.push(Portal { a, b: cursor }),
+            None => self.pending_portal = Some(cursor),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.level.obstacles.clear();
+        self.level.portals.clear();
+        self.pending_portal = None;
+    }
+
+    // A smaller board drops whatever no longer fits on it.
+    pub fn set_grid_size(&mut self, grid_size: i32) {
+        if grid_size == self.level.grid_size {
+            return;
+        }
+        let fits = |p: &Position| p.u < grid_size && p.v < grid_size;
+        self.level.grid_size = grid_size;
+        self.level.obstacles.retain(fits);
+        self.level.portals.retain(|p| fits(&p.a) && fits(&p.b));
+        // The old start may be where the new one is.
+        let start = start_cell(grid_size);
+        self.level.obstacles.retain(|p| *p != start);
+        self.level.portals.retain(|p| p.other_end(start).is_none());
+        self.pending_portal = None;
+        self.cursor = start;
+    }
+
+    pub fn export(&self) -> String {
+        serde_json::to_string_pretty(&self.level).unwrap_or_default()
+    }
+
+    // Replaces the level with one from JSON, if it parses and is playable.
+    pub fn import(&mut self, json: &str) -> Result<(), String> {
+        let level: LevelDefinition = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        level.validate().map_err(|e| e.to_string())?;
+        self.cursor = start_cell(level.grid_size);
+        self.level = level;
+        self.pending_portal = None;
+        Ok(())
+    }
+}
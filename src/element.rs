@@ -0,0 +1,69 @@
+use wasm_bindgen::prelude::*;
+
+use crate::events;
+use crate::SnakeGame;
+
+pub const TAG: &str = "snake-3d";
+
+// `<snake-3d grid="12" mode="hazards" theme="retro">`: a canvas with its own
+// game, menus drawn in the canvas. Attributes are read when the element is
+// attached; `element.game` is the `SnakeGame` behind it, and the events of
+// `game.on` are dispatched on the element as CustomEvents with the value in
+// `detail`. A `manual` attribute leaves `start()` to the page.
+#[wasm_bindgen(inline_js = r#"
+export function define_element(tag, create, events) {
+    if (customElements.get(tag)) {
+        return;
+    }
+    let count = 0;
+    customElements.define(tag, class extends HTMLElement {
+        connectedCallback() {
+            if (this.game) {
+                return;
+            }
+            const canvas = document.createElement('canvas');
+            canvas.id = `${tag}-${++count}`;
+            canvas.tabIndex = 0;
+            canvas.style.cssText = 'display: block; width: 100%; height: 100%';
+            this.appendChild(canvas);
+
+            const config = { ui: 'canvas', idPrefix: `${canvas.id}-` };
+            const grid = this.getAttribute('grid');
+            if (grid !== null) {
+                config.gridSize = Number(grid);
+            }
+            for (const name of ['mode', 'theme']) {
+                if (this.hasAttribute(name)) {
+                    config[name] = this.getAttribute(name);
+                }
+            }
+            this.game = create(canvas.id, config);
+            for (const event of events) {
+                this.game.on(event, detail => this.dispatchEvent(new CustomEvent(event, { detail, bubbles: true })));
+            }
+            if (!this.hasAttribute('manual')) {
+                this.game.start();
+            }
+        }
+    });
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(catch)]
+    fn define_element(
+        tag: &str,
+        create: &Closure<dyn Fn(String, JsValue) -> Result<JsValue, JsValue>>,
+        events: js_sys::Array,
+    ) -> Result<(), JsValue>;
+}
+
+// Registers `<snake-3d>`; called once when the module loads.
+pub fn define() -> Result<(), JsValue> {
+    let create = Closure::wrap(Box::new(|canvas_id: String, config: JsValue| {
+        SnakeGame::new(&canvas_id, config).map(JsValue::from)
+    }) as Box<dyn Fn(String, JsValue) -> Result<JsValue, JsValue>>);
+    let names = events::NAMES.iter().map(|name| JsValue::from_str(name)).collect();
+    define_element(TAG, &create, names)?;
+    create.forget();
+    Ok(())
+}
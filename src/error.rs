@@ -0,0 +1,125 @@
+use std::fmt;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::i18n::t;
+
+#[derive(Debug)]
+pub enum AppError {
+    NoWindow,
+    NoDocument,
+    NoStorage,
+    MissingElement(String),
+    WrongElement(String),
+    WebGl(String),
+    InvalidConfig(String),
+    Js(String),
+}
+
+pub type Result<T> = std::result::Result<T, AppError>;
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::NoWindow => write!(f, "This page is not running in a browser window."),
+            AppError::NoDocument => write!(f, "The page document is not available."),
+            AppError::NoStorage => write!(f, "Local storage is disabled, so scores and settings can't be saved."),
+            AppError::MissingElement(id) => write!(f, "The page is missing the '{}' element.", id),
+            AppError::WrongElement(id) => write!(f, "The '{}' element has the wrong type.", id),
+            AppError::WebGl(detail) => write!(
+                f,
+                "WebGL 2 is not supported by this browser or device, so the game can't start. ({})",
+                detail
+            ),
+            AppError::InvalidConfig(detail) => write!(f, "Invalid game configuration: {}", detail),
+            AppError::Js(detail) => write!(f, "Browser error: {}", detail),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<JsValue> for AppError {
+    fn from(value: JsValue) -> Self {
+        AppError::Js(value.as_string().unwrap_or_else(|| format!("{:?}", value)))
+    }
+}
+
+impl From<AppError> for JsValue {
+    fn from(err: AppError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+pub fn window() -> Result<web_sys::Window> {
+    web_sys::window().ok_or(AppError::NoWindow)
+}
+
+pub fn document() -> Result<web_sys::Document> {
+    window()?.document().ok_or(AppError::NoDocument)
+}
+
+pub fn storage() -> Result<web_sys::Storage> {
+    window()?.local_storage().ok().flatten().ok_or(AppError::NoStorage)
+}
+
+pub fn element<T: JsCast>(document: &web_sys::Document, id: &str) -> Result<T> {
+    document
+        .get_element_by_id(id)
+        .ok_or_else(|| AppError::MissingElement(id.to_string()))?
+        .dyn_into::<T>()
+        .map_err(|_| AppError::WrongElement(id.to_string()))
+}
+
+// Shows a human-readable error panel instead of leaving a blank canvas.
+// Uses `#error-panel` from the page if present, otherwise builds one.
+pub fn show(err: &AppError) {
+    log::error!("{}", err);
+    let document = match document() {
+        Ok(document) => document,
+        Err(_) => return,
+    };
+
+    if let Some(loading) = document.get_element_by_id("loading") {
+        loading.set_attribute("style", "display: none").unwrap_or(());
+    }
+
+    if let (Some(panel), Some(message)) = (
+        document.get_element_by_id("error-panel"),
+        document.get_element_by_id("error-message"),
+    ) {
+        message.set_text_content(Some(&err.to_string()));
+        panel.class_list().remove_1("hidden").unwrap_or(());
+        return;
+    }
+
+    let build = || -> std::result::Result<(), JsValue> {
+        let panel = document.create_element("div")?;
+        panel.set_id("error-panel");
+        panel.set_attribute(
+            "style",
+            "position:fixed; inset:0; display:flex; flex-direction:column; justify-content:center; \
+             align-items:center; background:#1a1a1a; color:white; font-family:sans-serif; \
+             text-align:center; padding:20px; z-index:100;",
+        )?;
+        let title = document.create_element("h2")?;
+        title.set_text_content(Some(t("error_title")));
+        let message = document.create_element("p")?;
+        message.set_id("error-message");
+        message.set_text_content(Some(&err.to_string()));
+        panel.append_child(&title)?;
+        panel.append_child(&message)?;
+        document.body().ok_or(AppError::NoDocument)?.append_child(&panel)?;
+        Ok(())
+    };
+    build().unwrap_or(());
+}
+
+// Panics still get logged with a stack trace, but also surface the panel.
+pub fn install_panic_hook() {
+    std::panic::set_hook(Box::new(|info| {
+        console_error_panic_hook::hook(info);
+        show(&AppError::Js(info.to_string()));
+    }));
+}
@@ -0,0 +1,76 @@
+use wasm_bindgen::prelude::*;
+
+use crate::error::{self, AppError};
+
+// Something the host page can subscribe to with `game.on(name, callback)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HostEvent {
+    // The current score, whenever it changes.
+    Score(u32),
+    // The final score of a run that ended.
+    GameOver(u32),
+    // The level just reached.
+    LevelUp(u32),
+    // The id of an achievement unlocked for the first time.
+    Achievement(&'static str),
+    // A run's score that beat the personal best for its mode and board.
+    PersonalBest(u32),
+}
+
+pub const NAMES: [&str; 5] = ["score", "gameover", "levelup", "achievement", "personalbest"];
+
+impl HostEvent {
+    pub fn name(self) -> &'static str {
+        match self {
+            HostEvent::Score(_) => "score",
+            HostEvent::GameOver(_) => "gameover",
+            HostEvent::LevelUp(_) => "levelup",
+            HostEvent::Achievement(_) => "achievement",
+            HostEvent::PersonalBest(_) => "personalbest",
+        }
+    }
+
+    // The callback's argument: a number, or the achievement's id.
+    pub fn detail(self) -> JsValue {
+        match self {
+            HostEvent::Score(n) | HostEvent::GameOver(n) | HostEvent::LevelUp(n) | HostEvent::PersonalBest(n) => n.into(),
+            HostEvent::Achievement(id) => id.into(),
+        }
+    }
+}
+
+// Callbacks the host registered, by event name.
+#[derive(Default)]
+pub struct Listeners {
+    callbacks: Vec<(&'static str, js_sys::Function)>,
+}
+
+impl Listeners {
+    pub fn add(&mut self, name: &str, callback: js_sys::Function) -> error::Result<()> {
+        let name = NAMES
+            .into_iter()
+            .find(|n| *n == name)
+            .ok_or_else(|| AppError::InvalidConfig(format!("unknown event '{}' (expected one of {})", name, NAMES.join(", "))))?;
+        self.callbacks.push((name, callback));
+        Ok(())
+    }
+
+    // The callbacks for `event`, cloned so they can be called once the
+    // `Controls` borrow is released and can use the JS API themselves.
+    pub fn for_event(&self, event: HostEvent) -> Vec<js_sys::Function> {
+        self.callbacks
+            .iter()
+            .filter(|(name, _)| *name == event.name())
+            .map(|(_, callback)| callback.clone())
+            .collect()
+    }
+}
+
+pub fn dispatch(callbacks: Vec<js_sys::Function>, event: HostEvent) {
+    let detail = event.detail();
+    for callback in callbacks {
+        if let Err(e) = callback.call1(&JsValue::NULL, &detail) {
+            log::warn!("'{}' listener threw: {:?}", event.name(), e);
+        }
+    }
+}
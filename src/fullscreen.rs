@@ -0,0 +1,116 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::app::Controls;
+use crate::dom::Dom;
+use crate::error;
+
+pub fn is_fullscreen(document: &web_sys::Document) -> bool {
+    document.fullscreen_element().is_some()
+}
+
+// Must be called from a user gesture (click/key handler); browsers reject
+// fullscreen requests made from the render loop.
+pub fn toggle(canvas: &web_sys::HtmlCanvasElement) {
+    let document = match error::document() {
+        Ok(document) => document,
+        Err(_) => return,
+    };
+    if is_fullscreen(&document) {
+        document.exit_fullscreen();
+    } else if canvas.request_fullscreen().is_ok() {
+        // Swipes need the room; phones that allow it get locked to landscape.
+        if let Some(screen) = web_sys::window().and_then(|w| w.screen().ok()) {
+            let _ = screen.orientation().lock(web_sys::OrientationLockType::Landscape);
+        }
+    }
+}
+
+pub fn attach(
+    document: &web_sys::Document,
+    dom: &Dom,
+    canvas: &web_sys::HtmlCanvasElement,
+    controls: &Rc<RefCell<Controls>>,
+) -> Result<(), JsValue> {
+    if let Some(btn) = dom.get("fullscreen-btn") {
+        let canvas = canvas.clone();
+        let closure = Closure::wrap(Box::new(move || {
+            toggle(&canvas);
+        }) as Box<dyn FnMut()>);
+        btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+
+    // F / F11, unless the player is typing a name or another game on the
+    // page has focus.
+    {
+        let canvas = canvas.clone();
+        let closure = Closure::wrap(Box::new(move |e: web_sys::KeyboardEvent| {
+            let own: &web_sys::Element = &canvas;
+            let active = error::document().ok().and_then(|d| d.active_element());
+            let typing = active.as_ref().map(|el| el.tag_name() == "INPUT").unwrap_or(false);
+            let other_game = active.as_ref().map(|el| el.tag_name() == "CANVAS" && el != own).unwrap_or(false);
+            let key = e.key();
+            if !typing && !other_game && (key == "f" || key == "F" || key == "F11") {
+                e.prevent_default();
+                toggle(&canvas);
+            }
+        }) as Box<dyn FnMut(_)>);
+        document.add_event_listener_with_callback("keydown", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+
+    // Remember the choice and let the window pick up the new canvas size.
+    {
+        let controls = controls.clone();
+        let closure = Closure::wrap(Box::new(move || {
+            let (window, document) = match (error::window(), error::document()) {
+                (Ok(window), Ok(document)) => (window, document),
+                _ => return,
+            };
+            let fullscreen = is_fullscreen(&document);
+            {
+                let mut controls = controls.borrow_mut();
+                if controls.settings.fullscreen != fullscreen {
+                    controls.settings.fullscreen = fullscreen;
+                    controls.settings.save();
+                }
+            }
+            if let Ok(event) = web_sys::Event::new("resize") {
+                let _ = window.dispatch_event(&event);
+            }
+        }) as Box<dyn FnMut()>);
+        document.add_event_listener_with_callback("fullscreenchange", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+
+    // A remembered preference can only be restored on the first interaction.
+    if controls.borrow().settings.fullscreen {
+        let canvas = canvas.clone();
+        let closure = Closure::once(move |e: web_sys::Event| {
+            // The fullscreen button handles its own click.
+            let on_button = e
+                .target()
+                .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+                .map(|el| el.id() == "fullscreen-btn")
+                .unwrap_or(false);
+            let in_fullscreen = error::document().map(|d| is_fullscreen(&d)).unwrap_or(true);
+            if !on_button && !in_fullscreen {
+                toggle(&canvas);
+            }
+        });
+        let options = web_sys::AddEventListenerOptions::new();
+        options.set_once(true);
+        document.add_event_listener_with_callback_and_add_event_listener_options(
+            "pointerdown",
+            closure.as_ref().unchecked_ref(),
+            &options,
+        )?;
+        closure.forget();
+    }
+
+    Ok(())
+}
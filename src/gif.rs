@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+// A minimal animated GIF encoder for clips (see `capture.rs`). Every frame
+// shares one fixed palette of 3 bits red, 3 green and 2 blue, so frames can
+// be reduced to palette indices as they are captured and encoding is only
+// the LZW pass.
+
+// Palette index for a color.
+pub fn index(r: u8, g: u8, b: u8) -> u8 {
+    (r & 0xe0) | ((g & 0xe0) >> 3) | (b >> 6)
+}
+
+fn palette() -> Vec<u8> {
+    // Each channel's top bits spread back over 0..=255.
+    let spread = |value: u8, bits: u32| (value as u32 * 255 / ((1 << bits) - 1)) as u8;
+    (0..=255u8)
+        .flat_map(|i| [spread(i >> 5, 3), spread((i >> 2) & 7, 3), spread(i & 3, 2)])
+        .collect()
+}
+
+// Encodes frames of `width` x `height` palette indices, top row first, into
+// a GIF that loops forever with `delay` hundredths of a second per frame.
+pub fn encode(width: u16, height: u16, frames: &[Vec<u8>], delay: u16) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(b"GIF89a");
+    out.extend_from_slice(&width.to_le_bytes());
+    out.extend_from_slice(&height.to_le_bytes());
+    // A global table of 256 colors, 8 bits per channel.
+    out.extend_from_slice(&[0xf7, 0, 0]);
+    out.extend_from_slice(&palette());
+    // Loop forever.
+    out.extend_from_slice(&[0x21, 0xff, 0x0b]);
+    out.extend_from_slice(b"NETSCAPE2.0");
+    out.extend_from_slice(&[0x03, 0x01, 0x00, 0x00, 0x00]);
+    for frame in frames {
+        out.extend_from_slice(&[0x21, 0xf9, 0x04, 0x00]);
+        out.extend_from_slice(&delay.to_le_bytes());
+        out.extend_from_slice(&[0x00, 0x00]);
+        out.push(0x2c);
+        out.extend_from_slice(&[0, 0, 0, 0]);
+        out.extend_from_slice(&width.to_le_bytes());
+        out.extend_from_slice(&height.to_le_bytes());
+        out.push(0x00);
+        out.push(MIN_CODE_SIZE);
+        for block in lzw(frame).chunks(255) {
+            out.push(block.len() as u8);
+            out.extend_from_slice(block);
+        }
+        out.push(0x00);
+    }
+    out.push(0x3b);
+    out
+}
+
+const MIN_CODE_SIZE: u8 = 8;
+const CLEAR: u16 = 1 << MIN_CODE_SIZE;
+const END: u16 = CLEAR + 1;
+// Codes are at most 12 bits.
+const LAST_CODE: u16 = 4095;
+
+fn lzw(indices: &[u8]) -> Vec<u8> {
+    let mut bits = Bits::default();
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut width = MIN_CODE_SIZE + 1;
+    // Last code given out.
+    let mut last = END;
+    bits.write(CLEAR, width);
+    let mut rest = indices.iter().copied();
+    let Some(first) = rest.next() else {
+        bits.write(END, width);
+        return bits.finish();
+    };
+    let mut prefix = first as u16;
+    for k in rest {
+        if let Some(&code) = table.get(&(prefix, k)) {
+            prefix = code;
+            continue;
+        }
+        bits.write(prefix, width);
+        last += 1;
+        table.insert((prefix, k), last);
+        if last >= 1 << width {
+            width += 1;
+        }
+        if last == LAST_CODE {
+            bits.write(CLEAR, width);
+            table.clear();
+            width = MIN_CODE_SIZE + 1;
+            last = END;
+        }
+        prefix = k as u16;
+    }
+    bits.write(prefix, width);
+    bits.write(END, width);
+    bits.finish()
+}
+
+// Codes packed least significant bit first.
+#[derive(Default)]
+struct Bits {
+    bytes: Vec<u8>,
+    pending: u32,
+    count: u8,
+}
+
+impl Bits {
+    fn write(&mut self, code: u16, width: u8) {
+        self.pending |= (code as u32) << self.count;
+        self.count += width;
+        while self.count >= 8 {
+            self.bytes.push(self.pending as u8);
+            self.pending >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.pending as u8);
+        }
+        self.bytes
+    }
+}
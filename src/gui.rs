@@ -0,0 +1,671 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use three_d::egui;
+use three_d::*;
+#[cfg(not(feature = "minimal"))]
+use snake3d_core::GameMode;
+use snake3d_core::GameState;
+#[cfg(feature = "editor")]
+use snake3d_core::WinCondition;
+
+use crate::app::{Controls, MenuAction};
+use crate::campaign;
+use crate::community;
+#[cfg(feature = "editor")]
+use crate::editor::Editor;
+use crate::i18n::{t, Language};
+use crate::leaderboard;
+use crate::settings::{
+    self, ControlScheme, GraphicsPreset, Narration, Projection, Skin, SoundPack, Theme, Trail, MAX_GRID_SIZE, MIN_GRID_SIZE,
+};
+
+// Menus drawn inside the canvas with egui: the main menu shown over the
+// slowly turning cube, and, for host pages that don't provide the HTML panels
+// (`ui: 'canvas'`), the pause and game over menus too. Nothing here touches
+// the DOM, so the same menus work in a native window.
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Screen {
+    Hidden,
+    Menu,
+    #[cfg(not(feature = "minimal"))]
+    Modes,
+    Pause,
+    Settings,
+    Leaderboard,
+    // The level editor's window, over the level being edited.
+    #[cfg(feature = "editor")]
+    Editor,
+    // Levels from the host's list (see `community.rs`).
+    Community,
+    // The shipped campaign's level select.
+    Campaign,
+}
+
+// The main menu's entries, top to bottom.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MainEntry {
+    Play,
+    #[cfg(not(feature = "minimal"))]
+    Modes,
+    Settings,
+    Leaderboard,
+    #[cfg(feature = "editor")]
+    Editor,
+    Community,
+    Campaign,
+}
+
+fn main_menu() -> Vec<MainEntry> {
+    let mut entries = vec![MainEntry::Play];
+    #[cfg(not(feature = "minimal"))]
+    entries.push(MainEntry::Modes);
+    entries.extend([MainEntry::Settings, MainEntry::Leaderboard]);
+    #[cfg(feature = "editor")]
+    entries.push(MainEntry::Editor);
+    entries.extend([MainEntry::Community, MainEntry::Campaign]);
+    entries
+}
+
+// Arrow keys (or W/S) move through a menu, Enter or Space picks the
+// highlighted entry.
+#[derive(Default)]
+struct Nav {
+    step: i32,
+    pick: bool,
+}
+
+impl Nav {
+    fn read(events: &[Event]) -> Nav {
+        let mut nav = Nav::default();
+        for event in events {
+            if let Event::KeyPress { kind, handled: false, .. } = event {
+                match kind {
+                    Key::ArrowUp | Key::W => nav.step -= 1,
+                    Key::ArrowDown | Key::S => nav.step += 1,
+                    Key::Enter | Key::Space => nav.pick = true,
+                    _ => {}
+                }
+            }
+        }
+        nav
+    }
+}
+
+pub struct Gui {
+    gui: GUI,
+    screen: Screen,
+    // Where Settings and Leaderboard return to.
+    back: Screen,
+    // Highlighted entry of the current menu.
+    selected: usize,
+    // Pause and game over menus; the page draws its own otherwise.
+    in_game: bool,
+    // Name field on the game over window, prefilled with the last one used.
+    name: String,
+    // Why the last level import failed.
+    #[cfg(feature = "editor")]
+    import_error: Option<String>,
+}
+
+impl Gui {
+    pub fn new(context: &Context, in_game: bool) -> Self {
+        Self {
+            gui: GUI::new(context),
+            screen: Screen::Menu,
+            back: Screen::Menu,
+            selected: 0,
+            in_game,
+            name: leaderboard::remembered_name().unwrap_or_default(),
+            #[cfg(feature = "editor")]
+            import_error: None,
+        }
+    }
+
+    // A menu is up, so the game should hold still.
+    pub fn is_open(&self) -> bool {
+        self.screen != Screen::Hidden
+    }
+
+    // Opens the pause menu over a running game, when this draws one.
+    pub fn pause(&mut self) {
+        if self.in_game && self.screen == Screen::Hidden {
+            self.open(Screen::Pause);
+        }
+    }
+
+    fn open(&mut self, screen: Screen) {
+        if matches!(screen, Screen::Settings | Screen::Leaderboard) {
+            self.back = self.screen;
+        }
+        self.screen = screen;
+        self.selected = 0;
+    }
+
+    // Back on the main menu with `entry` highlighted.
+    fn return_to(&mut self, entry: MainEntry) {
+        self.open(Screen::Menu);
+        self.selected = main_menu().iter().position(|e| *e == entry).unwrap_or(0);
+    }
+
+    // Entries of a list menu, or None for the other screens.
+    fn entries(&self, controls: &Controls) -> Option<Vec<String>> {
+        match self.screen {
+            Screen::Menu => Some(
+                main_menu()
+                    .into_iter()
+                    .map(|entry| match entry {
+                        MainEntry::Play => t("play").to_string(),
+                        #[cfg(not(feature = "minimal"))]
+                        MainEntry::Modes => format!("{}: {}", t("modes"), t(mode_key(controls.mode))),
+                        MainEntry::Settings => t("settings").to_string(),
+                        MainEntry::Leaderboard => t("leaderboard").to_string(),
+                        #[cfg(feature = "editor")]
+                        MainEntry::Editor => t("level_editor").to_string(),
+                        MainEntry::Community => t("community_levels").to_string(),
+                        MainEntry::Campaign => t("campaign").to_string(),
+                    })
+                    .collect(),
+            ),
+            // Each level with its best score here, if played.
+            Screen::Community => {
+                let mut entries: Vec<String> = controls
+                    .community
+                    .iter()
+                    .map(|level| match community::best(level) {
+                        Some(best) => format!("{} ({})", level.name, best),
+                        None => level.name.clone(),
+                    })
+                    .collect();
+                entries.push(t("back").to_string());
+                Some(entries)
+            }
+            // Each level with its stars, or a lock until the one before
+            // has a star.
+            Screen::Campaign => {
+                let mut entries: Vec<String> = campaign::levels()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, level)| {
+                        if !campaign::unlocked(i) {
+                            return format!("{}. 🔒", i + 1);
+                        }
+                        let stars = campaign::stars(i) as usize;
+                        format!("{}. {} {}{}", i + 1, level.level.name, "★".repeat(stars), "☆".repeat(3 - stars))
+                    })
+                    .collect();
+                entries.push(t("back").to_string());
+                Some(entries)
+            }
+            #[cfg(not(feature = "minimal"))]
+            Screen::Modes => {
+                let mut entries: Vec<String> = GameMode::ALL.iter().map(|m| t(mode_key(*m)).to_string()).collect();
+                entries.push(t("back").to_string());
+                Some(entries)
+            }
+            Screen::Pause => Some(vec![
+                t("resume").to_string(),
+                t("restart").to_string(),
+                t("settings").to_string(),
+                t("leaderboard").to_string(),
+                t("main_menu").to_string(),
+            ]),
+            _ => None,
+        }
+    }
+
+    // Runs entry `index` of the current list menu.
+    fn pick(&mut self, index: usize, controls: &Rc<RefCell<Controls>>) -> Option<MenuAction> {
+        match (self.screen, index) {
+            (Screen::Menu, i) => match main_menu().get(i) {
+                Some(MainEntry::Play) => {
+                    self.screen = Screen::Hidden;
+                    return Some(MenuAction::Play);
+                }
+                #[cfg(not(feature = "minimal"))]
+                Some(MainEntry::Modes) => self.open(Screen::Modes),
+                Some(MainEntry::Settings) => self.open(Screen::Settings),
+                Some(MainEntry::Leaderboard) => self.open(Screen::Leaderboard),
+                #[cfg(feature = "editor")]
+                Some(MainEntry::Editor) => {
+                    self.open(Screen::Editor);
+                    return Some(MenuAction::Edit);
+                }
+                Some(MainEntry::Community) => self.open(Screen::Community),
+                Some(MainEntry::Campaign) => self.open(Screen::Campaign),
+                None => {}
+            },
+            (Screen::Pause, 2) => self.open(Screen::Settings),
+            (Screen::Pause, 3) => self.open(Screen::Leaderboard),
+            (Screen::Community, i) => {
+                let level = controls.borrow().community.get(i).cloned();
+                match level {
+                    Some(level) => {
+                        self.screen = Screen::Hidden;
+                        return Some(MenuAction::PlayLevel(level));
+                    }
+                    None => self.return_to(MainEntry::Community),
+                }
+            }
+            (Screen::Campaign, i) if i < campaign::levels().len() && campaign::unlocked(i) => {
+                self.screen = Screen::Hidden;
+                return Some(MenuAction::PlayCampaign(i));
+            }
+            // Locked stages stay put.
+            (Screen::Campaign, i) if i < campaign::levels().len() => {}
+            (Screen::Campaign, _) => self.return_to(MainEntry::Campaign),
+            #[cfg(not(feature = "minimal"))]
+            (Screen::Modes, i) => {
+                if let Some(mode) = GameMode::ALL.get(i) {
+                    controls.borrow_mut().mode = *mode;
+                }
+                self.return_to(MainEntry::Modes);
+            }
+            (Screen::Pause, 0) => self.screen = Screen::Hidden,
+            (Screen::Pause, 1) => {
+                self.screen = Screen::Hidden;
+                return Some(MenuAction::Restart);
+            }
+            (Screen::Pause, 4) => {
+                self.open(Screen::Menu);
+                return Some(MenuAction::MainMenu);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    // Escape opens the pause menu or backs out of the open one. Events the
+    // menus use are marked handled so they don't reach the camera.
+    pub fn update(
+        &mut self,
+        events: &mut [Event],
+        frame_input: &FrameInput,
+        game: &GameState,
+        controls: &Rc<RefCell<Controls>>,
+    ) -> Option<MenuAction> {
+        #[cfg_attr(not(feature = "editor"), allow(unused_mut))]
+        let mut leave_editor = false;
+        for event in events.iter() {
+            if let Event::KeyPress { kind: Key::Escape, .. } = event {
+                match self.screen {
+                    Screen::Hidden if self.in_game && !game.game_over => self.open(Screen::Pause),
+                    Screen::Settings | Screen::Leaderboard => self.open(self.back),
+                    Screen::Community | Screen::Campaign => self.open(Screen::Menu),
+                    #[cfg(not(feature = "minimal"))]
+                    Screen::Modes => self.open(Screen::Menu),
+                    Screen::Pause => self.screen = Screen::Hidden,
+                    #[cfg(feature = "editor")]
+                    Screen::Editor => leave_editor = true,
+                    _ => {}
+                }
+            }
+        }
+
+        let entries = self.entries(&controls.borrow());
+        let nav = Nav::read(events);
+        if let Some(entries) = &entries {
+            self.selected = (self.selected as i32 + nav.step).rem_euclid(entries.len() as i32) as usize;
+        }
+
+        let mut clicked = None;
+        let mut game_over_action = None;
+        let mut close = false;
+        let mut open_pause = false;
+        #[cfg_attr(not(feature = "editor"), allow(unused_mut))]
+        let mut editor_action = None;
+        let screen = self.screen;
+        let selected = self.selected;
+        let in_game = self.in_game;
+        let name = &mut self.name;
+        #[cfg(feature = "editor")]
+        let import_error = &mut self.import_error;
+        self.gui.update(
+            events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |ctx| match screen {
+                Screen::Hidden if !in_game => {}
+                Screen::Hidden if game.game_over => {
+                    game_over_action = game_over_window(ctx, game.score, game.can_rewind(), name);
+                }
+                Screen::Hidden => {
+                    egui::Area::new("menu-button")
+                        .anchor(egui::Align2::RIGHT_TOP, [-12.0, 12.0])
+                        .show(ctx, |ui| {
+                            open_pause = ui.button("☰").clicked();
+                        });
+                }
+                Screen::Settings => {
+                    window(t("settings")).show(ctx, |ui| {
+                        let current = controls.borrow().settings.clone();
+                        let mut edited = current.clone();
+                        settings_grid(ui, &mut edited);
+                        if edited != current {
+                            settings::commit(&mut controls.borrow_mut(), edited.sanitized());
+                        }
+                        close = ui.button(t("close")).clicked();
+                    });
+                }
+                Screen::Leaderboard => {
+                    window(t("top_players")).show(ctx, |ui| {
+                        leaderboard_list(ui);
+                        close = ui.button(t("close")).clicked();
+                    });
+                }
+                #[cfg(feature = "editor")]
+                Screen::Editor => {
+                    editor_action = editor_window(ctx, &mut controls.borrow_mut().editor, import_error);
+                }
+                // The list menus: main, modes, pause, community and campaign.
+                _ => {
+                    let title = match screen {
+                        Screen::Menu => "3D Snake",
+                        #[cfg(not(feature = "minimal"))]
+                        Screen::Modes => t("modes"),
+                        Screen::Community => t("community_levels"),
+                        Screen::Campaign => t("campaign"),
+                        _ => t("paused"),
+                    };
+                    window(title).show(ctx, |ui| {
+                        ui.vertical_centered_justified(|ui| {
+                            for (i, entry) in entries.iter().flatten().enumerate() {
+                                if ui.selectable_label(i == selected, entry.as_str()).clicked() {
+                                    clicked = Some(i);
+                                }
+                            }
+                        });
+                    });
+                }
+            },
+        );
+
+        if game_over_action.is_some() {
+            return game_over_action;
+        }
+        if leave_editor || matches!(editor_action, Some(MenuAction::MainMenu)) {
+            self.open(Screen::Menu);
+            return Some(MenuAction::MainMenu);
+        }
+        if editor_action.is_some() {
+            self.screen = Screen::Hidden;
+            return editor_action;
+        }
+        if open_pause {
+            self.open(Screen::Pause);
+        }
+        if close {
+            self.open(self.back);
+        }
+        let picked = clicked.or((nav.pick && entries.is_some()).then_some(self.selected));
+        picked.and_then(|index| self.pick(index, controls))
+    }
+
+    pub fn render(&self) {
+        self.gui.render();
+    }
+}
+
+#[cfg(not(feature = "minimal"))]
+fn mode_key(mode: GameMode) -> &'static str {
+    match mode {
+        GameMode::Classic => "mode_classic",
+        GameMode::Hazards => "mode_hazards",
+        GameMode::Tron => "mode_tron",
+        GameMode::Speedrun => "mode_speedrun",
+        GameMode::Survival => "mode_survival",
+        GameMode::Rhythm => "mode_rhythm",
+        GameMode::Dungeon => "mode_dungeon",
+        GameMode::Boss => "mode_boss",
+        GameMode::Coop => "mode_coop",
+    }
+}
+
+fn window(title: &str) -> egui::Window<'static> {
+    egui::Window::new(title.to_string())
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .collapsible(false)
+        .resizable(false)
+}
+
+// Restart once a qualifying score was submitted or restart was chosen, or
+// rewind if offered and taken.
+fn game_over_window(ctx: &egui::Context, score: u32, can_rewind: bool, name: &mut String) -> Option<MenuAction> {
+    let mut action = None;
+    window(t("game_over")).show(ctx, |ui| {
+        ui.label(t("final_score").replace("{score}", &score.to_string()));
+        if leaderboard::qualifies(score) {
+            let field = ui.add(egui::TextEdit::singleline(name).hint_text(t("name_placeholder")));
+            let entered = field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if (ui.button(t("submit_score")).clicked() || entered) && !name.trim().is_empty() {
+                leaderboard::save_score(name.trim(), score);
+                action = Some(MenuAction::Restart);
+            }
+        }
+        if can_rewind && ui.button(t("rewind")).clicked() {
+            action = Some(MenuAction::Rewind);
+        }
+        if ui.button(t("restart")).clicked() {
+            action = Some(MenuAction::Restart);
+        }
+    });
+    action
+}
+
+// The editor's controls, to one side so the cube stays in view. Returns
+// `TestLevel` or `MainMenu` when picked.
+#[cfg(feature = "editor")]
+fn editor_window(ctx: &egui::Context, editor: &mut Editor, import_error: &mut Option<String>) -> Option<MenuAction> {
+    let mut action = None;
+    egui::Window::new(t("level_editor"))
+        .anchor(egui::Align2::LEFT_TOP, [12.0, 12.0])
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(t("editor_help"));
+            if editor.pending_portal.is_some() {
+                ui.label(t("editor_portal_pending"));
+            }
+            egui::Grid::new("editor").num_columns(2).show(ui, |ui| {
+                ui.label(t("level_name"));
+                ui.text_edit_singleline(&mut editor.level.name);
+                ui.end_row();
+                ui.label(t("grid_size"));
+                let mut grid_size = editor.level.grid_size;
+                ui.add(egui::Slider::new(&mut grid_size, MIN_GRID_SIZE..=MAX_GRID_SIZE));
+                editor.set_grid_size(grid_size);
+                ui.end_row();
+                let win = &mut editor.level.win;
+                let mut kind = match win {
+                    WinCondition::Endless => 0,
+                    WinCondition::Score(_) => 1,
+                    WinCondition::Survive(_) => 2,
+                    WinCondition::Prizes(_) => 3,
+                };
+                let goals = [(0, t("goal_endless")), (1, t("goal_score")), (2, t("goal_survive")), (3, t("goal_prizes"))];
+                choice(ui, t("level_goal"), &mut kind, &goals);
+                *win = match (kind, *win) {
+                    (0, _) => WinCondition::Endless,
+                    (1, WinCondition::Score(points)) => WinCondition::Score(points),
+                    (1, _) => WinCondition::Score(50),
+                    (2, WinCondition::Survive(seconds)) => WinCondition::Survive(seconds),
+                    (2, _) => WinCondition::Survive(60.0),
+                    (_, WinCondition::Prizes(prizes)) => WinCondition::Prizes(prizes),
+                    _ => WinCondition::Prizes(3),
+                };
+                match win {
+                    WinCondition::Endless => {}
+                    WinCondition::Score(points) => {
+                        ui.label("");
+                        ui.add(egui::DragValue::new(points).clamp_range(1..=999));
+                        ui.end_row();
+                    }
+                    WinCondition::Survive(seconds) => {
+                        ui.label("");
+                        ui.add(egui::DragValue::new(seconds).clamp_range(5.0..=600.0).suffix(" s"));
+                        ui.end_row();
+                    }
+                    WinCondition::Prizes(prizes) => {
+                        ui.label("");
+                        ui.add(egui::DragValue::new(prizes).clamp_range(1..=99));
+                        ui.end_row();
+                    }
+                }
+            });
+            ui.horizontal(|ui| {
+                if ui.button(t("play_test")).clicked() {
+                    action = Some(MenuAction::TestLevel);
+                }
+                if ui.button(t("clear")).clicked() {
+                    editor.clear();
+                }
+                if ui.button(t("export")).clicked() {
+                    editor.text = editor.export();
+                    *import_error = None;
+                }
+                if ui.button(t("import")).clicked() {
+                    let text = editor.text.clone();
+                    *import_error = editor.import(&text).err();
+                }
+            });
+            if let Some(error) = import_error {
+                ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("{}: {}", t("level_invalid"), error));
+            }
+            ui.add(egui::TextEdit::multiline(&mut editor.text).code_editor().desired_rows(6));
+            if ui.button(t("back")).clicked() {
+                action = Some(MenuAction::MainMenu);
+            }
+        });
+    action
+}
+
+fn leaderboard_list(ui: &mut egui::Ui) {
+    let entries = leaderboard::top_scores();
+    if entries.is_empty() {
+        ui.label(t("no_scores"));
+        return;
+    }
+    egui::Grid::new("leaderboard").striped(true).show(ui, |ui| {
+        for (i, entry) in entries.iter().enumerate() {
+            ui.label(format!("{}. {}", i + 1, entry.label()));
+            ui.colored_label(egui::Color32::from_rgb(255, 235, 59), entry.score.to_string());
+            ui.end_row();
+        }
+    });
+}
+
+fn choice<T: PartialEq + Copy>(ui: &mut egui::Ui, label: &str, value: &mut T, options: &[(T, &str)]) {
+    ui.label(label);
+    let selected = options.iter().find(|(v, _)| v == value).map(|(_, name)| *name).unwrap_or_default();
+    egui::ComboBox::from_id_source(label).selected_text(selected).show_ui(ui, |ui| {
+        for (v, name) in options {
+            ui.selectable_value(value, *v, *name);
+        }
+    });
+    ui.end_row();
+}
+
+// Same fields as the HTML settings panel.
+fn settings_grid(ui: &mut egui::Ui, settings: &mut settings::Settings) {
+    egui::Grid::new("settings").num_columns(2).show(ui, |ui| {
+        ui.label(t("master_volume"));
+        ui.add(egui::Slider::new(&mut settings.master_volume, 0.0..=1.0).show_value(false));
+        ui.end_row();
+        ui.label(t("sfx_volume"));
+        ui.add(egui::Slider::new(&mut settings.sfx_volume, 0.0..=1.0).show_value(false));
+        ui.end_row();
+        ui.label(t("announcer"));
+        ui.checkbox(&mut settings.announcer, "");
+        ui.end_row();
+        choice(
+            ui,
+            t("narration"),
+            &mut settings.narration,
+            &[(Narration::Off, t("off")), (Narration::Brief, t("brief")), (Narration::Full, t("full"))],
+        );
+        choice(
+            ui,
+            t("controls"),
+            &mut settings.control_scheme,
+            &[(ControlScheme::Both, "Arrows + WASD"), (ControlScheme::Arrows, "Arrows"), (ControlScheme::Wasd, "WASD")],
+        );
+        choice(
+            ui,
+            t("graphics"),
+            &mut settings.graphics,
+            &[(GraphicsPreset::Low, t("low")), (GraphicsPreset::Medium, t("medium")), (GraphicsPreset::High, t("high"))],
+        );
+        ui.label(t("ambient_occlusion"));
+        ui.checkbox(&mut settings.ambient_occlusion, "");
+        ui.end_row();
+        choice(
+            ui,
+            t("projection"),
+            &mut settings.projection,
+            &[(Projection::Perspective, t("perspective")), (Projection::Orthographic, t("orthographic"))],
+        );
+        ui.label(t("far_side_view"));
+        ui.checkbox(&mut settings.far_side_view, "");
+        ui.end_row();
+        ui.label(t("food_hint"));
+        ui.checkbox(&mut settings.food_hint, "");
+        ui.end_row();
+        ui.label(t("director"));
+        ui.checkbox(&mut settings.director, "");
+        ui.end_row();
+        ui.label(t("record_clips"));
+        ui.checkbox(&mut settings.record_clips, "");
+        ui.end_row();
+        ui.label(t("high_contrast"));
+        ui.checkbox(&mut settings.high_contrast, "");
+        ui.end_row();
+        choice(
+            ui,
+            t("reduced_motion"),
+            &mut settings.reduced_motion,
+            &[(None, t("auto")), (Some(true), t("on")), (Some(false), t("off"))],
+        );
+        // Locked choices are put back on commit (see `unlocks::gate`).
+        choice(ui, t("theme"), &mut settings.theme, &[(Theme::Neon, "Neon"), (Theme::Retro, "Retro")]);
+        choice(
+            ui,
+            t("skin"),
+            &mut settings.skin,
+            &[
+                (Skin::Classic, t("cosmetic_skin_classic")),
+                (Skin::Gold, t("cosmetic_skin_gold")),
+                (Skin::Ghost, t("cosmetic_skin_ghost")),
+            ],
+        );
+        choice(
+            ui,
+            t("trail"),
+            &mut settings.trail,
+            &[(Trail::None, t("cosmetic_trail_none")), (Trail::Sparks, t("cosmetic_trail_sparks"))],
+        );
+        choice(
+            ui,
+            t("sound_pack"),
+            &mut settings.sound_pack,
+            &[(SoundPack::Classic, t("cosmetic_sound_classic")), (SoundPack::Chiptune, t("cosmetic_sound_chiptune"))],
+        );
+        ui.label(t("grid_size"));
+        ui.add(egui::Slider::new(&mut settings.grid_size, MIN_GRID_SIZE..=MAX_GRID_SIZE));
+        ui.end_row();
+        choice(
+            ui,
+            t("language"),
+            &mut settings.language,
+            &[
+                (None, t("auto")),
+                (Some(Language::En), "English"),
+                (Some(Language::Es), "Español"),
+                (Some(Language::De), "Deutsch"),
+            ],
+        );
+        ui.label(t("analytics"));
+        ui.checkbox(&mut settings.analytics, "");
+        ui.end_row();
+    });
+}
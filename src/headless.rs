@@ -0,0 +1,38 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use three_d::*;
+use snake3d_core::GameState;
+
+use crate::app::{Controls, MenuAction};
+
+// Stands in for the canvas menus in builds without the `ui` feature. There
+// is no main menu, so the first frame starts a run; R restarts after a game
+// over as usual, and the host page does the rest through the JS API.
+pub struct Gui {
+    started: bool,
+}
+
+impl Gui {
+    pub fn new(_context: &Context, _in_game: bool) -> Self {
+        Self { started: false }
+    }
+
+    pub fn is_open(&self) -> bool {
+        false
+    }
+
+    pub fn pause(&mut self) {}
+
+    pub fn update(
+        &mut self,
+        _events: &mut [Event],
+        _frame_input: &FrameInput,
+        _game: &GameState,
+        _controls: &Rc<RefCell<Controls>>,
+    ) -> Option<MenuAction> {
+        (!std::mem::replace(&mut self.started, true)).then_some(MenuAction::Play)
+    }
+
+    pub fn render(&self) {}
+}
@@ -0,0 +1,556 @@
+use std::cell::Cell;
+
+use serde::{Serialize, Deserialize};
+use wasm_bindgen::JsCast;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    En,
+    Es,
+    De,
+}
+
+thread_local! {
+    static CURRENT: Cell<Language> = const { Cell::new(Language::En) };
+}
+
+// Language packs. Keys missing from a pack fall back to English.
+const EN: &[(&str, &str)] = &[
+    ("loading", "Loading 3D Snake..."),
+    ("score_label", "Score:"),
+    ("high_score_label", "High Score:"),
+    ("pb_label", "PB:"),
+    ("lives_label", "Lives:"),
+    ("streak_label", "Streak"),
+    ("leaderboard_btn", "🏆 Leaderboard"),
+    ("unlocks_btn", "🎨 Unlocks"),
+    ("shop_btn", "🪙 Shop"),
+    ("shop", "Shop"),
+    ("shop_sold_out", "You have everything!"),
+    ("coins_earned", "+{coins} coins"),
+    ("unlocks", "Unlocks"),
+    ("lifetime_score", "Lifetime score"),
+    ("skin", "Skin"),
+    ("trail", "Trail"),
+    ("sound_pack", "Sound Pack"),
+    ("cosmetic_theme_neon", "Neon theme"),
+    ("cosmetic_theme_retro", "Retro theme"),
+    ("cosmetic_skin_classic", "Classic"),
+    ("cosmetic_skin_gold", "Gold"),
+    ("cosmetic_skin_ghost", "Ghost"),
+    ("cosmetic_trail_none", "None"),
+    ("cosmetic_trail_sparks", "Sparks"),
+    ("cosmetic_sound_classic", "Classic"),
+    ("cosmetic_sound_chiptune", "Chiptune"),
+    ("cosmetic_unlocked", "Unlocked:"),
+    ("unlock_by_score", "Score {points} points in total ({progress}/{points})"),
+    ("unlock_by_achievement", "Earn \"{achievement}\""),
+    ("settings_btn", "⚙ Settings"),
+    ("game_over", "Game Over"),
+    ("final_score", "Score: {score}"),
+    ("finish_time", "Finished in {time}"),
+    ("name_placeholder", "Enter Name"),
+    ("submit_score", "Submit Score"),
+    ("restart_hint", "Press 'R' or Submit to Restart"),
+    ("rewind_hint", "Press Backspace to rewind"),
+    ("top_players", "Top 10 Players"),
+    ("no_scores", "No scores yet!"),
+    ("all_players", "All"),
+    ("friends", "Friends"),
+    ("no_friends", "Star a player to add them as a friend."),
+    ("beat_friend", "You beat {name}'s best!"),
+    ("load_failed", "Failed to load scores."),
+    ("close", "Close"),
+    ("controls_hint", "WASD / Arrows to Move | R to Restart | F for Fullscreen"),
+    ("settings", "Settings"),
+    ("master_volume", "Master Volume"),
+    ("sfx_volume", "Effects Volume"),
+    ("announcer", "Announcer"),
+    ("controls", "Controls"),
+    ("graphics", "Graphics"),
+    ("ambient_occlusion", "Ambient Occlusion"),
+    ("projection", "Camera"),
+    ("perspective", "Perspective"),
+    ("orthographic", "Orthographic"),
+    ("far_side_view", "Far Side View"),
+    ("food_hint", "Food Hint"),
+    ("director", "Adaptive Difficulty"),
+    ("record_clips", "Record Clips"),
+    ("save_clip", "Save Clip"),
+    ("high_contrast", "High Contrast"),
+    ("reduced_motion", "Reduced Motion"),
+    ("on", "On"),
+    ("off", "Off"),
+    ("paused", "Paused"),
+    ("resume", "Resume"),
+    ("restart", "Restart"),
+    ("rewind", "⏪ Rewind"),
+    ("leaderboard", "Leaderboard"),
+    ("play", "Play"),
+    ("modes", "Mode"),
+    ("main_menu", "Main Menu"),
+    ("back", "Back"),
+    ("level_editor", "Level Editor"),
+    ("community_levels", "Community Levels"),
+    ("level_best", "New best on this level!"),
+    ("new_pb", "New personal best!"),
+    ("unranked", "Assisted run: not ranked"),
+    ("campaign", "Campaign"),
+    ("campaign_stars", "Stars earned:"),
+    ("editor_help", "Arrows move · O obstacle · P portal · Esc leaves"),
+    ("editor_portal_pending", "Now place the other end of the portal"),
+    ("level_name", "Name"),
+    ("level_goal", "Goal"),
+    ("goal_endless", "Endless"),
+    ("goal_score", "Reach a score"),
+    ("goal_survive", "Survive"),
+    ("goal_prizes", "Eat prizes"),
+    ("play_test", "▶ Play"),
+    ("clear", "Clear"),
+    ("export", "Export"),
+    ("import", "Import"),
+    ("level_invalid", "Can't load level"),
+    ("mode_classic", "Classic"),
+    ("mode_hazards", "Hazards"),
+    ("mode_tron", "Tron"),
+    ("mode_speedrun", "Speedrun"),
+    ("mode_survival", "Survival"),
+    ("mode_rhythm", "Rhythm"),
+    ("mode_dungeon", "Endless Dungeon"),
+    ("mode_boss", "Boss Rush"),
+    ("mode_coop", "Co-op"),
+    ("boss_defeated", "Boss defeated!"),
+    ("theme", "Theme"),
+    ("grid_size", "Grid Size"),
+    ("language", "Language"),
+    ("analytics", "Share Anonymous Stats"),
+    ("auto", "Auto"),
+    ("low", "Low"),
+    ("medium", "Medium"),
+    ("high", "High"),
+    ("share", "Share"),
+    ("fullscreen_btn", "⛶ Fullscreen"),
+    ("share_text", "I scored {score} points in 3D Snake!"),
+    ("error_title", "Something went wrong"),
+    ("achievement_unlocked", "Achievement unlocked:"),
+    ("achievement_grand_tour", "Grand Tour"),
+    ("event_food_rush", "Food rush!"),
+    ("event_quake", "Quake!"),
+    ("event_blackout", "Blackout!"),
+    ("new_record", "NEW RECORD!"),
+    ("announce_level", "Level {level}!"),
+    ("announce_combo", "Combo x{combo}!"),
+    ("narration", "Screen Reader"),
+    ("brief", "Brief"),
+    ("full", "Full"),
+    ("sr_score", "Score {score}"),
+    ("sr_game_over", "Game over, final score {score}"),
+    ("sr_finished", "Finished, final score {score}"),
+    ("sr_life_lost", "Life lost"),
+    ("sr_shield", "Shield up"),
+    ("sr_shield_lost", "Shield lost"),
+    ("sr_prize", "Prize ahead on the {face} face"),
+    ("sr_food", "Food on the {face} face"),
+    ("death_snake", "Ran into its own body"),
+    ("death_hazard", "Hit a hazard"),
+    ("death_turret", "Shot by a turret"),
+    ("death_wall", "Crashed into a wall"),
+    ("death_storm", "Caught by the storm"),
+    ("death_boss", "Crushed by the boss"),
+    ("death_segment", "{cause}, segment {segment}"),
+    ("death_at", "{cause} on the {face} face at ({u}, {v})"),
+    ("compass_food", "Food"),
+    ("face_front", "Front"),
+    ("face_back", "Back"),
+    ("face_left", "Left"),
+    ("face_right", "Right"),
+    ("face_top", "Top"),
+    ("face_bottom", "Bottom"),
+    ("reload", "Reload"),
+];
+
+const ES: &[(&str, &str)] = &[
+    ("loading", "Cargando 3D Snake..."),
+    ("score_label", "Puntos:"),
+    ("high_score_label", "Récord:"),
+    ("pb_label", "MP:"),
+    ("lives_label", "Vidas:"),
+    ("streak_label", "Racha"),
+    ("leaderboard_btn", "🏆 Clasificación"),
+    ("unlocks_btn", "🎨 Desbloqueos"),
+    ("shop_btn", "🪙 Tienda"),
+    ("shop", "Tienda"),
+    ("shop_sold_out", "¡Ya lo tienes todo!"),
+    ("coins_earned", "+{coins} monedas"),
+    ("unlocks", "Desbloqueos"),
+    ("lifetime_score", "Puntos totales"),
+    ("skin", "Aspecto"),
+    ("trail", "Estela"),
+    ("sound_pack", "Sonidos"),
+    ("cosmetic_theme_neon", "Tema neón"),
+    ("cosmetic_theme_retro", "Tema retro"),
+    ("cosmetic_skin_classic", "Clásico"),
+    ("cosmetic_skin_gold", "Oro"),
+    ("cosmetic_skin_ghost", "Fantasma"),
+    ("cosmetic_trail_none", "Ninguna"),
+    ("cosmetic_trail_sparks", "Chispas"),
+    ("cosmetic_sound_classic", "Clásicos"),
+    ("cosmetic_sound_chiptune", "Chiptune"),
+    ("cosmetic_unlocked", "Desbloqueado:"),
+    ("unlock_by_score", "Consigue {points} puntos en total ({progress}/{points})"),
+    ("unlock_by_achievement", "Logra \"{achievement}\""),
+    ("settings_btn", "⚙ Ajustes"),
+    ("game_over", "Fin del juego"),
+    ("final_score", "Puntos: {score}"),
+    ("finish_time", "Terminado en {time}"),
+    ("name_placeholder", "Tu nombre"),
+    ("submit_score", "Enviar puntuación"),
+    ("restart_hint", "Pulsa 'R' o Enviar para reiniciar"),
+    ("rewind_hint", "Pulsa Retroceso para rebobinar"),
+    ("top_players", "Los 10 mejores"),
+    ("no_scores", "¡Aún no hay puntuaciones!"),
+    ("all_players", "Todos"),
+    ("friends", "Amigos"),
+    ("no_friends", "Marca a un jugador con la estrella para añadirlo como amigo."),
+    ("beat_friend", "¡Superaste el récord de {name}!"),
+    ("load_failed", "No se pudieron cargar las puntuaciones."),
+    ("close", "Cerrar"),
+    ("controls_hint", "WASD / Flechas para moverte | R para reiniciar | F pantalla completa"),
+    ("settings", "Ajustes"),
+    ("master_volume", "Volumen general"),
+    ("sfx_volume", "Volumen de efectos"),
+    ("announcer", "Locutor"),
+    ("controls", "Controles"),
+    ("graphics", "Gráficos"),
+    ("ambient_occlusion", "Oclusión ambiental"),
+    ("projection", "Cámara"),
+    ("perspective", "Perspectiva"),
+    ("orthographic", "Ortográfica"),
+    ("far_side_view", "Vista del lado opuesto"),
+    ("food_hint", "Pista de comida"),
+    ("director", "Dificultad adaptativa"),
+    ("record_clips", "Grabar clips"),
+    ("save_clip", "Guardar clip"),
+    ("high_contrast", "Alto contraste"),
+    ("reduced_motion", "Movimiento reducido"),
+    ("on", "Sí"),
+    ("off", "No"),
+    ("paused", "Pausa"),
+    ("resume", "Continuar"),
+    ("restart", "Reiniciar"),
+    ("rewind", "⏪ Rebobinar"),
+    ("leaderboard", "Clasificación"),
+    ("play", "Jugar"),
+    ("modes", "Modo"),
+    ("main_menu", "Menú principal"),
+    ("back", "Volver"),
+    ("level_editor", "Editor de niveles"),
+    ("community_levels", "Niveles de la comunidad"),
+    ("level_best", "¡Nuevo récord en este nivel!"),
+    ("new_pb", "¡Nueva mejor marca personal!"),
+    ("unranked", "Partida asistida: no puntúa"),
+    ("campaign", "Campaña"),
+    ("campaign_stars", "Estrellas ganadas:"),
+    ("editor_help", "Flechas: mover · O obstáculo · P portal · Esc: salir"),
+    ("editor_portal_pending", "Ahora coloca el otro extremo del portal"),
+    ("level_name", "Nombre"),
+    ("level_goal", "Objetivo"),
+    ("goal_endless", "Sin fin"),
+    ("goal_score", "Alcanzar una puntuación"),
+    ("goal_survive", "Sobrevivir"),
+    ("goal_prizes", "Comer premios"),
+    ("play_test", "▶ Jugar"),
+    ("clear", "Borrar"),
+    ("export", "Exportar"),
+    ("import", "Importar"),
+    ("level_invalid", "No se puede cargar el nivel"),
+    ("mode_classic", "Clásico"),
+    ("mode_hazards", "Peligros"),
+    ("mode_tron", "Tron"),
+    ("mode_speedrun", "Contrarreloj"),
+    ("mode_survival", "Supervivencia"),
+    ("mode_rhythm", "Ritmo"),
+    ("mode_dungeon", "Mazmorra sin fin"),
+    ("mode_boss", "Jefes"),
+    ("mode_coop", "Cooperativo"),
+    ("boss_defeated", "¡Jefe derrotado!"),
+    ("theme", "Tema"),
+    ("grid_size", "Tamaño de la cuadrícula"),
+    ("language", "Idioma"),
+    ("analytics", "Compartir estadísticas anónimas"),
+    ("auto", "Automático"),
+    ("low", "Bajo"),
+    ("medium", "Medio"),
+    ("high", "Alto"),
+    ("share", "Compartir"),
+    ("fullscreen_btn", "⛶ Pantalla completa"),
+    ("share_text", "¡Hice {score} puntos en 3D Snake!"),
+    ("error_title", "Algo salió mal"),
+    ("achievement_unlocked", "Logro desbloqueado:"),
+    ("achievement_grand_tour", "Gran vuelta"),
+    ("event_food_rush", "¡Lluvia de comida!"),
+    ("event_quake", "¡Terremoto!"),
+    ("event_blackout", "¡Apagón!"),
+    ("new_record", "¡NUEVO RÉCORD!"),
+    ("announce_level", "¡Nivel {level}!"),
+    ("announce_combo", "¡Combo x{combo}!"),
+    ("narration", "Lector de pantalla"),
+    ("brief", "Breve"),
+    ("full", "Completo"),
+    ("sr_score", "Puntuación {score}"),
+    ("sr_game_over", "Fin del juego, puntuación final {score}"),
+    ("sr_finished", "Terminado, puntuación final {score}"),
+    ("sr_life_lost", "Vida perdida"),
+    ("sr_shield", "Escudo activado"),
+    ("sr_shield_lost", "Escudo perdido"),
+    ("sr_prize", "Premio en la cara {face}"),
+    ("sr_food", "Comida en la cara {face}"),
+    ("death_snake", "Chocó con su propio cuerpo"),
+    ("death_hazard", "Chocó con un peligro"),
+    ("death_turret", "Alcanzado por una torreta"),
+    ("death_wall", "Chocó contra un muro"),
+    ("death_storm", "Atrapado por la tormenta"),
+    ("death_boss", "Aplastado por el jefe"),
+    ("death_segment", "{cause}, segmento {segment}"),
+    ("death_at", "{cause} en la cara {face}, en ({u}, {v})"),
+    ("compass_food", "Comida"),
+    ("face_front", "Frente"),
+    ("face_back", "Atrás"),
+    ("face_left", "Izquierda"),
+    ("face_right", "Derecha"),
+    ("face_top", "Arriba"),
+    ("face_bottom", "Abajo"),
+    ("reload", "Recargar"),
+];
+
+const DE: &[(&str, &str)] = &[
+    ("loading", "3D Snake wird geladen..."),
+    ("score_label", "Punkte:"),
+    ("high_score_label", "Rekord:"),
+    ("pb_label", "PB:"),
+    ("lives_label", "Leben:"),
+    ("streak_label", "Serie"),
+    ("leaderboard_btn", "🏆 Bestenliste"),
+    ("unlocks_btn", "🎨 Freischaltungen"),
+    ("shop_btn", "🪙 Laden"),
+    ("shop", "Laden"),
+    ("shop_sold_out", "Du hast schon alles!"),
+    ("coins_earned", "+{coins} Münzen"),
+    ("unlocks", "Freischaltungen"),
+    ("lifetime_score", "Gesamtpunkte"),
+    ("skin", "Aussehen"),
+    ("trail", "Spur"),
+    ("sound_pack", "Klänge"),
+    ("cosmetic_theme_neon", "Neon-Thema"),
+    ("cosmetic_theme_retro", "Retro-Thema"),
+    ("cosmetic_skin_classic", "Klassisch"),
+    ("cosmetic_skin_gold", "Gold"),
+    ("cosmetic_skin_ghost", "Geist"),
+    ("cosmetic_trail_none", "Keine"),
+    ("cosmetic_trail_sparks", "Funken"),
+    ("cosmetic_sound_classic", "Klassisch"),
+    ("cosmetic_sound_chiptune", "Chiptune"),
+    ("cosmetic_unlocked", "Freigeschaltet:"),
+    ("unlock_by_score", "Insgesamt {points} Punkte erzielen ({progress}/{points})"),
+    ("unlock_by_achievement", "Erfolg \"{achievement}\" erreichen"),
+    ("settings_btn", "⚙ Einstellungen"),
+    ("game_over", "Spiel vorbei"),
+    ("final_score", "Punkte: {score}"),
+    ("finish_time", "Geschafft in {time}"),
+    ("name_placeholder", "Name eingeben"),
+    ("submit_score", "Punkte eintragen"),
+    ("restart_hint", "'R' oder Eintragen für Neustart"),
+    ("rewind_hint", "Rücktaste zum Zurückspulen"),
+    ("top_players", "Top 10 Spieler"),
+    ("no_scores", "Noch keine Punkte!"),
+    ("all_players", "Alle"),
+    ("friends", "Freunde"),
+    ("no_friends", "Markiere Spieler mit dem Stern, um sie als Freunde hinzuzufügen."),
+    ("beat_friend", "Du hast den Rekord von {name} geschlagen!"),
+    ("load_failed", "Punkte konnten nicht geladen werden."),
+    ("close", "Schließen"),
+    ("controls_hint", "WASD / Pfeiltasten zum Bewegen | R für Neustart | F für Vollbild"),
+    ("settings", "Einstellungen"),
+    ("master_volume", "Gesamtlautstärke"),
+    ("sfx_volume", "Effektlautstärke"),
+    ("announcer", "Ansager"),
+    ("controls", "Steuerung"),
+    ("graphics", "Grafik"),
+    ("ambient_occlusion", "Umgebungsverdeckung"),
+    ("projection", "Kamera"),
+    ("perspective", "Perspektivisch"),
+    ("orthographic", "Orthografisch"),
+    ("far_side_view", "Rückseitenansicht"),
+    ("food_hint", "Futterhinweis"),
+    ("director", "Adaptive Schwierigkeit"),
+    ("record_clips", "Clips aufnehmen"),
+    ("save_clip", "Clip speichern"),
+    ("high_contrast", "Hoher Kontrast"),
+    ("reduced_motion", "Weniger Bewegung"),
+    ("on", "An"),
+    ("off", "Aus"),
+    ("paused", "Pausiert"),
+    ("resume", "Weiter"),
+    ("restart", "Neu starten"),
+    ("rewind", "⏪ Zurückspulen"),
+    ("leaderboard", "Bestenliste"),
+    ("play", "Spielen"),
+    ("modes", "Modus"),
+    ("main_menu", "Hauptmenü"),
+    ("back", "Zurück"),
+    ("level_editor", "Level-Editor"),
+    ("community_levels", "Community-Level"),
+    ("level_best", "Neuer Bestwert für dieses Level!"),
+    ("new_pb", "Neue persönliche Bestleistung!"),
+    ("unranked", "Unterstützter Lauf: nicht gewertet"),
+    ("campaign", "Kampagne"),
+    ("campaign_stars", "Sterne verdient:"),
+    ("editor_help", "Pfeile bewegen · O Hindernis · P Portal · Esc verlässt"),
+    ("editor_portal_pending", "Jetzt das andere Ende des Portals setzen"),
+    ("level_name", "Name"),
+    ("level_goal", "Ziel"),
+    ("goal_endless", "Endlos"),
+    ("goal_score", "Punktzahl erreichen"),
+    ("goal_survive", "Überleben"),
+    ("goal_prizes", "Preise fressen"),
+    ("play_test", "▶ Spielen"),
+    ("clear", "Leeren"),
+    ("export", "Exportieren"),
+    ("import", "Importieren"),
+    ("level_invalid", "Level kann nicht geladen werden"),
+    ("mode_classic", "Klassisch"),
+    ("mode_hazards", "Gefahren"),
+    ("mode_tron", "Tron"),
+    ("mode_speedrun", "Speedrun"),
+    ("mode_survival", "Überleben"),
+    ("mode_rhythm", "Rhythmus"),
+    ("mode_dungeon", "Endloses Verlies"),
+    ("mode_boss", "Bossjagd"),
+    ("mode_coop", "Koop"),
+    ("boss_defeated", "Boss besiegt!"),
+    ("theme", "Design"),
+    ("grid_size", "Rastergröße"),
+    ("language", "Sprache"),
+    ("analytics", "Anonyme Statistiken teilen"),
+    ("auto", "Automatisch"),
+    ("low", "Niedrig"),
+    ("medium", "Mittel"),
+    ("high", "Hoch"),
+    ("share", "Teilen"),
+    ("fullscreen_btn", "⛶ Vollbild"),
+    ("share_text", "Ich habe {score} Punkte in 3D Snake erreicht!"),
+    ("error_title", "Etwas ist schiefgelaufen"),
+    ("achievement_unlocked", "Erfolg freigeschaltet:"),
+    ("achievement_grand_tour", "Große Rundreise"),
+    ("event_food_rush", "Futterrausch!"),
+    ("event_quake", "Erdbeben!"),
+    ("event_blackout", "Stromausfall!"),
+    ("new_record", "NEUER REKORD!"),
+    ("announce_level", "Level {level}!"),
+    ("announce_combo", "Kombo x{combo}!"),
+    ("narration", "Bildschirmleser"),
+    ("brief", "Kurz"),
+    ("full", "Ausführlich"),
+    ("sr_score", "Punkte {score}"),
+    ("sr_game_over", "Spiel vorbei, Endstand {score}"),
+    ("sr_finished", "Geschafft, Endstand {score}"),
+    ("sr_life_lost", "Leben verloren"),
+    ("sr_shield", "Schild aktiv"),
+    ("sr_shield_lost", "Schild verloren"),
+    ("sr_prize", "Preis auf der Seite {face}"),
+    ("sr_food", "Futter auf der Seite {face}"),
+    ("death_snake", "In den eigenen Körper gefahren"),
+    ("death_hazard", "Gegen eine Gefahr gestoßen"),
+    ("death_turret", "Von einem Geschütz getroffen"),
+    ("death_wall", "Gegen eine Wand gefahren"),
+    ("death_storm", "Vom Sturm erfasst"),
+    ("death_boss", "Vom Boss zerquetscht"),
+    ("death_segment", "{cause}, Segment {segment}"),
+    ("death_at", "{cause} auf der Seite {face} bei ({u}, {v})"),
+    ("compass_food", "Futter"),
+    ("face_front", "Vorne"),
+    ("face_back", "Hinten"),
+    ("face_left", "Links"),
+    ("face_right", "Rechts"),
+    ("face_top", "Oben"),
+    ("face_bottom", "Unten"),
+    ("reload", "Neu laden"),
+];
+
+impl Language {
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::En => "en",
+            Language::Es => "es",
+            Language::De => "de",
+        }
+    }
+
+    fn pack(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::En => EN,
+            Language::Es => ES,
+            Language::De => DE,
+        }
+    }
+
+    // Picks a language from `navigator.language` ("es-MX" -> Spanish).
+    pub fn detect() -> Language {
+        let tag = web_sys::window()
+            .and_then(|w| w.navigator().language())
+            .unwrap_or_default()
+            .to_lowercase();
+        match tag.split('-').next().unwrap_or("") {
+            "es" => Language::Es,
+            "de" => Language::De,
+            _ => Language::En,
+        }
+    }
+}
+
+pub fn set_language(language: Language) {
+    CURRENT.with(|c| c.set(language));
+}
+
+pub fn language() -> Language {
+    CURRENT.with(|c| c.get())
+}
+
+// Looks up a UI string in the current language.
+pub fn t(key: &'static str) -> &'static str {
+    let find = |pack: &'static [(&'static str, &'static str)]| {
+        pack.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+    };
+    find(language().pack()).or_else(|| find(EN)).unwrap_or(key)
+}
+
+// Translates static markup: elements tagged with `data-i18n` get their text
+// replaced, `data-i18n-placeholder` sets the placeholder attribute.
+pub fn apply_to_document(document: &web_sys::Document) {
+    if let Some(root) = document.document_element() {
+        root.set_attribute("lang", language().code()).unwrap_or(());
+    }
+
+    let translate = |selector: &str, apply: &dyn Fn(&web_sys::Element, &'static str)| {
+        let attr = &selector[1..selector.len() - 1];
+        if let Ok(nodes) = document.query_selector_all(selector) {
+            for i in 0..nodes.length() {
+                let element = match nodes.item(i).and_then(|n| n.dyn_into::<web_sys::Element>().ok()) {
+                    Some(element) => element,
+                    None => continue,
+                };
+                if let Some(key) = element.get_attribute(attr) {
+                    if let Some((k, _)) = EN.iter().find(|(k, _)| *k == key) {
+                        apply(&element, t(k));
+                    }
+                }
+            }
+        }
+    };
+
+    translate("[data-i18n]", &|e, text| e.set_text_content(Some(text)));
+    translate("[data-i18n-placeholder]", &|e, text| {
+        e.set_attribute("placeholder", text).unwrap_or(());
+    });
+}
@@ -1,15 +1,106 @@
+use std::cmp::Reverse;
+
 use serde::{Serialize, Deserialize};
 
+use crate::dom::Dom;
+use crate::error;
+use crate::i18n::t;
+
+const SCORES_KEY: &str = "snake3d_scores";
+const NAME_KEY: &str = "snake3d_player_name";
+// Every submission ever made on this device, oldest first.
+const HISTORY_KEY: &str = "snake3d_history";
+// Names the player starred on the board.
+const FRIENDS_KEY: &str = "snake3d_friends";
+const MAX_ENTRIES: usize = 10;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LeaderboardEntry {
-    name: String,
-    score: u32,
+    pub name: String,
+    pub score: u32,
+    // Two-letter country code, when the browser's locale has one.
+    #[serde(default)]
+    pub region: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Submission {
+    pub name: String,
+    pub score: u32,
+    // Milliseconds since the Unix epoch.
+    pub at: f64,
+    #[serde(default)]
+    pub region: Option<String>,
+}
+
+// One point on a history chart.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct TimelinePoint {
+    pub at: f64,
+    pub score: u32,
+}
+
+// The country from the browser's locale ("es-MX" -> "MX"), if it names one.
+pub fn region() -> Option<String> {
+    let tag = web_sys::window()?.navigator().language()?;
+    tag.split('-')
+        .skip(1)
+        .find(|part| part.len() == 2 && part.chars().all(|c| c.is_ascii_alphabetic()))
+        .map(|part| part.to_ascii_uppercase())
+}
+
+// The flag emoji for a country code, built from regional indicator letters.
+pub fn flag(region: &str) -> String {
+    region
+        .chars()
+        .filter_map(|c| char::from_u32(0x1F1E6 + (c.to_ascii_uppercase() as u32).checked_sub('A' as u32)?))
+        .collect()
+}
+
+fn history() -> Vec<Submission> {
+    error::storage()
+        .ok()
+        .and_then(|storage| storage.get_item(HISTORY_KEY).ok().flatten())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+// The best score so far, each time it went up.
+pub fn top_score_timeline() -> Vec<TimelinePoint> {
+    let mut best = 0;
+    history()
+        .into_iter()
+        .filter(|s| {
+            let record = s.score > best;
+            best = best.max(s.score);
+            record
+        })
+        .map(|s| TimelinePoint { at: s.at, score: s.score })
+        .collect()
+}
+
+// Every score `name` has submitted, oldest first.
+pub fn player_progression(name: &str) -> Vec<TimelinePoint> {
+    history()
+        .into_iter()
+        .filter(|s| s.name == name)
+        .map(|s| TimelinePoint { at: s.at, score: s.score })
+        .collect()
+}
+
+impl LeaderboardEntry {
+    // The name with its country's flag in front, when known.
+    pub fn label(&self) -> String {
+        match &self.region {
+            Some(region) => format!("{} {}", flag(region), self.name),
+            None => self.name.clone(),
+        }
+    }
 }
 
 fn get_leaderboard() -> Result<Vec<LeaderboardEntry>, Box<dyn std::error::Error>> {
-    let window = web_sys::window().unwrap();
-    let storage = window.local_storage().unwrap().unwrap();
-    if let Ok(Some(json)) = storage.get_item("snake3d_scores") {
+    let storage = error::storage()?;
+    if let Ok(Some(json)) = storage.get_item(SCORES_KEY) {
         let entries: Vec<LeaderboardEntry> = serde_json::from_str(&json)?;
         Ok(entries)
     } else {
@@ -18,60 +109,238 @@ fn get_leaderboard() -> Result<Vec<LeaderboardEntry>, Box<dyn std::error::Error>
 }
 
 pub fn save_score(name: &str, score: u32) {
-    let window = web_sys::window().unwrap();
-    let storage = window.local_storage().unwrap().unwrap();
+    let storage = match error::storage() {
+        Ok(storage) => storage,
+        Err(e) => {
+            log::warn!("Score not saved: {}", e);
+            return;
+        }
+    };
 
+    let region = region();
     let mut entries = get_leaderboard().unwrap_or_default();
-    entries.push(LeaderboardEntry { name: name.to_string(), score });
+    entries.push(LeaderboardEntry { name: name.to_string(), score, region: region.clone() });
+    store_entries(&storage, entries);
+    let _ = storage.set_item(NAME_KEY, name);
+
+    let mut submissions = history();
+    submissions.push(Submission { name: name.to_string(), score, at: js_sys::Date::now(), region });
+    store_history(&storage, &submissions);
+}
+
+fn store_entries(storage: &web_sys::Storage, mut entries: Vec<LeaderboardEntry>) {
     // Sort by score descending
-    entries.sort_by(|a, b| b.score.cmp(&a.score));
+    entries.sort_by_key(|e| Reverse(e.score));
     // Keep top 10
-    if entries.len() > 10 {
-        entries.truncate(10);
+    if entries.len() > MAX_ENTRIES {
+        entries.truncate(MAX_ENTRIES);
     }
 
     if let Ok(json) = serde_json::to_string(&entries) {
-        let _ = storage.set_item("snake3d_scores", &json);
+        let _ = storage.set_item(SCORES_KEY, &json);
+    }
+}
+
+fn store_history(storage: &web_sys::Storage, submissions: &[Submission]) {
+    if let Ok(json) = serde_json::to_string(submissions) {
+        let _ = storage.set_item(HISTORY_KEY, &json);
+    }
+}
+
+// Every submission on this device as JSON, for moving scores to another
+// browser with `import_scores`.
+pub fn export_json() -> String {
+    serde_json::to_string(&history()).unwrap_or_else(|_| "[]".to_string())
+}
+
+// The same as a spreadsheet-friendly `name,score,at,region` table.
+pub fn export_csv() -> String {
+    let mut csv = String::from("name,score,at,region\n");
+    for s in history() {
+        let region = s.region.unwrap_or_default();
+        csv.push_str(&format!("\"{}\",{},{},{}\n", s.name.replace('"', "\"\""), s.score, s.at, region));
     }
+    csv
+}
+
+// Merges a JSON export into this device's history and top 10. Submissions
+// already present (same name, score and time) are skipped, so importing the
+// same dump twice is harmless. Returns how many were new.
+pub fn import_scores(json: &str) -> Result<usize, Box<dyn std::error::Error>> {
+    let imported: Vec<Submission> = serde_json::from_str(json)?;
+    let storage = error::storage()?;
+    let mut submissions = history();
+    let mut entries = get_leaderboard().unwrap_or_default();
+    let mut added = 0;
+    for s in imported {
+        let known = submissions.iter().any(|k| k.name == s.name && k.score == s.score && k.at == s.at);
+        if known {
+            continue;
+        }
+        entries.push(LeaderboardEntry { name: s.name.clone(), score: s.score, region: s.region.clone() });
+        submissions.push(s);
+        added += 1;
+    }
+    submissions.sort_by(|a, b| a.at.total_cmp(&b.at));
+    store_entries(&storage, entries);
+    store_history(&storage, &submissions);
+    Ok(added)
+}
+
+// The stored top 10, best first; empty if storage is unavailable.
+pub fn top_scores() -> Vec<LeaderboardEntry> {
+    get_leaderboard().unwrap_or_default()
+}
+
+// The best 10 submissions from one country, best first. Built from the
+// history, since the global top 10 may hold none of them.
+pub fn top_scores_in(region: &str) -> Vec<LeaderboardEntry> {
+    let mut entries: Vec<LeaderboardEntry> = history()
+        .into_iter()
+        .filter(|s| s.region.as_deref().is_some_and(|r| r.eq_ignore_ascii_case(region)))
+        .map(|s| LeaderboardEntry { name: s.name, score: s.score, region: s.region })
+        .collect();
+    entries.sort_by_key(|e| Reverse(e.score));
+    entries.truncate(MAX_ENTRIES);
+    entries
+}
+
+// Friends are names starred on this device. Their scores arrive like any
+// other, from playing here or from an `importScores` of their export.
+pub fn friends() -> Vec<String> {
+    error::storage()
+        .ok()
+        .and_then(|storage| storage.get_item(FRIENDS_KEY).ok().flatten())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+pub fn is_friend(name: &str) -> bool {
+    friends().iter().any(|f| f == name)
+}
+
+pub fn set_friend(name: &str, friend: bool) {
+    let mut names = friends();
+    names.retain(|f| f != name);
+    if friend {
+        names.push(name.to_string());
+    }
+    if let (Ok(storage), Ok(json)) = (error::storage(), serde_json::to_string(&names)) {
+        let _ = storage.set_item(FRIENDS_KEY, &json);
+    }
+}
+
+// Each friend's best submission, best first.
+pub fn friend_scores() -> Vec<LeaderboardEntry> {
+    let names = friends();
+    let mut entries: Vec<LeaderboardEntry> = Vec::new();
+    for s in history().into_iter().filter(|s| names.contains(&s.name)) {
+        match entries.iter_mut().find(|e| e.name == s.name) {
+            Some(best) if best.score >= s.score => {}
+            Some(best) => *best = LeaderboardEntry { name: s.name, score: s.score, region: s.region },
+            None => entries.push(LeaderboardEntry { name: s.name, score: s.score, region: s.region }),
+        }
+    }
+    entries.sort_by_key(|e| Reverse(e.score));
+    entries
+}
 
-    update_leaderboard_ui();
+// The friend with the highest best that `score` beats and the player's own
+// best so far didn't, for the game over screen.
+pub fn beaten_friend(score: u32) -> Option<String> {
+    let name = remembered_name();
+    let own_best = history().iter().filter(|s| Some(&s.name) == name.as_ref()).map(|s| s.score).max().unwrap_or(0);
+    friend_scores()
+        .into_iter()
+        .find(|e| e.score < score && e.score >= own_best && Some(&e.name) != name.as_ref())
+        .map(|e| e.name)
+}
+
+// Whether `score` would make it onto the local top 10.
+pub fn qualifies(score: u32) -> bool {
+    if score == 0 {
+        return false;
+    }
+    let entries = get_leaderboard().unwrap_or_default();
+    entries.len() < MAX_ENTRIES || entries.last().map(|e| score > e.score).unwrap_or(true)
+}
+
+// Name used for the last submitted score, offered again next time.
+pub fn remembered_name() -> Option<String> {
+    error::storage().ok()?.get_item(NAME_KEY).ok().flatten()
+}
+
+// Shows the name entry on the game over screen only for scores that make the
+// board, prefilled with the remembered name.
+pub fn prepare_name_entry(dom: &Dom, score: u32) {
+    let entry = match dom.get("name-entry") {
+        Some(entry) => entry,
+        None => return,
+    };
+    if !qualifies(score) {
+        entry.class_list().add_1("hidden").unwrap_or(());
+        return;
+    }
+    entry.class_list().remove_1("hidden").unwrap_or(());
+    if let Ok(input) = dom.element::<web_sys::HtmlInputElement>("player-name") {
+        input.set_value(&remembered_name().unwrap_or_default());
+        input.focus().unwrap_or(());
+        input.select();
+    }
+}
+
+pub fn update_leaderboard_ui(dom: &Dom) {
+    if let Err(e) = render_leaderboard(dom) {
+        log::error!("Failed to render leaderboard: {}", e);
+    }
 }
 
-pub fn update_leaderboard_ui() {
-    let document = web_sys::window().unwrap().document().unwrap();
-    if let Some(list) = document.get_element_by_id("leaderboard-list") {
+// The list shows the top 10, or friends' bests while `data-view="friends"`
+// is set on it by the tabs.
+fn render_leaderboard(dom: &Dom) -> error::Result<()> {
+    let document = error::document()?;
+    if let Some(list) = dom.get("leaderboard-list") {
         list.set_inner_html("");
 
-        match get_leaderboard() {
+        let friends_view = list.get_attribute("data-view").as_deref() == Some("friends");
+        let entries = if friends_view { Ok(friend_scores()) } else { get_leaderboard() };
+        match entries {
             Ok(entries) => {
                  if entries.is_empty() {
-                     let li = document.create_element("li").unwrap();
-                     li.set_text_content(Some("No scores yet!"));
+                     let li = document.create_element("li")?;
+                     li.set_text_content(Some(t(if friends_view { "no_friends" } else { "no_scores" })));
                      li.set_attribute("style", "justify-content: center; color: #888;").unwrap_or(());
-                     list.append_child(&li).unwrap();
+                     list.append_child(&li)?;
                 } else {
                     for (i, entry) in entries.iter().enumerate() {
-                        let li = document.create_element("li").unwrap();
+                        let li = document.create_element("li")?;
 
-                        let name_span = document.create_element("span").unwrap();
-                        name_span.set_text_content(Some(&format!("{}. {}", i + 1, entry.name)));
+                        let name_span = document.create_element("span")?;
+                        name_span.set_text_content(Some(&format!("{}. {}", i + 1, entry.label())));
+                        // Clicks are handled on the list (see `attach_leaderboard`).
+                        let star = document.create_element("button")?;
+                        star.set_class_name("friend-star");
+                        star.set_attribute("data-friend", &entry.name)?;
+                        star.set_text_content(Some(if is_friend(&entry.name) { "★" } else { "☆" }));
+                        name_span.append_child(&star)?;
 
-                        let score_span = document.create_element("span").unwrap();
+                        let score_span = document.create_element("span")?;
                         score_span.set_text_content(Some(&entry.score.to_string()));
                         score_span.set_attribute("style", "color: #ffeb3b;").unwrap_or(());
 
-                        li.append_child(&name_span).unwrap();
-                        li.append_child(&score_span).unwrap();
-                        list.append_child(&li).unwrap();
+                        li.append_child(&name_span)?;
+                        li.append_child(&score_span)?;
+                        list.append_child(&li)?;
                     }
                 }
             },
              Err(_) => {
-                let li = document.create_element("li").unwrap();
-                li.set_text_content(Some("Failed to load scores."));
+                let li = document.create_element("li")?;
+                li.set_text_content(Some(t("load_failed")));
                 li.set_attribute("style", "justify-content: center; color: #f44;").unwrap_or(());
-                list.append_child(&li).unwrap();
+                list.append_child(&li)?;
             }
         }
     }
+    Ok(())
 }
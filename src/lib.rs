@@ -1,361 +1,702 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use serde::Deserialize;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use three_d::*;
-use crate::game::{GameState, Direction};
-use crate::renderer::GameRenderer;
-use crate::audio::AudioPlayer;
-
-mod game;
+use snake3d_core::{Direction, GameMode, SpeedCurve, VoteTally};
+
+use crate::analytics::Analytics;
+use crate::app::{App, Controls};
+use crate::dom::Dom;
+use crate::error::AppError;
+use crate::query::UrlConfig;
+use crate::seasons::Season;
+use crate::settings::{Settings, Theme, MAX_GRID_SIZE, MIN_GRID_SIZE};
+
+// Fastest `tickRate` a host can ask for, in moves per second.
+const MAX_TICK_RATE: f64 = 30.0;
+
+mod achievements;
+mod analytics;
+mod app;
+mod assets;
+mod atlas;
+mod backend;
+mod background;
+mod bests;
+mod dom;
+#[cfg(feature = "editor")]
+mod editor;
+mod element;
+mod error;
+mod events;
+mod fullscreen;
+mod gif;
+#[cfg(feature = "ui")]
+mod gui;
+#[cfg(not(feature = "ui"))]
+#[path = "headless.rs"]
+mod gui;
+mod i18n;
 mod renderer;
+mod resolution;
 mod audio;
+mod campaign;
+mod capture;
+mod chat;
+mod clips;
+mod community;
 mod leaderboard;
+mod loader;
+mod models;
+#[cfg(not(feature = "minimal"))]
+mod music;
+mod narrator;
+mod offscreen;
+mod particles;
+mod profiler;
+mod query;
+mod seasons;
+mod settings;
+mod share;
+mod shop;
+#[cfg(not(feature = "minimal"))]
+mod speedrun;
+mod ssao;
+mod storage;
+mod transition;
+mod unlocks;
+mod xr;
 
 use leaderboard::{save_score, update_leaderboard_ui};
 
+// Options accepted by the `SnakeGame` constructor, e.g. `{ gridSize: 12, mode: "classic" }`.
+// Anything left out comes from the player's saved settings.
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct GameOptions {
+    grid_size: Option<i32>,
+    mode: Option<String>,
+    // Speed curve overrides, in seconds per tick (see `SpeedCurve`).
+    base_interval: Option<f64>,
+    min_interval: Option<f64>,
+    speed_ramp: Option<f64>,
+    // A fixed pace instead, in ticks (moves) per second, up to 30 for fast
+    // party and co-op play.
+    tick_rate: Option<f64>,
+    // "html" (default) drives the page's own panels, "canvas" draws the
+    // menus inside the canvas instead.
+    ui: Option<String>,
+    // "neon" or "retro"; overrides the saved theme.
+    theme: Option<String>,
+    // Put in front of every element id the game looks up, so several games
+    // can share a page (see `Dom`).
+    id_prefix: Option<String>,
+    // Where run statistics are POSTed for players who opt in; nothing is
+    // collected without one.
+    analytics_endpoint: Option<String>,
+    // Party play: the snake turns whichever way most votes said over each
+    // second, with votes from `game.vote()` and, given a URL, a chat bridge
+    // WebSocket sending one direction per message.
+    votes: Option<bool>,
+    vote_socket: Option<String>,
+    // "halloween", "winter" or "none" forces the seasonal event; otherwise
+    // the calendar at `seasonsUrl` (or the built-in one) decides by date.
+    season: Option<String>,
+    seasons_url: Option<String>,
+    // JSON array of community levels for the main menu's list.
+    levels_url: Option<String>,
+    // glTF models in place of the built-in snake head, body, food and prize
+    // (see `models.rs`).
+    models: Option<models::ModelUrls>,
+}
+
 #[wasm_bindgen(start)]
-pub fn init() -> Result<(), JsValue> {
-    web_sys::console::log_1(&"Rust: init started".into());
-    console_error_panic_hook::set_once();
-    console_log::init_with_level(log::Level::Debug).unwrap();
-
-    let window = web_sys::window().unwrap();
-    let document = window.document().unwrap();
-    let canvas = document.get_element_by_id("canvas").unwrap()
-        .dyn_into::<web_sys::HtmlCanvasElement>().unwrap();
-
-    log::info!("Found canvas, creating Window...");
-    let window = Window::new(WindowSettings {
-        title: "3D Snake".to_string(),
-        min_size: (100, 100),
-        ..Default::default()
-    })
-    .unwrap();
-    log::info!("Window created successfully!");
-
-    let context = window.gl();
-    let grid_size = 10;
-    let mut game = GameState::new(grid_size);
-    let mut renderer = GameRenderer::new(context, grid_size);
-    let audio = AudioPlayer::new();
-
-    // Game loop variables
-    let mut time_since_last_move = 0.0;
-    let mut has_logged = false;
-
-    // Shared state for mobile controls (Arc<Mutex<>> not needed as closure captures it, but need Interior Mutability for event listeners)
-    // Since event listeners are callbacks, they can't easily share state with the main loop unless we use Rc<RefCell<>>
-    // However, the main loop is a closure passed to render_loop.
-    // Simple approach: Polling global variables or using specific events in the frame input if possible?
-    // No, `three-d` events are from window.
-    // But our buttons are HTML elements. `three-d` might not capture clicks on them if they are outside canvas?
-    // Actually, we can just check a shared state that the click handlers update.
-
-    use std::rc::Rc;
-    use std::cell::RefCell;
-
-    let mobile_input = Rc::new(RefCell::new(None));
-    let mobile_input_clone = mobile_input.clone();
+pub fn init() {
+    error::install_panic_hook();
+    console_log::init_with_level(log::Level::Debug).unwrap_or(());
+    if let Err(e) = element::define() {
+        log::warn!("<{}> is not available: {:?}", element::TAG, e);
+    }
+}
+
+#[wasm_bindgen]
+pub struct SnakeGame {
+    canvas: web_sys::HtmlCanvasElement,
+    controls: Rc<RefCell<Controls>>,
+    started: Cell<bool>,
+}
+
+#[wasm_bindgen]
+impl SnakeGame {
+    #[wasm_bindgen(constructor)]
+    pub fn new(canvas_id: &str, config: JsValue) -> Result<SnakeGame, JsValue> {
+        Self::create(canvas_id, config).map_err(report)
+    }
+
+    // Creates the WebGL window and starts the render loop on first call;
+    // afterwards it just resumes a paused game.
+    pub fn start(&self) -> Result<(), JsValue> {
+        self.controls.borrow_mut().paused = false;
+        if self.started.replace(true) {
+            return Ok(());
+        }
+        self.launch().map_err(report)
+    }
+
+    pub fn pause(&self) {
+        self.controls.borrow_mut().paused = true;
+    }
+
+    // Switches mode and restarts the current run.
+    #[wasm_bindgen(js_name = setMode)]
+    pub fn set_mode(&self, mode: &str) -> Result<(), JsValue> {
+        self.controls.borrow_mut().mode = parse_mode(mode)?;
+        Ok(())
+    }
 
+    // Rolling frame timings in ms (p50/p95/p99/max for frame, update,
+    // render and instance upload), for diagnosing slow devices.
+    #[wasm_bindgen(getter, js_name = frameStats)]
+    pub fn frame_stats(&self) -> Result<JsValue, JsValue> {
+        profiler::to_js(&self.controls.borrow().profiler.stats())
+    }
+
+    // `{ active, webgpu }`: the graphics API drawing the game, and whether
+    // the browser offers WebGPU.
+    #[wasm_bindgen(getter, js_name = renderBackend)]
+    pub fn render_backend(&self) -> Result<JsValue, JsValue> {
+        let json = serde_json::json!({ "active": backend::WEBGL2, "webgpu": backend::webgpu_available() });
+        js_sys::JSON::parse(&json.to_string())
+    }
+
+    // Saves the last seconds of play as a GIF, shared or downloaded like the
+    // share card. Needs Record Clips on in the settings.
+    #[wasm_bindgen(js_name = saveClip)]
+    pub fn save_clip(&self) {
+        self.controls.borrow_mut().clip_requested = true;
+    }
+
+    // Promise of whether a WebXR headset could run the game in VR.
+    #[wasm_bindgen(js_name = xrSupported)]
+    pub fn xr_supported(&self) -> js_sys::Promise {
+        xr::supported()
+    }
+
+    // Submitted scores over time as `[{ at, score }]`, `at` in ms since the
+    // epoch: the best score each time it rose, or every score `name` submitted.
+    #[wasm_bindgen(js_name = scoreHistory)]
+    pub fn score_history(&self, name: Option<String>) -> Result<JsValue, JsValue> {
+        let points = match name {
+            Some(name) => leaderboard::player_progression(&name),
+            None => leaderboard::top_score_timeline(),
+        };
+        let json = serde_json::to_string(&points).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        js_sys::JSON::parse(&json)
+    }
+
+    // The local top 10 as `[{ name, score, region }]`, or the best 10 from
+    // one country when given a code like 'DE'.
+    #[wasm_bindgen(js_name = topScores)]
+    pub fn top_scores(&self, region: Option<String>) -> Result<JsValue, JsValue> {
+        let entries = match region {
+            Some(region) => leaderboard::top_scores_in(&region),
+            None => leaderboard::top_scores(),
+        };
+        let json = serde_json::to_string(&entries).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        js_sys::JSON::parse(&json)
+    }
+
+    // All local submissions as a string, `format` 'json' (the default) or
+    // 'csv'. JSON exports can be fed back to `importScores`.
+    #[wasm_bindgen(js_name = exportScores)]
+    pub fn export_scores(&self, format: Option<String>) -> Result<String, JsValue> {
+        match format.as_deref() {
+            None | Some("json") => Ok(leaderboard::export_json()),
+            Some("csv") => Ok(leaderboard::export_csv()),
+            Some(other) => Err(JsValue::from_str(&format!("unknown export format '{}'", other))),
+        }
+    }
+
+    // Merges a JSON export into this device's scores, skipping ones already
+    // present. Returns how many were added.
+    #[wasm_bindgen(js_name = importScores)]
+    pub fn import_scores(&self, json: &str) -> Result<u32, JsValue> {
+        let added = leaderboard::import_scores(json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        update_leaderboard_ui(&self.controls.borrow().dom);
+        Ok(added as u32)
+    }
+
+    // The level in the editor as JSON (see `snake3d_core::level`).
+    #[cfg(feature = "editor")]
+    #[wasm_bindgen(js_name = exportLevel)]
+    pub fn export_level(&self) -> String {
+        self.controls.borrow().editor.export()
+    }
+
+    // Loads a level into the editor, refusing one that doesn't parse or
+    // can't be played.
+    #[cfg(feature = "editor")]
+    #[wasm_bindgen(js_name = importLevel)]
+    pub fn import_level(&self, json: &str) -> Result<(), JsValue> {
+        self.controls.borrow_mut().editor.import(json).map_err(|e| JsValue::from_str(&e))
+    }
+
+    // Coins to spend in the shop.
+    #[wasm_bindgen(getter)]
+    pub fn coins(&self) -> u32 {
+        shop::Wallet::load().coins
+    }
+
+    // Names starred as friends on this device.
+    pub fn friends(&self) -> Vec<String> {
+        leaderboard::friends()
+    }
+
+    #[wasm_bindgen(js_name = addFriend)]
+    pub fn add_friend(&self, name: &str) {
+        leaderboard::set_friend(name, true);
+        update_leaderboard_ui(&self.controls.borrow().dom);
+    }
+
+    #[wasm_bindgen(js_name = removeFriend)]
+    pub fn remove_friend(&self, name: &str) {
+        leaderboard::set_friend(name, false);
+        update_leaderboard_ui(&self.controls.borrow().dom);
+    }
+
+    // Each friend's best as `[{ name, score, region }]`, best first.
+    #[wasm_bindgen(js_name = friendScores)]
+    pub fn friend_scores(&self) -> Result<JsValue, JsValue> {
+        let json = serde_json::to_string(&leaderboard::friend_scores()).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        js_sys::JSON::parse(&json)
+    }
+
+    // Casts a vote ("up", "!left", ...) in party play. Returns false when it
+    // isn't a direction or voting is off.
+    pub fn vote(&self, direction: &str) -> bool {
+        chat::vote(&self.controls, direction)
+    }
+
+    // Called with the final score whenever a run ends. Same as
+    // `on('gameover', callback)`.
+    #[wasm_bindgen(js_name = onGameOver)]
+    pub fn on_game_over(&self, callback: js_sys::Function) {
+        self.on("gameover", callback).unwrap_or(());
+    }
+
+    // Subscribes to 'score' (the new score), 'gameover' (the final score),
+    // 'levelup' (the new level) or 'achievement' (its id).
+    pub fn on(&self, event: &str, callback: js_sys::Function) -> Result<(), JsValue> {
+        self.controls.borrow_mut().listeners.add(event, callback)?;
+        Ok(())
+    }
+}
+
+impl SnakeGame {
+    fn create(canvas_id: &str, config: JsValue) -> error::Result<SnakeGame> {
+        let document = error::document()?;
+        let canvas = error::element::<web_sys::HtmlCanvasElement>(&document, canvas_id)?;
+
+        let options: GameOptions = if config.is_undefined() || config.is_null() {
+            GameOptions::default()
+        } else {
+            let json = js_sys::JSON::stringify(&config)?;
+            serde_json::from_str(&String::from(json))
+                .map_err(|e| AppError::InvalidConfig(e.to_string()))?
+        };
+
+        // A linked setup goes over the saved settings, and the host's
+        // options over both.
+        let url = UrlConfig::from_location();
+        let mut settings = Settings::load();
+        url.apply(&mut settings);
+        if let Some(grid_size) = options.grid_size {
+            if !(MIN_GRID_SIZE..=MAX_GRID_SIZE).contains(&grid_size) {
+                return Err(AppError::InvalidConfig(format!(
+                    "gridSize must be between {} and {}",
+                    MIN_GRID_SIZE, MAX_GRID_SIZE
+                )));
+            }
+            settings.grid_size = grid_size;
+        }
+        if let Some(name) = &options.theme {
+            settings.theme = Theme::from_name(name)
+                .ok_or_else(|| AppError::InvalidConfig(format!("unknown theme '{}'", name)))?;
+        }
+
+        i18n::set_language(settings.effective_language());
+
+        let controls = Rc::new(RefCell::new(Controls {
+            settings,
+            dom: Dom::new(options.id_prefix.as_deref().unwrap_or_default()),
+            analytics: Analytics::new(options.analytics_endpoint.clone()),
+            ..Default::default()
+        }));
+        if let Some(mode) = url.mode {
+            controls.borrow_mut().mode = mode;
+        }
+        if let Some(name) = &options.mode {
+            controls.borrow_mut().mode = parse_mode(name)?;
+        }
+        let mode = controls.borrow().mode;
+        controls.borrow_mut().speed = parse_speed(&options, mode)?.or_else(|| url.speed(mode));
+        controls.borrow_mut().seed = url.seed;
+        if options.votes == Some(true) || options.vote_socket.is_some() {
+            controls.borrow_mut().votes = Some(VoteTally::default());
+        }
+        if let Some(url) = options.vote_socket {
+            #[cfg(feature = "vote-socket")]
+            chat::connect(url, controls.clone())?;
+            #[cfg(not(feature = "vote-socket"))]
+            return Err(AppError::InvalidConfig(format!("voteSocket '{}' needs the vote-socket feature", url)));
+        }
+        if let Some(url) = &options.levels_url {
+            community::fetch(url, controls.clone())?;
+        }
+        #[cfg(feature = "models")]
+        if let Some(urls) = &options.models {
+            models::fetch(urls, &controls)?;
+        }
+        #[cfg(not(feature = "models"))]
+        if options.models.is_some() {
+            return Err(AppError::InvalidConfig("models need the models feature".to_string()));
+        }
+        controls.borrow_mut().season = match options.season.as_deref() {
+            None => {
+                if let Some(url) = &options.seasons_url {
+                    seasons::fetch(url, controls.clone())?;
+                }
+                seasons::local()
+            }
+            Some("none") => None,
+            Some(name) => Some(
+                Season::from_name(name)
+                    .ok_or_else(|| AppError::InvalidConfig(format!("unknown season '{}'", name)))?,
+            ),
+        };
+        controls.borrow_mut().canvas_ui = match options.ui.as_deref() {
+            None | Some("html") => false,
+            Some("canvas") => true,
+            Some(other) => return Err(AppError::InvalidConfig(format!("unknown ui '{}'", other))),
+        };
+
+        Ok(SnakeGame {
+            canvas,
+            controls,
+            started: Cell::new(false),
+        })
+    }
+
+    fn launch(&self) -> error::Result<()> {
+        log::info!("Creating Window ({})...", backend::select());
+        let window = Window::new(WindowSettings {
+            title: "3D Snake".to_string(),
+            min_size: (100, 100),
+            #[cfg(target_arch = "wasm32")]
+            canvas: Some(self.canvas.clone()),
+            ..Default::default()
+        })
+        .map_err(|e| AppError::WebGl(format!("{:?}", e)))?;
+        log::info!("Window created successfully!");
+
+        let document = error::document()?;
+        let dom = self.controls.borrow().dom.clone();
+        i18n::apply_to_document(&document);
+        attach_mobile_controls(&dom, &self.canvas, &self.controls)?;
+        if !self.controls.borrow().canvas_ui {
+            attach_leaderboard(&dom, &self.controls)?;
+            settings::attach_settings_panel(&dom, &self.controls)?;
+            unlocks::attach(&dom)?;
+            shop::attach(&dom)?;
+        }
+        fullscreen::attach(&document, &dom, &self.canvas, &self.controls)?;
+        profiler::attach(&document, &dom, &self.controls)?;
+        attach_visibility(&document, &self.controls)?;
+
+        // Hide the loading screen, once the host's files are in too
+        loader::window_ready();
+
+        // Focus canvas to ensure it receives keys
+        self.canvas.focus().unwrap_or(());
+
+        let mut app = App::new(window.gl(), self.controls.clone());
+
+        // On the web the winit event loop never returns (it throws to hand
+        // control back to the browser), so run it from a timeout instead of
+        // unwinding through the caller of `start()`.
+        let run = Closure::once(move || {
+            window.render_loop(move |frame_input| app.frame(frame_input));
+        });
+        error::window()?
+            .set_timeout_with_callback_and_timeout_and_arguments_0(run.as_ref().unchecked_ref(), 0)?;
+        run.forget();
+
+        Ok(())
+    }
+}
+
+// Shows the error panel and hands the message back to the embedding page.
+fn report(err: AppError) -> JsValue {
+    error::show(&err);
+    err.into()
+}
+
+fn parse_speed(options: &GameOptions, mode: GameMode) -> error::Result<Option<SpeedCurve>> {
+    if let Some(rate) = options.tick_rate {
+        if options.base_interval.is_some() || options.min_interval.is_some() || options.speed_ramp.is_some() {
+            return Err(AppError::InvalidConfig("tickRate replaces the speed curve options".to_string()));
+        }
+        if !(1.0..=MAX_TICK_RATE).contains(&rate) {
+            return Err(AppError::InvalidConfig(format!("tickRate must be between 1 and {}", MAX_TICK_RATE)));
+        }
+        return Ok(Some(SpeedCurve::fixed(rate)));
+    }
+    if options.base_interval.is_none() && options.min_interval.is_none() && options.speed_ramp.is_none() {
+        return Ok(None);
+    }
+    let defaults = mode.speed();
+    let speed = SpeedCurve {
+        base_interval: options.base_interval.unwrap_or(defaults.base_interval),
+        min_interval: options.min_interval.unwrap_or(defaults.min_interval),
+        ramp: options.speed_ramp.unwrap_or(defaults.ramp),
+    };
+    if !speed.is_valid() {
+        return Err(AppError::InvalidConfig(
+            "speed needs 0 < minInterval <= baseInterval and speedRamp >= 0".to_string(),
+        ));
+    }
+    Ok(Some(speed))
+}
+
+fn parse_mode(name: &str) -> error::Result<GameMode> {
+    let mode = GameMode::from_name(name).ok_or_else(|| AppError::InvalidConfig(format!("unknown mode '{}'", name)))?;
+    if !app::mode_built(mode) {
+        return Err(AppError::InvalidConfig(format!("mode '{}' isn't in minimal builds", name)));
+    }
+    Ok(mode)
+}
+
+// Browsers stop animation frames in hidden tabs, so the loop only learns
+// about it afterwards, from this flag.
+fn attach_visibility(document: &web_sys::Document, controls: &Rc<RefCell<Controls>>) -> Result<(), JsValue> {
+    let controls = controls.clone();
+    let closure = Closure::wrap(Box::new(move || {
+        if error::document().map(|d| d.hidden()).unwrap_or(false) {
+            let mut controls = controls.borrow_mut();
+            controls.page_was_hidden = true;
+            // Possibly the last chance before the tab is closed.
+            controls.analytics.flush();
+        }
+    }) as Box<dyn FnMut()>);
+    document.add_event_listener_with_callback("visibilitychange", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+fn attach_mobile_controls(
+    dom: &Dom,
+    canvas: &web_sys::HtmlCanvasElement,
+    controls: &Rc<RefCell<Controls>>,
+) -> Result<(), JsValue> {
     // Attach listeners to buttons
-    let attach_btn = |id: &str, dir: Direction| {
-        let elem = document.get_element_by_id(id);
-        if let Some(e) = elem {
-            let input = mobile_input_clone.clone();
+    let attach_btn = |id: &str, dir: Direction| -> Result<(), JsValue> {
+        if let Some(e) = dom.get(id) {
+            let controls = controls.clone();
             let closure = Closure::wrap(Box::new(move || {
-                *input.borrow_mut() = Some(dir);
+                controls.borrow_mut().mobile_input = Some(dir);
             }) as Box<dyn FnMut()>);
             // Use pointerdown to be responsive
-            e.add_event_listener_with_callback("pointerdown", closure.as_ref().unchecked_ref()).unwrap();
+            e.add_event_listener_with_callback("pointerdown", closure.as_ref().unchecked_ref())?;
             closure.forget(); // Memory leak but fine for single page app
         }
+        Ok(())
     };
 
-    attach_btn("btn-up", Direction::Up);
-    attach_btn("btn-down", Direction::Down);
-    attach_btn("btn-left", Direction::Left);
-    attach_btn("btn-right", Direction::Right);
+    attach_btn("btn-up", Direction::Up)?;
+    attach_btn("btn-down", Direction::Down)?;
+    attach_btn("btn-left", Direction::Left)?;
+    attach_btn("btn-right", Direction::Right)?;
 
     // Swipe detection
     let swipe_start = Rc::new(RefCell::new(None));
-    let swipe_start_clone = swipe_start.clone();
-    let mobile_input_swipe = mobile_input.clone();
 
     {
+        let swipe_start = swipe_start.clone();
         let closure = Closure::wrap(Box::new(move |e: web_sys::TouchEvent| {
             if let Some(touch) = e.touches().get(0) {
-                *swipe_start_clone.borrow_mut() = Some((touch.client_x(), touch.client_y()));
+                *swipe_start.borrow_mut() = Some((touch.client_x(), touch.client_y()));
             }
         }) as Box<dyn FnMut(_)>);
-        canvas.add_event_listener_with_callback("touchstart", closure.as_ref().unchecked_ref()).unwrap();
+        canvas.add_event_listener_with_callback("touchstart", closure.as_ref().unchecked_ref())?;
         closure.forget();
     }
 
     {
-        let swipe_start_move = swipe_start.clone();
+        let swipe_start = swipe_start.clone();
+        let controls = controls.clone();
         let closure = Closure::wrap(Box::new(move |e: web_sys::TouchEvent| {
-             if let Some(start) = *swipe_start_move.borrow() {
-                 if let Some(touch) = e.changed_touches().get(0) {
-                     let dx = touch.client_x() - start.0;
-                     let dy = touch.client_y() - start.1;
-
-                     if dx.abs() > 30 || dy.abs() > 30 {
-                         if dx.abs() > dy.abs() {
-                             if dx > 0 { *mobile_input_swipe.borrow_mut() = Some(Direction::Right); }
-                             else { *mobile_input_swipe.borrow_mut() = Some(Direction::Left); }
-                         } else {
-                             if dy > 0 { *mobile_input_swipe.borrow_mut() = Some(Direction::Down); }
-                             else { *mobile_input_swipe.borrow_mut() = Some(Direction::Up); }
-                         }
-                         // Reset start to avoid continuous triggers?
-                         // Or just trigger once per swipe.
-                         *swipe_start_move.borrow_mut() = None;
-                     }
-                 }
-             }
+            let start = *swipe_start.borrow();
+            if let Some(start) = start {
+                if let Some(touch) = e.changed_touches().get(0) {
+                    let dx = touch.client_x() - start.0;
+                    let dy = touch.client_y() - start.1;
+
+                    if dx.abs() > 30 || dy.abs() > 30 {
+                        let dir = if dx.abs() > dy.abs() {
+                            if dx > 0 { Direction::Right } else { Direction::Left }
+                        } else if dy > 0 {
+                            Direction::Down
+                        } else {
+                            Direction::Up
+                        };
+                        controls.borrow_mut().mobile_input = Some(dir);
+                        // Trigger once per swipe.
+                        *swipe_start.borrow_mut() = None;
+                    }
+                }
+            }
         }) as Box<dyn FnMut(_)>);
-        canvas.add_event_listener_with_callback("touchmove", closure.as_ref().unchecked_ref()).unwrap();
+        canvas.add_event_listener_with_callback("touchmove", closure.as_ref().unchecked_ref())?;
         closure.forget();
     }
     // Also reset on touchend
     {
-         let swipe_start_reset = swipe_start.clone();
-         let closure = Closure::wrap(Box::new(move || {
-            *swipe_start_reset.borrow_mut() = None;
+        let closure = Closure::wrap(Box::new(move || {
+            *swipe_start.borrow_mut() = None;
         }) as Box<dyn FnMut()>);
-        canvas.add_event_listener_with_callback("touchend", closure.as_ref().unchecked_ref()).unwrap();
+        canvas.add_event_listener_with_callback("touchend", closure.as_ref().unchecked_ref())?;
         closure.forget();
     }
 
-    // Leaderboard logic setup
-    let leaderboard_btn = document.get_element_by_id("leaderboard-btn").unwrap();
-    let close_leaderboard_btn = document.get_element_by_id("close-leaderboard-btn").unwrap();
-    let submit_score_btn = document.get_element_by_id("submit-score-btn").unwrap();
-
-    let show_leaderboard = Rc::new(Box::new(move || {
-        let document = web_sys::window().unwrap().document().unwrap();
-        if let Some(modal) = document.get_element_by_id("leaderboard-modal") {
-            modal.class_list().remove_1("hidden").unwrap_or(());
-            update_leaderboard_ui();
-        }
-    }) as Box<dyn Fn()>);
+    Ok(())
+}
 
-    let hide_leaderboard = Rc::new(Box::new(move || {
-         let document = web_sys::window().unwrap().document().unwrap();
-         if let Some(modal) = document.get_element_by_id("leaderboard-modal") {
-             modal.class_list().add_1("hidden").unwrap_or(());
-         }
-    }) as Box<dyn Fn()>);
+// The leaderboard UI is optional: host pages without these elements just don't get it.
+fn attach_leaderboard(dom: &Dom, controls: &Rc<RefCell<Controls>>) -> Result<(), JsValue> {
+    let show_leaderboard = {
+        let dom = dom.clone();
+        Rc::new(move || {
+            if let Some(modal) = dom.get("leaderboard-modal") {
+                modal.class_list().remove_1("hidden").unwrap_or(());
+                update_leaderboard_ui(&dom);
+            }
+        })
+    };
 
-    {
+    if let Some(leaderboard_btn) = dom.get("leaderboard-btn") {
         let show = show_leaderboard.clone();
         let closure = Closure::wrap(Box::new(move || {
             show();
         }) as Box<dyn FnMut()>);
-        leaderboard_btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref()).unwrap();
+        leaderboard_btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
         closure.forget();
     }
 
-    {
-        let hide = hide_leaderboard.clone();
-        let closure = Closure::wrap(Box::new(move || {
-            hide();
-        }) as Box<dyn FnMut()>);
-        close_leaderboard_btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref()).unwrap();
-        closure.forget();
+    // The All / Friends tabs switch the list's view.
+    for (id, view) in [("leaderboard-tab-all", "all"), ("leaderboard-tab-friends", "friends")] {
+        if let Some(tab) = dom.get(id) {
+            let dom = dom.clone();
+            let closure = Closure::wrap(Box::new(move || {
+                if let Some(list) = dom.get("leaderboard-list") {
+                    list.set_attribute("data-view", view).unwrap_or(());
+                }
+                for (id, other) in [("leaderboard-tab-all", "all"), ("leaderboard-tab-friends", "friends")] {
+                    if let Some(tab) = dom.get(id) {
+                        tab.class_list().toggle_with_force("active", other == view).unwrap_or(false);
+                    }
+                }
+                update_leaderboard_ui(&dom);
+            }) as Box<dyn FnMut()>);
+            tab.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+            closure.forget();
+        }
     }
 
-    // Shared state for restart request from UI (since main loop handles GameState reset)
-    let restart_requested = Rc::new(RefCell::new(false));
-
-    // Submit score handler
-    // We need to access current score. We can't access `game` directly in callback.
-    // We can store the pending score in a shared cell when Game Over happens.
-    let pending_score = Rc::new(RefCell::new(0u32));
-    let pending_score_clone = pending_score.clone();
-    let show_leaderboard_clone = show_leaderboard.clone();
-    let restart_requested_clone = restart_requested.clone();
+    // A star next to each name adds or removes that player as a friend.
+    if let Some(list) = dom.get("leaderboard-list") {
+        let dom = dom.clone();
+        let closure = Closure::wrap(Box::new(move |e: web_sys::Event| {
+            let star = e.target().and_then(|t| t.dyn_into::<web_sys::Element>().ok());
+            if let Some(name) = star.and_then(|s| s.get_attribute("data-friend")) {
+                leaderboard::set_friend(&name, !leaderboard::is_friend(&name));
+                update_leaderboard_ui(&dom);
+            }
+        }) as Box<dyn FnMut(_)>);
+        list.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
 
-    {
+    if let Some(close_leaderboard_btn) = dom.get("close-leaderboard-btn") {
+        let dom = dom.clone();
         let closure = Closure::wrap(Box::new(move || {
-            let document = web_sys::window().unwrap().document().unwrap();
-            let input = document.get_element_by_id("player-name").unwrap()
-                .dyn_into::<web_sys::HtmlInputElement>().unwrap();
-            let name = input.value();
-            if !name.is_empty() {
-                save_score(&name, *pending_score_clone.borrow());
-                input.set_value(""); // Clear input
-                show_leaderboard_clone();
-                // Also hide game over screen logic via restart or explicit hide?
-                // Actually, if we submit, we probably want to restart or stay in game over.
-                // Usually showing leaderboard is good.
-                // Let's trigger a restart of the game state but maybe keep showing leaderboard.
-                *restart_requested_clone.borrow_mut() = true;
-
-                // Close Game Over screen? Main loop handles visibility based on game state.
-                // If we set restart_requested, main loop will reset game, game_over becomes false, UI updates.
-                // But we want to see the leaderboard first.
-                // If we reset game immediately, background plays.
-                // Let's just reset. Leaderboard is a modal on top.
+            if let Some(modal) = dom.get("leaderboard-modal") {
+                modal.class_list().add_1("hidden").unwrap_or(());
             }
         }) as Box<dyn FnMut()>);
-        submit_score_btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref()).unwrap();
+        close_leaderboard_btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
         closure.forget();
     }
 
-    // Hide loading screen
-    if let Some(loading_el) = document.get_element_by_id("loading") {
-        loading_el.set_attribute("style", "display: none").unwrap();
-    }
-    
-    // Focus canvas to ensure it receives keys
-    canvas.focus().unwrap_or(());
-    
-    window.render_loop(move |frame_input| {
-        if !has_logged {
-            log::info!("Viewport: {:?}", frame_input.viewport);
-            has_logged = true;
-        }
-        let mut events = frame_input.events.clone(); // Clone events to pass to camera and handle locally
-        
-        // Check for restart request from UI
-        if *restart_requested.borrow() {
-            if game.game_over {
-                let high_score = game.high_score;
-                game = GameState::new(grid_size);
-                game.high_score = high_score;
-            }
-            *restart_requested.borrow_mut() = false;
-        }
-
-        // Handle Input
-        // Check mobile input
-        let mut mobile_dir = None;
-        if let Ok(mut input) = mobile_input.try_borrow_mut() {
-            if let Some(dir) = *input {
-                mobile_dir = Some(dir);
-                audio.resume_context(); // Resume on mobile interaction too
-                *input = None;
-            }
-        }
-
-        if let Some(dir) = mobile_dir {
-             match dir {
-                Direction::Up => if game.snake.direction != Direction::Down { game.snake.next_direction = Direction::Up; },
-                Direction::Down => if game.snake.direction != Direction::Up { game.snake.next_direction = Direction::Down; },
-                Direction::Left => if game.snake.direction != Direction::Right { game.snake.next_direction = Direction::Left; },
-                Direction::Right => if game.snake.direction != Direction::Left { game.snake.next_direction = Direction::Right; },
-             }
-        }
-
-        for event in &events {
-            if let Event::KeyPress { kind, .. } = event {
-                // Resume audio context on first interaction
-                audio.resume_context();
-
-                match kind {
-                    Key::ArrowUp | Key::W => {
-                        if game.snake.direction != Direction::Down {
-                            game.snake.next_direction = Direction::Up;
-                        }
-                    }
-                    Key::ArrowDown | Key::S => {
-                        if game.snake.direction != Direction::Up {
-                            game.snake.next_direction = Direction::Down;
-                        }
-                    }
-                    Key::ArrowLeft | Key::A => {
-                        if game.snake.direction != Direction::Right {
-                            game.snake.next_direction = Direction::Left;
-                        }
-                    }
-                    Key::ArrowRight | Key::D => {
-                        if game.snake.direction != Direction::Left {
-                            game.snake.next_direction = Direction::Right;
-                        }
-                    }
-                    Key::R => {
-                        if game.game_over {
-                            let high_score = game.high_score;
-                            game = GameState::new(grid_size);
-                            game.high_score = high_score;
-                        }
-                    }
-                    _ => {}
+    // Submit score handler
+    // The callback can't see `game`, so the loop stores the final score in
+    // `Controls::pending_score` when Game Over happens.
+    if let Some(submit_score_btn) = dom.get("submit-score-btn") {
+        let controls = controls.clone();
+        let closure = {
+            let dom = dom.clone();
+            Closure::wrap(Box::new(move || {
+                let input = match dom.element::<web_sys::HtmlInputElement>("player-name") {
+                    Ok(input) => input,
+                    Err(_) => return,
+                };
+                let name = input.value().trim().to_string();
+                if !name.is_empty() {
+                    let score = controls.borrow().pending_score;
+                    save_score(&name, score);
+                    input.set_value(""); // Clear input
+                    show_leaderboard();
+                    // Restart right away; the leaderboard is a modal on top of the new run.
+                    controls.borrow_mut().restart_requested = true;
                 }
-            }
-        }
+            }) as Box<dyn FnMut()>)
+        };
+        submit_score_btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+        closure.forget();
 
-        // Update Camera
-        renderer.update_camera(&mut events);
-        renderer.resize(frame_input.viewport.width, frame_input.viewport.height);
-
-        // Update Game Logic
-        // Use accumulated time for fixed step update
-        time_since_last_move += frame_input.elapsed_time / 1000.0; // elapsed_time is ms
-
-        // Calculate current speed based on score (max speed at 50 points)
-        let base_speed = 0.15;
-        let min_speed = 0.05;
-        let speed_reduction = (game.score as f64 * 0.002).min(base_speed - min_speed);
-        let move_interval = base_speed - speed_reduction;
-
-        if time_since_last_move >= move_interval {
-            let old_food_pos = game.food;
-            let event = game.update();
-            match event {
-                crate::game::GameEvent::Eat => {
-                    audio.play_eat();
-                    renderer.spawn_particles(old_food_pos, false);
-                },
-                crate::game::GameEvent::EatPrize => {
-                    audio.play_prize();
-                    renderer.spawn_particles(old_food_pos, true);
-                },
-                crate::game::GameEvent::GameOver => {
-                    audio.play_game_over();
-                    // Update pending score for submit
-                    *pending_score.borrow_mut() = game.score;
-                },
-                crate::game::GameEvent::None => {}
-            }
-            time_since_last_move = 0.0;
+        // Enter in the name field submits too.
+        if let Some(input) = dom.get("player-name") {
+            let submit = submit_score_btn.dyn_into::<web_sys::HtmlElement>()?;
+            let closure = Closure::wrap(Box::new(move |e: web_sys::KeyboardEvent| {
+                if e.key() == "Enter" {
+                    e.prevent_default();
+                    submit.click();
+                }
+            }) as Box<dyn FnMut(_)>);
+            input.add_event_listener_with_callback("keydown", closure.as_ref().unchecked_ref())?;
+            closure.forget();
         }
-
-        // Update UI
-        update_ui(&game);
-
-        // Render
-        renderer.render(&game, &frame_input.screen(), frame_input.elapsed_time / 1000.0);
-
-        FrameOutput::default()
-    });
-
-    Ok(())
-}
-
-fn update_ui(game: &GameState) {
-    let document = web_sys::window().unwrap().document().unwrap();
-    
-    if let Some(score_el) = document.get_element_by_id("score") {
-        score_el.set_inner_html(&game.score.to_string());
     }
 
-    if let Some(high_score_el) = document.get_element_by_id("high-score") {
-        high_score_el.set_inner_html(&game.high_score.to_string());
-        if let Some(container) = document.get_element_by_id("high-score-container") {
-             container.class_list().remove_1("hidden").unwrap_or(());
-        }
+    // Share button on the game over screen; the render loop does the capture.
+    if let Some(share_btn) = dom.get("share-btn") {
+        let controls = controls.clone();
+        let closure = Closure::wrap(Box::new(move || {
+            controls.borrow_mut().share_requested = true;
+        }) as Box<dyn FnMut()>);
+        share_btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+        closure.forget();
     }
-
-    if let Some(game_over_el) = document.get_element_by_id("game-over") {
-        let class_list = game_over_el.class_list();
-        if game.game_over {
-            class_list.remove_1("hidden").unwrap();
-            if let Some(final_score_el) = document.get_element_by_id("final-score") {
-                final_score_el.set_inner_html(&format!("Score: {}", game.score));
-            }
-        } else {
-            class_list.add_1("hidden").unwrap();
-        }
+    if let Some(clip_btn) = dom.get("clip-btn") {
+        let controls = controls.clone();
+        let closure = Closure::wrap(Box::new(move || {
+            controls.borrow_mut().clip_requested = true;
+        }) as Box<dyn FnMut()>);
+        clip_btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+        closure.forget();
     }
+
+    Ok(())
 }
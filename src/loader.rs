@@ -0,0 +1,163 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::error;
+use crate::i18n::t;
+
+// Files fetched from the host at startup (the community level list, the
+// season calendar and any models) go through here. They load side by side,
+// and the loading screen counts them off and stays up until they have all
+// settled and the window is up. Failures that may pass - no connection, a
+// 408, 429 or 5xx - are retried with growing waits. Responses are kept by
+// URL, so asking for the same file again doesn't go back to the network.
+
+// Tries per file, the first included.
+const ATTEMPTS: u32 = 3;
+// Wait before the first retry; it doubles for each one after.
+const RETRY_MS: i32 = 500;
+
+#[derive(Default)]
+struct Progress {
+    total: u32,
+    settled: u32,
+    window_ready: bool,
+}
+
+thread_local! {
+    static PROGRESS: RefCell<Progress> = RefCell::new(Progress::default());
+    static CACHE: RefCell<HashMap<String, Rc<[u8]>>> = RefCell::new(HashMap::new());
+}
+
+// Takes the fetched file.
+type OnBytes = Box<dyn FnOnce(&[u8])>;
+
+struct Request {
+    url: String,
+    // Names the file in warnings.
+    what: &'static str,
+    on_bytes: OnBytes,
+    tries: u32,
+}
+
+// Taken by whichever of a request's callbacks fires first.
+type Pending = Rc<RefCell<Option<Request>>>;
+
+// Fetches `url` and hands its contents to `on_bytes`. A file that can't be
+// had is logged and `on_bytes` never runs.
+pub fn fetch_bytes(url: &str, what: &'static str, on_bytes: impl FnOnce(&[u8]) + 'static) -> Result<(), JsValue> {
+    if let Some(bytes) = CACHE.with(|c| c.borrow().get(url).cloned()) {
+        on_bytes(&bytes);
+        return Ok(());
+    }
+    PROGRESS.with(|p| p.borrow_mut().total += 1);
+    update_screen();
+    send(Request { url: url.to_string(), what, on_bytes: Box::new(on_bytes), tries: 1 })
+}
+
+pub fn fetch_text(url: &str, what: &'static str, on_text: impl FnOnce(String) + 'static) -> Result<(), JsValue> {
+    fetch_bytes(url, what, move |bytes| on_text(String::from_utf8_lossy(bytes).into_owned()))
+}
+
+// The window is up; the loading screen goes as soon as the files are in too.
+pub fn window_ready() {
+    PROGRESS.with(|p| p.borrow_mut().window_ready = true);
+    update_screen();
+}
+
+fn send(request: Request) -> Result<(), JsValue> {
+    let promise = error::window()?.fetch_with_str(&request.url);
+    let pending: Pending = Rc::new(RefCell::new(Some(request)));
+    let on_response = {
+        let pending = pending.clone();
+        Closure::once(move |response: JsValue| {
+            let Some(request) = pending.borrow_mut().take() else { return };
+            let response: web_sys::Response = response.unchecked_into();
+            if !response.ok() {
+                let status = response.status();
+                let transient = matches!(status, 408 | 429 | 500..=599);
+                return failed(request, &format!("HTTP {}", status), transient);
+            }
+            match response.array_buffer() {
+                Ok(body) => read(body, request),
+                Err(e) => failed(request, &format!("{:?}", e), false),
+            }
+        })
+    };
+    // A network error, before there is any response.
+    let on_error = Closure::once(move |e: JsValue| {
+        if let Some(request) = pending.borrow_mut().take() {
+            failed(request, &format!("{:?}", e), true);
+        }
+    });
+    let _ = promise.then(&on_response).catch(&on_error);
+    on_response.forget();
+    on_error.forget();
+    Ok(())
+}
+
+fn read(body: js_sys::Promise, request: Request) {
+    let pending: Pending = Rc::new(RefCell::new(Some(request)));
+    let on_body = {
+        let pending = pending.clone();
+        Closure::once(move |body: JsValue| {
+            let Some(request) = pending.borrow_mut().take() else { return };
+            let bytes: Rc<[u8]> = js_sys::Uint8Array::new(&body).to_vec().into();
+            CACHE.with(|c| c.borrow_mut().insert(request.url.clone(), bytes.clone()));
+            settle();
+            (request.on_bytes)(&bytes);
+        })
+    };
+    // The connection dropped partway through the body.
+    let on_error = Closure::once(move |e: JsValue| {
+        if let Some(request) = pending.borrow_mut().take() {
+            failed(request, &format!("{:?}", e), true);
+        }
+    });
+    let _ = body.then(&on_body).catch(&on_error);
+    on_body.forget();
+    on_error.forget();
+}
+
+fn failed(mut request: Request, reason: &str, transient: bool) {
+    if !transient || request.tries >= ATTEMPTS {
+        log::warn!("{} couldn't be fetched: {}", request.what, reason);
+        settle();
+        return;
+    }
+    let wait = RETRY_MS << (request.tries - 1);
+    log::warn!("{} couldn't be fetched ({}); retrying in {}ms", request.what, reason, wait);
+    request.tries += 1;
+    let retry = Closure::once(move || {
+        if let Err(e) = send(request) {
+            log::warn!("Retry failed to start: {:?}", e);
+            settle();
+        }
+    });
+    if let Ok(window) = error::window() {
+        let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(retry.as_ref().unchecked_ref(), wait);
+    }
+    retry.forget();
+}
+
+fn settle() {
+    PROGRESS.with(|p| p.borrow_mut().settled += 1);
+    update_screen();
+}
+
+// "Loading... 1/2" while files are out, hidden once everything is in.
+fn update_screen() {
+    let (settled, total, ready) = PROGRESS.with(|p| {
+        let p = p.borrow();
+        (p.settled, p.total, p.window_ready)
+    });
+    let Some(loading) = error::document().ok().and_then(|d| d.get_element_by_id("loading")) else { return };
+    if ready && settled == total {
+        loading.set_attribute("style", "display: none").unwrap_or(());
+    } else if total > 0 {
+        loading.set_text_content(Some(&format!("{} {}/{}", t("loading"), settled, total)));
+    }
+}
@@ -0,0 +1,153 @@
+#[cfg(feature = "models")]
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use serde::Deserialize;
+use three_d::*;
+#[cfg(feature = "models")]
+use wasm_bindgen::prelude::*;
+
+#[cfg(feature = "models")]
+use crate::app::Controls;
+use crate::clips::{Clips, Cue};
+#[cfg(feature = "models")]
+use crate::loader;
+use crate::renderer;
+
+// glTF models a host can put in place of the built-in shapes, by item
+// (`models: { head, body, food, prize }`, each a URL). Files need to be
+// self-contained: a GLB, or a .gltf with its buffers and images embedded.
+// Every model is scaled to fill the same unit cube as the shape it replaces.
+// The head, food and prize keep the model's own materials; body segments take
+// only its shape, so themes and skins still color the snake. Until a model
+// arrives, or if it never does, the built-in shape stands.
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct ModelUrls {
+    head: Option<String>,
+    body: Option<String>,
+    food: Option<String>,
+    prize: Option<String>,
+}
+
+#[derive(Clone, Default)]
+pub struct Models {
+    pub head: Option<Rc<CpuModel>>,
+    pub body: Option<Rc<CpuModel>>,
+    pub food: Option<Rc<CpuModel>>,
+    pub prize: Option<Rc<CpuModel>>,
+}
+
+// Where in `Models` a fetched model goes.
+#[cfg(feature = "models")]
+type Slot = fn(&mut Models) -> &mut Option<Rc<CpuModel>>;
+
+#[cfg(feature = "models")]
+pub fn fetch(urls: &ModelUrls, controls: &Rc<RefCell<Controls>>) -> Result<(), JsValue> {
+    let items: [(&Option<String>, &'static str, Slot); 4] = [
+        (&urls.head, "Head model", |m| &mut m.head),
+        (&urls.body, "Body model", |m| &mut m.body),
+        (&urls.food, "Food model", |m| &mut m.food),
+        (&urls.prize, "Prize model", |m| &mut m.prize),
+    ];
+    for (url, what, slot) in items {
+        let Some(url) = url else { continue };
+        let controls = controls.clone();
+        let path = if url.ends_with(".gltf") { "model.gltf" } else { "model.glb" };
+        loader::fetch_bytes(url, what, move |bytes| {
+            let mut raw = three_d_asset::io::RawAssets::new();
+            raw.insert(path, bytes.to_vec());
+            match raw.deserialize::<CpuModel>(path) {
+                Ok(model) => {
+                    let mut controls = controls.borrow_mut();
+                    *slot(&mut controls.models) = Some(Rc::new(model));
+                    controls.models_changed = true;
+                }
+                Err(e) => log::warn!("{} didn't load: {:?}", what, e),
+            }
+        })?;
+    }
+    Ok(())
+}
+
+// Scales and centers a model into the cube from -1 to 1.
+fn fit(model: &CpuModel) -> Mat4 {
+    let mut aabb = AxisAlignedBoundingBox::EMPTY;
+    for primitive in &model.geometries {
+        if let CpuGeometry::Triangles(mesh) = &primitive.geometry {
+            let mut part = mesh.compute_aabb();
+            part.transform(&primitive.transformation);
+            aabb.expand_with_aabb(&part);
+        }
+    }
+    let size = aabb.size();
+    let largest = size.x.max(size.y).max(size.z);
+    if aabb.is_empty() || largest <= 0.0 {
+        return Mat4::identity();
+    }
+    Mat4::from_scale(2.0 / largest) * Mat4::from_translation(-aabb.center())
+}
+
+// A model's shape alone, as one mesh, for drawing instanced.
+pub fn shape(model: &CpuModel) -> CpuMesh {
+    let fit = fit(model);
+    renderer::merge(model.geometries.iter().filter_map(|primitive| match &primitive.geometry {
+        CpuGeometry::Triangles(mesh) => Some((mesh, fit * primitive.transformation)),
+        _ => None,
+    }))
+}
+
+// A model with its materials, drawn wherever `place` puts it.
+pub struct Placed {
+    model: Model<PhysicalMaterial>,
+    // Each part's transformation within the fitted model.
+    parts: Vec<Mat4>,
+    clips: Clips,
+    // The clip the parts are set to play, once one has been chosen.
+    chosen: Option<Option<String>>,
+}
+
+impl Placed {
+    pub fn new(context: &Context, cpu: &CpuModel) -> Option<Self> {
+        let model = match Model::<PhysicalMaterial>::new(context, cpu) {
+            Ok(model) => model,
+            Err(e) => {
+                log::warn!("Model {} can't be drawn: {:?}", cpu.name, e);
+                return None;
+            }
+        };
+        let fit = fit(cpu);
+        let parts = cpu
+            .geometries
+            .iter()
+            .filter(|primitive| matches!(primitive.geometry, CpuGeometry::Triangles(_)))
+            .map(|primitive| fit * primitive.transformation)
+            .collect();
+        Some(Self { model, parts, clips: Clips::new(cpu), chosen: None })
+    }
+
+    pub fn place(&mut self, transformation: Mat4) {
+        for (part, local) in self.model.iter_mut().zip(&self.parts) {
+            part.set_transformation(transformation * local);
+        }
+    }
+
+    pub fn cue(&mut self, cue: Cue, time: f64) {
+        self.clips.play(cue, time);
+    }
+
+    // Poses the model's clip for `time`; after `place`, which resets it.
+    pub fn animate(&mut self, time: f64) {
+        let Some((clip, at)) = self.clips.at(time) else { return };
+        if self.chosen.as_ref().map(Option::as_deref) != Some(clip) {
+            self.model.choose_animation(clip);
+            self.chosen = Some(clip.map(str::to_string));
+        }
+        self.model.animate(at);
+    }
+
+    pub fn objects(&self) -> impl Iterator<Item = &dyn Object> {
+        self.model.iter().map(|part| part as &dyn Object)
+    }
+}
@@ -0,0 +1,79 @@
+use snake3d_core::rhythm::BEAT_SECONDS;
+
+use crate::audio::AudioPlayer;
+use crate::seasons::Season;
+
+// Backing track for rhythm mode, sequenced on the audio clock. The game
+// ticks on its beats, so moves stay locked to what the player hears even
+// when frames stutter.
+
+// How far ahead notes are queued; covers a few slow frames.
+const LOOKAHEAD: f64 = 0.2;
+// Gap before the first beat, so it isn't already late when queued.
+const LEAD_IN: f64 = 0.1;
+// One bar of bass notes, one per beat.
+const BASS: [f32; 8] = [110.0, 110.0, 165.0, 110.0, 131.0, 110.0, 165.0, 147.0];
+// Seasonal lines: a minor creep for Halloween, a brighter major for winter.
+const HALLOWEEN_BASS: [f32; 8] = [110.0, 131.0, 117.0, 110.0, 104.0, 110.0, 131.0, 156.0];
+const WINTER_BASS: [f32; 8] = [131.0, 165.0, 196.0, 165.0, 147.0, 175.0, 220.0, 196.0];
+// A boss fight's own line: low and chromatic, leaning on the tritone.
+const BOSS_BASS: [f32; 8] = [82.0, 82.0, 117.0, 87.0, 82.0, 123.0, 117.0, 98.0];
+
+pub struct Sequencer {
+    // Audio time of beat 0.
+    start: f64,
+    // Beats queued with the audio player so far.
+    queued: u64,
+    // Beats handed to the game so far.
+    played: u64,
+    bass: &'static [f32; 8],
+}
+
+impl Sequencer {
+    pub fn new(now: f64, season: Option<Season>) -> Self {
+        let bass = match season {
+            Some(Season::Halloween) => &HALLOWEEN_BASS,
+            Some(Season::Winter) => &WINTER_BASS,
+            None => &BASS,
+        };
+        Self { start: now + LEAD_IN, queued: 0, played: 0, bass }
+    }
+
+    // The boss track, played over a fight without setting the game's pace.
+    pub fn boss(now: f64) -> Self {
+        Self { start: now + LEAD_IN, queued: 0, played: 0, bass: &BOSS_BASS }
+    }
+
+    pub fn beat_time(&self, beat: u64) -> f64 {
+        self.start + beat as f64 * BEAT_SECONDS
+    }
+
+    // Fraction of the way from the last beat to the next.
+    pub fn phase(&self, now: f64) -> f64 {
+        ((now - self.start) / BEAT_SECONDS).rem_euclid(1.0)
+    }
+
+    // Queues the notes due within the lookahead.
+    pub fn schedule(&mut self, audio: &AudioPlayer, now: f64) {
+        while self.beat_time(self.queued) < now + LOOKAHEAD {
+            let when = self.beat_time(self.queued);
+            let step = (self.queued % self.bass.len() as u64) as usize;
+            audio.schedule_note(self.bass[step], when, BEAT_SECONDS * 0.8);
+            // Accent on the downbeat of each half bar.
+            if step.is_multiple_of(4) {
+                audio.schedule_note(55.0, when, 0.12);
+            }
+            self.queued += 1;
+        }
+    }
+
+    // Audio times of the beats that have sounded since the last call.
+    pub fn take_beats(&mut self, now: f64) -> Vec<f64> {
+        let mut beats = Vec::new();
+        while self.beat_time(self.played) <= now {
+            beats.push(self.beat_time(self.played));
+            self.played += 1;
+        }
+        beats
+    }
+}
@@ -0,0 +1,69 @@
+use snake3d_core::{Face, GameEvent, GameState, Position};
+
+use crate::app::{death_text, face_key};
+use crate::dom::Dom;
+use crate::i18n::t;
+use crate::settings::Narration;
+
+// Feedback for screen readers: what happened on a tick, written into the
+// page's `aria-live` region (`#announcer`) for the reader to speak. Brief
+// narration covers the big moments - a prize appearing, a new level or
+// record, the end of the run - and full narration adds the score on every
+// bite, where the food went and the shield.
+
+// What the board was like before the tick.
+pub struct Before {
+    pub food: Position,
+    pub is_prize: bool,
+    pub level: u32,
+    pub new_record: bool,
+}
+
+pub fn describe(narration: Narration, event: GameEvent, game: &GameState, before: &Before) -> Option<String> {
+    if narration == Narration::Off {
+        return None;
+    }
+    let full = narration == Narration::Full;
+    let score = game.score.to_string();
+    let on_face = |key: &'static str, face: Face| t(key).replace("{face}", t(face_key(face)));
+    let mut said = Vec::new();
+    match event {
+        GameEvent::GameOver => {
+            said.push(t("sr_game_over").replace("{score}", &score));
+            said.extend(game.death.as_ref().map(death_text));
+        }
+        GameEvent::Finished => said.push(t("sr_finished").replace("{score}", &score)),
+        GameEvent::LifeLost => said.push(t("sr_life_lost").to_string()),
+        GameEvent::BossDefeated => said.push(t("boss_defeated").to_string()),
+        GameEvent::Eat | GameEvent::EatPrize | GameEvent::FeastBite | GameEvent::AllFacesBonus if full => {
+            said.push(t("sr_score").replace("{score}", &score))
+        }
+        GameEvent::ShieldPickup if full => said.push(t("sr_shield").to_string()),
+        GameEvent::ShieldBreak if full => said.push(t("sr_shield_lost").to_string()),
+        _ => {}
+    }
+    if game.new_record && !before.new_record {
+        said.push(t("new_record").to_string());
+    }
+    if game.level() > before.level {
+        said.push(t("announce_level").replace("{level}", &game.level().to_string()));
+    }
+    let moved = game.food != before.food || game.is_prize != before.is_prize;
+    if moved && !game.game_over {
+        if game.is_prize {
+            said.push(on_face("sr_prize", game.food.face));
+        } else if full {
+            said.push(on_face("sr_food", game.food.face));
+        }
+    }
+    (!said.is_empty()).then(|| said.join(". "))
+}
+
+// Hands `text` to the screen reader.
+pub fn say(dom: &Dom, text: &str) {
+    if let Some(region) = dom.get("announcer") {
+        // Emptied first, so the same words twice are still read twice.
+        region.set_text_content(None);
+        region.set_text_content(Some(text));
+    }
+}
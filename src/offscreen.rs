@@ -0,0 +1,116 @@
+use three_d::*;
+
+use crate::ssao::SsaoEffect;
+
+// Intermediate render target for the scene. It's used when the scene needs a
+// post pass (ambient occlusion) or renders below screen resolution (dynamic
+// resolution); the result is then stretched over the screen.
+
+const COPY_SHADER: &str = "
+in vec2 uvs;
+layout (location = 0) out vec4 outColor;
+
+void main() {
+    outColor = sample_color(uvs);
+    gl_FragDepth = sample_depth(uvs);
+}
+";
+
+// Plain copy of color and depth, with linear filtering for the upscale.
+struct CopyEffect;
+
+impl Effect for CopyEffect {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> String {
+        let color = color_texture.expect("copy needs the scene color");
+        let depth = depth_texture.expect("copy needs the scene depth");
+        format!("{}\n{}\n{}", color.fragment_shader_source(), depth.fragment_shader_source(), COPY_SHADER)
+    }
+
+    fn id(&self, color_texture: Option<ColorTexture>, depth_texture: Option<DepthTexture>) -> u16 {
+        0b1u16 << 14
+            | 0b1u16 << 11
+            | color_texture.map(|t| t.id()).unwrap_or(0)
+            | depth_texture.map(|t| t.id()).unwrap_or(0)
+    }
+
+    fn fragment_attributes(&self) -> FragmentAttributes {
+        FragmentAttributes { uv: true, ..FragmentAttributes::NONE }
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        _camera: &Camera,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if let Some(color) = color_texture {
+            color.use_uniforms(program);
+        }
+        if let Some(depth) = depth_texture {
+            depth.use_uniforms(program);
+        }
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR_AND_DEPTH,
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            ..Default::default()
+        }
+    }
+}
+
+pub struct Offscreen {
+    color: Texture2D,
+    depth: DepthTexture2D,
+    size: (u32, u32),
+}
+
+impl Offscreen {
+    pub fn new(context: &Context, width: u32, height: u32) -> Self {
+        Self {
+            color: Texture2D::new_empty::<[u8; 4]>(
+                context,
+                width,
+                height,
+                Interpolation::Linear,
+                Interpolation::Linear,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ),
+            depth: DepthTexture2D::new::<f32>(context, width, height, Wrapping::ClampToEdge, Wrapping::ClampToEdge),
+            size: (width, height),
+        }
+    }
+
+    // Runs `draw` against the offscreen targets, resizing them first if
+    // `width` x `height` changed.
+    pub fn draw(&mut self, context: &Context, width: u32, height: u32, draw: impl FnOnce(&RenderTarget)) {
+        if self.size != (width, height) {
+            *self = Self::new(context, width, height);
+        }
+        draw(&RenderTarget::new(self.color.as_color_target(None), self.depth.as_depth_target()));
+    }
+
+    // Stretches the last `draw` over `camera`'s viewport of `target`, with
+    // ambient occlusion if asked for.
+    pub fn composite(&self, target: &RenderTarget, camera: &Camera, ambient_occlusion: bool) {
+        let color = Some(ColorTexture::Single(&self.color));
+        let depth = Some(DepthTexture::Single(&self.depth));
+        if ambient_occlusion {
+            let texel_size = vec2(1.0 / self.size.0 as f32, 1.0 / self.size.1 as f32);
+            target.apply_screen_effect(&SsaoEffect { texel_size }, camera, &[], color, depth);
+        } else {
+            target.apply_screen_effect(&CopyEffect, camera, &[], color, depth);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use three_d::*;
+
+// Sparks, confetti and snowflakes. The pool is allocated once: spent slots
+// go on a free list for the next burst, and the instance buffers are
+// refilled in place each frame, so busy effects cost no allocations.
+
+// Live particles at most. A burst that finds the pool full is cut short.
+const CAPACITY: usize = 1024;
+// Seconds a particle lives.
+const LIFETIME: f64 = 1.0;
+
+#[derive(Clone, Copy)]
+pub struct Particle {
+    pub start_pos: Vec3,
+    pub velocity: Vec3,
+    pub spawn_time: f64,
+    pub color: Srgba,
+}
+
+pub struct ParticlePool {
+    slots: Vec<Particle>,
+    live: Vec<bool>,
+    // Slots that are free, the next one to use last.
+    free: Vec<usize>,
+    instances: Instances,
+}
+
+impl Default for ParticlePool {
+    fn default() -> Self {
+        let idle = Particle { start_pos: vec3(0.0, 0.0, 0.0), velocity: vec3(0.0, 0.0, 0.0), spawn_time: 0.0, color: Srgba::WHITE };
+        Self {
+            slots: vec![idle; CAPACITY],
+            live: vec![false; CAPACITY],
+            free: (0..CAPACITY).rev().collect(),
+            instances: Instances {
+                transformations: Vec::with_capacity(CAPACITY),
+                colors: Some(Vec::with_capacity(CAPACITY)),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl ParticlePool {
+    pub fn spawn(&mut self, particle: Particle) {
+        if let Some(slot) = self.free.pop() {
+            self.slots[slot] = particle;
+            self.live[slot] = true;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.live.fill(false);
+        self.free.clear();
+        self.free.extend((0..CAPACITY).rev());
+    }
+
+    // Frees the particles that have run their course and lays out the rest
+    // for drawing.
+    pub fn update(&mut self, time: f64) -> &Instances {
+        self.instances.transformations.clear();
+        let colors = self.instances.colors.get_or_insert_with(Vec::new);
+        colors.clear();
+        for (slot, p) in self.slots.iter().enumerate() {
+            if !self.live[slot] {
+                continue;
+            }
+            let age = time - p.spawn_time;
+            if age >= LIFETIME {
+                self.live[slot] = false;
+                self.free.push(slot);
+                continue;
+            }
+            let age = age as f32;
+            let pos = p.start_pos + p.velocity * age;
+            let scale = (1.0 - age) * 0.05;
+            self.instances.transformations.push(Mat4::from_translation(pos) * Mat4::from_scale(scale));
+            colors.push(p.color);
+        }
+        &self.instances
+    }
+}
@@ -0,0 +1,172 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::app::Controls;
+use crate::dom::Dom;
+use crate::error;
+
+// About four seconds at 60fps; long enough to catch periodic hitches.
+const WINDOW: usize = 240;
+// Overlay text is rewritten every this many frames, not every frame.
+const OVERLAY_INTERVAL: u32 = 15;
+
+// Milliseconds from `performance.now()`, or 0 where it isn't available.
+pub fn now() -> f64 {
+    web_sys::window()
+        .and_then(|w| w.performance())
+        .map(|p| p.now())
+        .unwrap_or(0.0)
+}
+
+#[derive(Default)]
+struct Samples {
+    values: VecDeque<f64>,
+}
+
+impl Samples {
+    fn push(&mut self, ms: f64) {
+        if self.values.len() == WINDOW {
+            self.values.pop_front();
+        }
+        self.values.push_back(ms);
+    }
+
+    fn summary(&self) -> Percentiles {
+        let mut sorted: Vec<f64> = self.values.iter().copied().collect();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let at = |p: f64| {
+            if sorted.is_empty() {
+                return 0.0;
+            }
+            let i = ((sorted.len() - 1) as f64 * p).round() as usize;
+            sorted[i]
+        };
+        Percentiles {
+            p50: at(0.50),
+            p95: at(0.95),
+            p99: at(0.99),
+            max: sorted.last().copied().unwrap_or(0.0),
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Default)]
+pub struct Percentiles {
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+// What `SnakeGame.frameStats` returns. All values are milliseconds.
+#[derive(Serialize, Default)]
+pub struct FrameStats {
+    pub samples: usize,
+    pub frame: Percentiles,
+    pub update: Percentiles,
+    pub render: Percentiles,
+    pub upload: Percentiles,
+}
+
+// Rolling per-frame timings. `render` is CPU time spent issuing draw calls;
+// the GPU side isn't visible from here, `frame` (time between frames) is the
+// best proxy for it.
+#[derive(Default)]
+pub struct Profiler {
+    frame: Samples,
+    update: Samples,
+    render: Samples,
+    upload: Samples,
+    frames: u32,
+}
+
+impl Profiler {
+    pub fn record(&mut self, frame: f64, update: f64, render: f64, upload: f64) {
+        self.frame.push(frame);
+        self.update.push(update);
+        self.render.push(render);
+        self.upload.push(upload);
+        self.frames = self.frames.wrapping_add(1);
+    }
+
+    pub fn stats(&self) -> FrameStats {
+        FrameStats {
+            samples: self.frame.values.len(),
+            frame: self.frame.summary(),
+            update: self.update.summary(),
+            render: self.render.summary(),
+            upload: self.upload.summary(),
+        }
+    }
+
+    pub fn overlay_due(&self) -> bool {
+        self.frames.is_multiple_of(OVERLAY_INTERVAL)
+    }
+}
+
+pub fn to_js(stats: &FrameStats) -> Result<JsValue, JsValue> {
+    let json = serde_json::to_string(stats).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    js_sys::JSON::parse(&json)
+}
+
+pub fn update_overlay(dom: &Dom, stats: &FrameStats) {
+    let overlay = match dom.get("debug-overlay") {
+        Some(overlay) => overlay,
+        None => return,
+    };
+    let fps = if stats.frame.p50 > 0.0 { 1000.0 / stats.frame.p50 } else { 0.0 };
+    let row = |name: &str, p: &Percentiles| {
+        format!("{:<7}{:>6.2}{:>7.2}{:>7.2}{:>7.2}\n", name, p.p50, p.p95, p.p99, p.max)
+    };
+    let text = format!(
+        "{:.0} fps\n{:<7}{:>6}{:>7}{:>7}{:>7}\n{}{}{}{}",
+        fps,
+        "ms",
+        "p50",
+        "p95",
+        "p99",
+        "max",
+        row("frame", &stats.frame),
+        row("update", &stats.update),
+        row("render", &stats.render),
+        row("upload", &stats.upload),
+    );
+    overlay.set_text_content(Some(&text));
+}
+
+// Backquote toggles the overlay; `?debug` in the URL shows it from the start.
+pub fn attach(document: &web_sys::Document, dom: &Dom, controls: &Rc<RefCell<Controls>>) -> Result<(), JsValue> {
+    let overlay = match dom.get("debug-overlay") {
+        Some(overlay) => overlay,
+        None => return Ok(()),
+    };
+
+    let from_url = error::window()?
+        .location()
+        .search()
+        .map(|s| s.trim_start_matches('?').split('&').any(|p| p == "debug"))
+        .unwrap_or(false);
+    if from_url {
+        controls.borrow_mut().debug_overlay = true;
+        overlay.class_list().remove_1("hidden")?;
+    }
+
+    let controls = controls.clone();
+    let closure = Closure::wrap(Box::new(move |e: web_sys::KeyboardEvent| {
+        if e.key() != "`" {
+            return;
+        }
+        let mut controls = controls.borrow_mut();
+        controls.debug_overlay = !controls.debug_overlay;
+        overlay.class_list().toggle_with_force("hidden", !controls.debug_overlay).unwrap_or(false);
+    }) as Box<dyn FnMut(_)>);
+    document.add_event_listener_with_callback("keydown", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+
+    Ok(())
+}
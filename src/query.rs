@@ -0,0 +1,89 @@
+use snake3d_core::{seed_from_str, GameMode, SpeedCurve};
+
+use crate::error;
+use crate::settings::{Settings, Theme, MAX_GRID_SIZE, MIN_GRID_SIZE};
+use crate::unlocks::{self, Cosmetic};
+
+// A setup linked through the page address, e.g.
+// `?grid=16&mode=speedrun&seed=abc&theme=retro&speed=fast`. Values that
+// don't parse are logged and left out, so a mangled link still starts a game.
+#[derive(Debug, Default)]
+pub struct UrlConfig {
+    pub grid_size: Option<i32>,
+    pub mode: Option<GameMode>,
+    pub seed: Option<u64>,
+    pub theme: Option<Theme>,
+    // Multiplies the mode's tick intervals.
+    pub pace: Option<f64>,
+}
+
+impl UrlConfig {
+    pub fn from_location() -> UrlConfig {
+        let search = error::window().and_then(|w| Ok(w.location().search()?)).unwrap_or_default();
+        UrlConfig::parse(&search)
+    }
+
+    pub fn parse(search: &str) -> UrlConfig {
+        let mut config = UrlConfig::default();
+        let pairs = search.trim_start_matches('?').split('&').filter_map(|p| p.split_once('='));
+        for (key, raw) in pairs {
+            let value = js_sys::decode_uri_component(raw).map(String::from).unwrap_or_else(|_| raw.to_string());
+            let known = match key {
+                "grid" => {
+                    config.grid_size = value.parse().ok().filter(|n| (MIN_GRID_SIZE..=MAX_GRID_SIZE).contains(n));
+                    config.grid_size.is_some()
+                }
+                "mode" => {
+                    config.mode = GameMode::from_name(&value).filter(|mode| crate::app::mode_built(*mode));
+                    config.mode.is_some()
+                }
+                "seed" => {
+                    config.seed = (!value.is_empty()).then(|| seed_from_str(&value));
+                    config.seed.is_some()
+                }
+                "theme" => {
+                    config.theme = Theme::from_name(&value);
+                    config.theme.is_some()
+                }
+                "speed" => {
+                    config.pace = match value.as_str() {
+                        "slow" => Some(1.5),
+                        "normal" => Some(1.0),
+                        "fast" => Some(0.65),
+                        _ => None,
+                    };
+                    config.pace.is_some()
+                }
+                _ => true,
+            };
+            if !known {
+                log::warn!("Ignoring link parameter {}={}", key, value);
+            }
+        }
+        config
+    }
+
+    // The link's settings on top of the saved ones. They last for this
+    // visit only unless the player saves the settings panel.
+    pub fn apply(&self, settings: &mut Settings) {
+        if let Some(grid_size) = self.grid_size {
+            settings.grid_size = grid_size;
+        }
+        // A link can't hand out a theme the player hasn't unlocked.
+        if let Some(theme) = self.theme.filter(|theme| unlocks::is_unlocked(Cosmetic::Theme(*theme))) {
+            settings.theme = theme;
+        }
+    }
+
+    // The mode's pacing, sped up or slowed down by `speed`. "normal" keeps
+    // the mode's own curve, and with it replays.
+    pub fn speed(&self, mode: GameMode) -> Option<SpeedCurve> {
+        let pace = self.pace.filter(|pace| *pace != 1.0)?;
+        let speed = mode.speed();
+        Some(SpeedCurve {
+            base_interval: speed.base_interval * pace,
+            min_interval: speed.min_interval * pace,
+            ramp: speed.ramp * pace,
+        })
+    }
+}
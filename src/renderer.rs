@@ -1,32 +1,344 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
 use three_d::*;
-use crate::game::{GameState, Position, Face};
+use snake3d_core::{Direction, GameState, Position, Face};
+use crate::assets;
+use crate::atlas::{self, Tile};
+use crate::background::Background;
+use crate::clips::Cue;
+use crate::profiler;
+use crate::seasons::Season;
+use crate::settings::{GraphicsPreset, Projection, Settings, Skin, Theme};
+use crate::models::{self, Models, Placed};
+use crate::offscreen::Offscreen;
+use crate::particles::{Particle, ParticlePool};
+use crate::transition::{Transition, TransitionKind};
+
+const SHIELD_COLOR: Srgba = Srgba { r: 0, g: 220, b: 255, a: 255 };
+const FEAST_COLOR: Srgba = Srgba { r: 255, g: 80, b: 200, a: 255 };
+const PARTNER_COLOR: Srgba = Srgba { r: 255, g: 150, b: 30, a: 255 };
+// Most vertices a board is baked with; past this it's drawn instanced,
+// which costs a draw call but not the memory.
+const BAKE_VERTEX_BUDGET: usize = 400_000;
+// The boss in each phase of its script.
+const BOSS_COLORS: [Srgba; 3] = [
+    Srgba { r: 120, g: 60, b: 160, a: 255 },
+    Srgba { r: 170, g: 50, b: 110, a: 255 },
+    Srgba { r: 200, g: 30, b: 40, a: 255 },
+];
+const RING_DOTS: usize = 24;
+const PORTAL_DOTS: usize = 10;
+// One color per portal pair, both ends alike.
+const PORTAL_COLORS: [Srgba; 4] = [
+    Srgba { r: 190, g: 80, b: 255, a: 255 },
+    Srgba { r: 80, g: 255, b: 160, a: 255 },
+    Srgba { r: 255, g: 170, b: 40, a: 255 },
+    Srgba { r: 80, g: 160, b: 255, a: 255 },
+];
+const CURSOR_COLOR: Srgba = Srgba { r: 255, g: 235, b: 60, a: 255 };
+const RIVAL_COLOR: Srgba = Srgba { r: 255, g: 120, b: 0, a: 255 };
+// Seconds for a new tail segment or a new food to grow to full size.
+const GROW_SECONDS: f64 = 0.25;
+const FOOD_SPAWN_SECONDS: f64 = 0.4;
+// Seconds between tongue flicks, and how long one lasts.
+const FLICK_PERIOD: f64 = 1.6;
+const FLICK_SECONDS: f64 = 0.3;
+
+// Unit vectors along a face's u and v directions, matching `pos_to_vec3`.
+pub fn face_axes(face: Face) -> (Vec3, Vec3) {
+    match face {
+        Face::Front => (vec3(1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0)),
+        Face::Back => (vec3(-1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0)),
+        Face::Right => (vec3(0.0, 0.0, -1.0), vec3(0.0, 1.0, 0.0)),
+        Face::Left => (vec3(0.0, 0.0, 1.0), vec3(0.0, 1.0, 0.0)),
+        Face::Top => (vec3(1.0, 0.0, 0.0), vec3(0.0, 0.0, -1.0)),
+        Face::Bottom => (vec3(1.0, 0.0, 0.0), vec3(0.0, 0.0, 1.0)),
+    }
+}
+
+// Die pips marking each face, opposite faces adding up to seven, as (u, v)
+// offsets from the face center.
+fn face_pips(face: Face) -> &'static [(f32, f32)] {
+    match face {
+        Face::Front => &[(0.0, 0.0)],
+        Face::Back => &[(-1.0, -1.0), (-1.0, 0.0), (-1.0, 1.0), (1.0, -1.0), (1.0, 0.0), (1.0, 1.0)],
+        Face::Left => &[(-1.0, -1.0), (1.0, 1.0)],
+        Face::Right => &[(-1.0, -1.0), (-1.0, 1.0), (0.0, 0.0), (1.0, -1.0), (1.0, 1.0)],
+        Face::Top => &[(-1.0, -1.0), (0.0, 0.0), (1.0, 1.0)],
+        Face::Bottom => &[(-1.0, -1.0), (-1.0, 1.0), (1.0, -1.0), (1.0, 1.0)],
+    }
+}
+
+// Point on the spline through p1..p2 at `t`, with p0 and p3 as neighbours.
+fn catmull_rom(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    (p1 * 2.0 + (p2 - p0) * t + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2 + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3) * 0.5
+}
+
+// Pushes a point that cuts across a cube edge back out to the surface.
+fn project_to_cube(p: Vec3, surface: f32) -> Vec3 {
+    let extent = p.x.abs().max(p.y.abs()).max(p.z.abs());
+    if extent > 0.0 { p * (surface / extent) } else { p }
+}
+
+// Moves the object placed by `m` to its mirror position under the face it
+// sits on, treating the cube's surface as a mirror.
+fn reflect_in_board(m: Mat4) -> Mat4 {
+    let p = m.w.truncate();
+    let normal = if p.x.abs() >= p.y.abs() && p.x.abs() >= p.z.abs() {
+        vec3(p.x.signum(), 0.0, 0.0)
+    } else if p.y.abs() >= p.z.abs() {
+        vec3(0.0, p.y.signum(), 0.0)
+    } else {
+        vec3(0.0, 0.0, p.z.signum())
+    };
+    let mut mirrored = m;
+    mirrored.w = (p - normal * 2.0 * (p.dot(normal) - 1.0)).extend(1.0);
+    mirrored
+}
+
+// A flat tile lying on `face`, `size` across.
+fn face_tile(face: Face, size: f32) -> Mat4 {
+    let thin = size * 0.1;
+    match face {
+        Face::Front | Face::Back => Mat4::from_nonuniform_scale(size, size, thin),
+        Face::Left | Face::Right => Mat4::from_nonuniform_scale(thin, size, size),
+        Face::Top | Face::Bottom => Mat4::from_nonuniform_scale(size, thin, size),
+    }
+}
+
+// Copies of `mesh` placed by each transformation, merged into one mesh, so
+// static scenery draws without an instance buffer. Built with the renderer,
+// which is rebuilt whenever the theme or grid size changes.
+fn bake(mesh: &CpuMesh, transformations: &[Mat4]) -> CpuMesh {
+    merge(transformations.iter().map(|transformation| (mesh, *transformation)))
+}
+
+// Meshes, each placed by its transformation, as one.
+pub fn merge<'a>(parts: impl IntoIterator<Item = (&'a CpuMesh, Mat4)>) -> CpuMesh {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut indices = Vec::new();
+    for (mesh, transformation) in parts {
+        let mut copy = mesh.clone();
+        if copy.normals.is_none() {
+            copy.compute_normals();
+        }
+        let template: Vec<u32> = copy.indices.to_u32().unwrap_or_else(|| (0..copy.vertex_count() as u32).collect());
+        // Only a degenerate scale fails to invert; there's nothing to draw.
+        if copy.transform(&transformation).is_err() {
+            continue;
+        }
+        let start = positions.len() as u32;
+        positions.extend(copy.positions.to_f32());
+        normals.extend(copy.normals.unwrap_or_default());
+        indices.extend(template.iter().map(|i| start + i));
+    }
+    CpuMesh {
+        positions: Positions::F32(positions),
+        indices: Indices::U32(indices),
+        normals: Some(normals),
+        ..Default::default()
+    }
+}
+
+// Fingerprints of what each instance buffer last received, by name, so a
+// frame that would send the same instances again skips the upload: a
+// paused snake, an idle board, particles once the last one has expired.
+// On mobile GPUs the transfers are much of what a still frame costs.
+#[derive(Default)]
+struct UploadCache(HashMap<&'static str, u64>);
+
+impl UploadCache {
+    fn set(&mut self, name: &'static str, mesh: &mut InstancedMesh, instances: &Instances) {
+        let print = fingerprint(instances);
+        if self.0.insert(name, print) != Some(print) {
+            mesh.set_instances(instances);
+        }
+    }
+}
+
+fn fingerprint(instances: &Instances) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    instances.transformations.len().hash(&mut hasher);
+    for m in &instances.transformations {
+        for value in AsRef::<[f32; 16]>::as_ref(m) {
+            value.to_bits().hash(&mut hasher);
+        }
+    }
+    for m in instances.texture_transformations.iter().flatten() {
+        for value in AsRef::<[f32; 9]>::as_ref(m) {
+            value.to_bits().hash(&mut hasher);
+        }
+    }
+    if let Some(colors) = &instances.colors {
+        for c in colors {
+            [c.r, c.g, c.b, c.a].hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+// Colors that change with the selected theme.
+struct Palette {
+    board: Srgba,
+    board_emissive: Srgba,
+    grid: Srgba,
+    grid_emissive: Srgba,
+    snake: Srgba,
+    snake_emissive: Srgba,
+    food: Srgba,
+    food_emissive: Srgba,
+    clear: (f32, f32, f32),
+    nebula: [Vec3; 2],
+}
+
+fn palette(name: &str) -> Palette {
+    // Theme colors live in the embedded assets/themes.json.
+    let def = assets::theme(name)
+        .or_else(|| assets::theme(Theme::Neon.name()))
+        .expect("themes.json is embedded at build time");
+    let c = |[r, g, b, a]: [u8; 4]| Srgba::new(r, g, b, a);
+    Palette {
+        board: c(def.board),
+        board_emissive: c(def.board_emissive),
+        grid: c(def.grid),
+        grid_emissive: c(def.grid_emissive),
+        snake: c(def.snake),
+        snake_emissive: c(def.snake_emissive),
+        food: c(def.food),
+        food_emissive: c(def.food_emissive),
+        clear: (def.clear[0], def.clear[1], def.clear[2]),
+        nebula: def.nebula.map(|[r, g, b]| vec3(r, g, b)),
+    }
+}
+
+// Recolors the snake for an unlocked skin.
+fn apply_skin(palette: &mut Palette, skin: Skin) {
+    let (snake, emissive) = match skin {
+        Skin::Classic => return,
+        Skin::Gold => (Srgba::new_opaque(255, 200, 40), Srgba::new_opaque(150, 110, 0)),
+        Skin::Ghost => (Srgba::new_opaque(225, 230, 255), Srgba::new_opaque(90, 90, 140)),
+    };
+    palette.snake = snake;
+    palette.snake_emissive = emissive;
+}
 
 pub struct GameRenderer {
     context: Context,
     camera: Camera,
     // control: OrbitControl, // Disabled for auto-camera
-    board_instances: Gm<InstancedMesh, PhysicalMaterial>,
-    grid_instances: Gm<InstancedMesh, PhysicalMaterial>,
+    // Baked into one mesh (see `bake`) unless the voxels would be too many
+    // vertices, in which case they stay instanced.
+    board: Box<dyn Object>,
+    // Baked, like the face labels.
+    grid_mesh: Gm<Mesh, PhysicalMaterial>,
     snake_instances: Gm<InstancedMesh, PhysicalMaterial>,
-    food_mesh: Gm<Mesh, PhysicalMaterial>,
-    prize_mesh: Gm<Mesh, PhysicalMaterial>,
+    // Host models standing in for the head, food and prize (see `models.rs`).
+    head_model: Option<Placed>,
+    food_model: Option<Placed>,
+    prize_model: Option<Placed>,
+    // The second snake in co-op, drawn cell to cell in its own color.
+    partner_instances: Gm<InstancedMesh, PhysicalMaterial>,
+    // Food, prizes, rush food and the shield (on the board as a pickup, or
+    // around the head while active), each textured from its atlas tile.
+    pickup_instances: Gm<InstancedMesh, PhysicalMaterial>,
+    // Six cones per hazard, pointing along the axes.
+    hazard_instances: Gm<InstancedMesh, PhysicalMaterial>,
+    // Faint die pips in the middle of each face, so faces can be told apart.
+    label_mesh: Gm<Mesh, PhysicalMaterial>,
+    // Tron trail walls.
+    wall_instances: Gm<InstancedMesh, PhysicalMaterial>,
+    // Spinning dots around each portal end.
+    portal_instances: Gm<InstancedMesh, PhysicalMaterial>,
+    // The level editor's cursor.
+    cursor_mesh: Gm<Mesh, PhysicalMaterial>,
+    cursor: Option<Position>,
+    rival_mesh: Gm<Mesh, PhysicalMaterial>,
+    tongue_mesh: Gm<Mesh, PhysicalMaterial>,
+    // Dots around the prize; they disappear as its time runs out.
+    ring_instances: Gm<InstancedMesh, PhysicalMaterial>,
+    // One block per uneaten feast cell.
+    feast_instances: Gm<InstancedMesh, PhysicalMaterial>,
+    turret_instances: Gm<InstancedMesh, PhysicalMaterial>,
+    // The boss's body, plus its weak points; the next one to eat glows.
+    boss_instances: Gm<InstancedMesh, PhysicalMaterial>,
+    weak_point_instances: Gm<InstancedMesh, PhysicalMaterial>,
+    // Projectiles plus the warning markers in front of charging turrets.
+    shot_instances: Gm<InstancedMesh, PhysicalMaterial>,
+    // Closed storm cells, plus the ring about to close and the cell a lost
+    // run ended on.
+    storm_instances: Gm<InstancedMesh, PhysicalMaterial>,
+    // Breadcrumbs along the shortest way from the head to the food.
+    hint_instances: Gm<InstancedMesh, PhysicalMaterial>,
+    // Rims around the snake and food in high contrast mode.
+    outline_instances: Gm<InstancedMesh, PhysicalMaterial>,
+    high_contrast: bool,
+    // Faint mirror images of the snake and food inside the glass (High only).
+    reflection_instances: Gm<InstancedMesh, PhysicalMaterial>,
     particle_system: Gm<InstancedMesh, PhysicalMaterial>,
-    particles: Vec<Particle>,
+    particles: ParticlePool,
+    ambient_occlusion: bool,
+    // Fraction of the viewport the scene renders at (see `resolution.rs`).
+    render_scale: f32,
+    // Created the first time the scene needs a post pass or a lower scale.
+    offscreen: Option<Offscreen>,
     grid_size: i32,
+    graphics: GraphicsPreset,
+    projection: Projection,
+    // Inset in the top right corner looking at the cube from behind.
+    far_side_view: bool,
+    food_hint: bool,
+    // The hint's route, for the head and food it was found between.
+    hint_route: Option<(Position, Position, Vec<Position>)>,
+    clear_color: (f32, f32, f32),
+    // Animated backdrop; Low graphics keep the flat clear color.
+    background: Option<Background>,
+    food_color: Srgba,
+    snake_color: Srgba,
+    grid_emissive: Srgba,
+    // When each body segment appeared, by index from the head.
+    segment_born: Vec<f64>,
+    // The food being drawn and when it appeared.
+    food_born: Option<(Position, bool, f64)>,
+    // Time of the last new-record celebration, for the golden grid flash.
+    celebrated_at: Option<f64>,
+    // Camera cuts instead of swings, and no particles, idle animation or
+    // transitions.
+    reduced_motion: bool,
+    // Position within the beat in rhythm mode; the grid pulses with it.
+    beat: Option<f32>,
+    // Loudness of the music, 0 to 1; the grid glows with it too.
+    music_level: f32,
+    // Kind and start time of the last state transition.
+    transition: Option<(TransitionKind, f64)>,
+    // Slowly circle the cube instead of following the head (main menu).
+    showcase: bool,
     target_pos: Vec3,
     target_up: Vec3,
     time: f64,
-}
-
-struct Particle {
-    start_pos: Vec3,
-    velocity: Vec3,
-    spawn_time: f64,
-    color: Srgba,
+    upload_ms: f64,
+    uploads: UploadCache,
+    // Pumpkin food at Halloween, snow in winter.
+    season: Option<Season>,
 }
 
 impl GameRenderer {
-    pub fn new(context: Context, grid_size: i32) -> Self {
+    // A season, while one runs, overrides the player's theme.
+    pub fn new(context: Context, settings: &Settings, season: Option<Season>, models: &Models) -> Self {
+        let grid_size = settings.grid_size;
+        let mut palette = palette(season.map_or(settings.theme.name(), Season::theme_key));
+        apply_skin(&mut palette, settings.skin);
+        // Board voxels dominate the vertex count, so their detail follows the preset.
+        let board_sphere_detail = match settings.graphics {
+            GraphicsPreset::Low => 6,
+            GraphicsPreset::Medium => 10,
+            GraphicsPreset::High => 16,
+        };
         let camera = Camera::new_perspective(
             Viewport::new_at_origo(1, 1),
             vec3(4.0, 4.0, 4.0),
@@ -60,33 +372,52 @@ impl GameRenderer {
             }
         }
 
-        let mut board_material = PhysicalMaterial::new(
-            &context,
-            &CpuMaterial {
-                albedo: Srgba::new(50, 50, 200, 50), // Transparent blueish
-                roughness: 0.1,
-                metallic: 0.9,
-                emissive: Srgba::new(0, 0, 50, 255), // Slight glow
-                ..Default::default()
-            },
-        );
-        board_material.render_states.blend = Blend::TRANSPARENCY;
-        board_material.render_states.write_mask = WriteMask::COLOR;
+        // High contrast swaps the glass for a flat, opaque dark surface.
+        let high_contrast = settings.high_contrast;
+        let board_material = if high_contrast {
+            PhysicalMaterial::new(
+                &context,
+                &CpuMaterial {
+                    albedo: Srgba::new_opaque(12, 12, 16),
+                    roughness: 1.0,
+                    ..Default::default()
+                },
+            )
+        } else {
+            let mut material = PhysicalMaterial::new(
+                &context,
+                &CpuMaterial {
+                    albedo: palette.board,
+                    roughness: 0.1,
+                    metallic: 0.9,
+                    emissive: palette.board_emissive,
+                    ..Default::default()
+                },
+            );
+            material.render_states.blend = Blend::TRANSPARENCY;
+            material.render_states.write_mask = WriteMask::COLOR;
+            material
+        };
 
         // Use spheres for rounder look
-        let board_instances = Gm::new(
-            InstancedMesh::new(&context, &Instances {
-                transformations: board_transformations, 
-                ..Default::default()
-            }, &CpuMesh::sphere(16)),
-            board_material,
-        );
+        let voxel = CpuMesh::sphere(board_sphere_detail);
+        let board: Box<dyn Object> = if voxel.vertex_count() * board_transformations.len() <= BAKE_VERTEX_BUDGET {
+            Box::new(Gm::new(Mesh::new(&context, &bake(&voxel, &board_transformations)), board_material))
+        } else {
+            Box::new(Gm::new(
+                InstancedMesh::new(&context, &Instances {
+                    transformations: board_transformations,
+                    ..Default::default()
+                }, &voxel),
+                board_material,
+            ))
+        };
 
         // Grid Lines (3D Beams)
         let mut grid_transformations = Vec::new();
         let step = 2.0 / grid_size as f32;
         let offset = 0.002; // Slightly above surface
-        let thickness = 0.02; // Thickness of the grid lines
+        let thickness = if high_contrast { 0.045 } else { 0.02 }; // Thickness of the grid lines
 
         // Helper to add beam
         let mut add_beam = |pos: Vec3, scale: Vec3| {
@@ -124,16 +455,13 @@ impl GameRenderer {
             add_beam(vec3(t, -1.0 - offset, 0.0), vec3(thickness, thickness, 1.0)); // Bottom
         }
 
-        let grid_instances = Gm::new(
-            InstancedMesh::new(&context, &Instances {
-                transformations: grid_transformations,
-                ..Default::default()
-            }, &CpuMesh::cube()),
+        let grid_mesh = Gm::new(
+            Mesh::new(&context, &bake(&CpuMesh::cube(), &grid_transformations)),
             PhysicalMaterial::new(
                 &context,
                 &CpuMaterial {
-                    albedo: Srgba::new(0, 255, 255, 255), // Bright Cyan
-                    emissive: Srgba::new(0, 200, 200, 255), // Glowing
+                    albedo: palette.grid,
+                    emissive: palette.grid_emissive,
                     roughness: 0.5,
                     metallic: 0.5,
                     ..Default::default()
@@ -141,14 +469,54 @@ impl GameRenderer {
             ),
         );
         
+        // Face labels - flattened spheres laid out like die pips
+        let mut label_transformations = Vec::new();
+        for face in Face::ALL {
+            let (axis_u, axis_v) = face_axes(face);
+            let center = axis_u.cross(axis_v) * (1.0 + 0.003);
+            for (u, v) in face_pips(face) {
+                let at = center + (axis_u * *u + axis_v * *v) * 0.3;
+                label_transformations.push(Mat4::from_translation(at) * face_tile(face, 0.07));
+            }
+        }
+        let mut label_material = PhysicalMaterial::new(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new(255, 255, 255, 50),
+                emissive: Srgba::new_opaque(40, 40, 40),
+                roughness: 0.6,
+                ..Default::default()
+            },
+        );
+        label_material.render_states.blend = Blend::TRANSPARENCY;
+        let label_mesh = Gm::new(
+            Mesh::new(&context, &bake(&CpuMesh::sphere(12), &label_transformations)),
+            label_material,
+        );
+
         // Snake Instances
+        let segment = models.body.as_deref().map_or_else(|| CpuMesh::sphere(16), models::shape);
         let snake_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &segment),
+            PhysicalMaterial::new(
+                &context,
+                &CpuMaterial {
+                    albedo: palette.snake,
+                    emissive: palette.snake_emissive,
+                    roughness: 0.2,
+                    metallic: 0.5,
+                    ..Default::default()
+                },
+            ),
+        );
+
+        let partner_instances = Gm::new(
             InstancedMesh::new(&context, &Instances::default(), &CpuMesh::sphere(16)),
             PhysicalMaterial::new(
                 &context,
                 &CpuMaterial {
-                    albedo: Srgba::new_opaque(50, 200, 50), // Green snake
-                    emissive: Srgba::new_opaque(50, 150, 50), // Glow
+                    albedo: PARTNER_COLOR,
+                    emissive: Srgba::new_opaque(60, 30, 0),
                     roughness: 0.2,
                     metallic: 0.5,
                     ..Default::default()
@@ -156,34 +524,248 @@ impl GameRenderer {
             ),
         );
 
-        // Food Mesh - Sphere
-        let food_mesh = Gm::new(
-            Mesh::new(&context, &CpuMesh::sphere(16)),
+        // Pickups - one textured sphere for every kind (see `atlas.rs`)
+        let mut pickup_mesh = CpuMesh::sphere(16);
+        atlas::sphere_uvs(&mut pickup_mesh);
+        let (albedo_atlas, emissive_atlas) = atlas::build(palette.food, palette.food_emissive);
+        let pickup_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &pickup_mesh),
+            PhysicalMaterial {
+                albedo: Srgba::WHITE,
+                albedo_texture: Some(Texture2DRef::from_cpu_texture(&context, &albedo_atlas)),
+                emissive: Srgba::WHITE,
+                emissive_texture: Some(Texture2DRef::from_cpu_texture(&context, &emissive_atlas)),
+                roughness: 0.2,
+                metallic: 0.5,
+                ..PhysicalMaterial::new(&context, &CpuMaterial::default())
+            },
+        );
+
+        // Hazards - spiky red balls built from cones
+        let hazard_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &CpuMesh::cone(12)),
             PhysicalMaterial::new(
                 &context,
                 &CpuMaterial {
-                    albedo: Srgba::new_opaque(200, 50, 50), // Red food
-                    emissive: Srgba::new_opaque(100, 0, 0),
+                    albedo: Srgba::new_opaque(220, 20, 20),
+                    emissive: Srgba::new_opaque(120, 0, 0),
+                    roughness: 0.4,
+                    metallic: 0.3,
                     ..Default::default()
                 },
             ),
         );
 
-        // Prize Mesh - Cylinder (Gold)
-        let prize_mesh = Gm::new(
-            Mesh::new(&context, &CpuMesh::cylinder(16)),
+        // Tron walls - glowing blocks
+        let wall_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &CpuMesh::cube()),
             PhysicalMaterial::new(
                 &context,
                 &CpuMaterial {
-                    albedo: Srgba::new_opaque(255, 215, 0), // Gold
-                    emissive: Srgba::new_opaque(100, 80, 0),
-                    roughness: 0.1,
-                    metallic: 0.9,
+                    albedo: Srgba::new_opaque(0, 200, 255),
+                    emissive: Srgba::new_opaque(0, 160, 220),
+                    roughness: 0.2,
+                    ..Default::default()
+                },
+            ),
+        );
+
+        let portal_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &CpuMesh::sphere(8)),
+            PhysicalMaterial::new(
+                &context,
+                &CpuMaterial {
+                    albedo: Srgba::WHITE,
+                    emissive: Srgba::new_opaque(90, 90, 90),
+                    ..Default::default()
+                },
+            ),
+        );
+
+        let cursor_mesh = Gm::new(
+            Mesh::new(&context, &CpuMesh::cube()),
+            PhysicalMaterial::new(
+                &context,
+                &CpuMaterial {
+                    albedo: CURSOR_COLOR,
+                    emissive: Srgba::new_opaque(160, 140, 0),
+                    ..Default::default()
+                },
+            ),
+        );
+
+        let rival_mesh = Gm::new(
+            Mesh::new(&context, &CpuMesh::cube()),
+            PhysicalMaterial::new(
+                &context,
+                &CpuMaterial {
+                    albedo: RIVAL_COLOR,
+                    emissive: Srgba::new_opaque(160, 60, 0),
+                    roughness: 0.2,
+                    metallic: 0.5,
                     ..Default::default()
                 },
             ),
         );
 
+        // Tongue - thin red cylinder flicking out of the head
+        let tongue_mesh = Gm::new(
+            Mesh::new(&context, &CpuMesh::cylinder(8)),
+            PhysicalMaterial::new(
+                &context,
+                &CpuMaterial {
+                    albedo: Srgba::new_opaque(230, 30, 60),
+                    emissive: Srgba::new_opaque(90, 0, 20),
+                    roughness: 0.3,
+                    ..Default::default()
+                },
+            ),
+        );
+
+        // Prize countdown ring - small gold dots
+        let ring_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &CpuMesh::sphere(6)),
+            PhysicalMaterial::new(
+                &context,
+                &CpuMaterial {
+                    albedo: Srgba::new_opaque(255, 215, 0),
+                    emissive: Srgba::new_opaque(160, 120, 0),
+                    ..Default::default()
+                },
+            ),
+        );
+
+        // Feast - pink blocks
+        let feast_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &CpuMesh::cube()),
+            PhysicalMaterial::new(
+                &context,
+                &CpuMaterial {
+                    albedo: FEAST_COLOR,
+                    emissive: Srgba::new_opaque(110, 20, 80),
+                    roughness: 0.3,
+                    ..Default::default()
+                },
+            ),
+        );
+
+        // Turrets - cylinders, colored per instance so charging ones can blink
+        let turret_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &CpuMesh::cylinder(12)),
+            PhysicalMaterial::new(
+                &context,
+                &CpuMaterial {
+                    albedo: Srgba::WHITE,
+                    roughness: 0.5,
+                    metallic: 0.7,
+                    ..Default::default()
+                },
+            ),
+        );
+
+        // Boss - one block over its 2x2 cells, colored by phase
+        let boss_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &CpuMesh::cube()),
+            PhysicalMaterial::new(
+                &context,
+                &CpuMaterial {
+                    albedo: Srgba::WHITE,
+                    roughness: 0.6,
+                    metallic: 0.3,
+                    ..Default::default()
+                },
+            ),
+        );
+        let weak_point_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &CpuMesh::sphere(12)),
+            PhysicalMaterial::new(
+                &context,
+                &CpuMaterial {
+                    albedo: Srgba::WHITE,
+                    emissive: Srgba::new_opaque(80, 80, 0),
+                    ..Default::default()
+                },
+            ),
+        );
+
+        let shot_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &CpuMesh::sphere(8)),
+            PhysicalMaterial::new(
+                &context,
+                &CpuMaterial {
+                    albedo: Srgba::WHITE,
+                    emissive: Srgba::new_opaque(120, 60, 0),
+                    ..Default::default()
+                },
+            ),
+        );
+
+        // Storm - red tiles over closed cells, colored per instance; the
+        // warning ring fades in and out
+        let mut storm_material = PhysicalMaterial::new(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::WHITE,
+                emissive: Srgba::new_opaque(90, 0, 0),
+                roughness: 0.8,
+                ..Default::default()
+            },
+        );
+        storm_material.render_states.blend = Blend::TRANSPARENCY;
+        let storm_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &CpuMesh::cube()),
+            storm_material,
+        );
+
+        // Food hint - faint tiles in the food's color, a glow running along them
+        let mut hint_material = PhysicalMaterial::new(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::WHITE,
+                emissive: Srgba::new_opaque(40, 40, 40),
+                roughness: 0.8,
+                ..Default::default()
+            },
+        );
+        hint_material.render_states.blend = Blend::TRANSPARENCY;
+        let hint_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &CpuMesh::cube()),
+            hint_material,
+        );
+
+        // Reflections - colored per instance, blended over the board
+        let mut reflection_material = PhysicalMaterial::new(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::WHITE,
+                roughness: 0.1,
+                metallic: 0.9,
+                ..Default::default()
+            },
+        );
+        reflection_material.render_states.blend = Blend::TRANSPARENCY;
+        reflection_material.render_states.write_mask = WriteMask::COLOR;
+        let reflection_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &CpuMesh::sphere(12)),
+            reflection_material,
+        );
+
+        // Outlines - inverted hulls: slightly larger copies with only their
+        // back faces drawn, showing as a bright rim around the snake and food
+        let mut outline_material = PhysicalMaterial::new(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::WHITE,
+                emissive: Srgba::WHITE,
+                ..Default::default()
+            },
+        );
+        outline_material.render_states.cull = Cull::Front;
+        let outline_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &CpuMesh::sphere(12)),
+            outline_material,
+        );
+
         // Particle System
         let particle_system = Gm::new(
             InstancedMesh::new(&context, &Instances::default(), &CpuMesh::sphere(8)),
@@ -197,24 +779,153 @@ impl GameRenderer {
             )
         );
 
+        let place = |model: &Option<Rc<CpuModel>>| model.as_deref().and_then(|m| Placed::new(&context, m));
+        let (head_model, food_model, prize_model) = (place(&models.head), place(&models.food), place(&models.prize));
+
         Self {
             context,
             camera,
             // control,
-            board_instances,
-            grid_instances,
+            board,
+            grid_mesh,
+            label_mesh,
             snake_instances,
-            food_mesh,
-            prize_mesh,
+            head_model,
+            food_model,
+            prize_model,
+            partner_instances,
+            pickup_instances,
+            hazard_instances,
+            wall_instances,
+            portal_instances,
+            cursor_mesh,
+            cursor: None,
+            rival_mesh,
+            tongue_mesh,
+            ring_instances,
+            feast_instances,
+            turret_instances,
+            boss_instances,
+            weak_point_instances,
+            shot_instances,
+            storm_instances,
+            hint_instances,
+            outline_instances,
+            high_contrast,
+            reflection_instances,
             particle_system,
-            particles: Vec::new(),
+            particles: ParticlePool::default(),
+            ambient_occlusion: settings.ambient_occlusion,
+            render_scale: 1.0,
+            offscreen: None,
             grid_size,
+            graphics: settings.graphics,
+            projection: settings.projection,
+            far_side_view: settings.far_side_view,
+            food_hint: settings.food_hint,
+            hint_route: None,
+            clear_color: palette.clear,
+            background: (settings.graphics != GraphicsPreset::Low).then(|| Background {
+                clear: vec3(palette.clear.0, palette.clear.1, palette.clear.2),
+                nebula: palette.nebula,
+                time: 0.0,
+            }),
+            food_color: palette.food,
+            snake_color: palette.snake,
+            grid_emissive: palette.grid_emissive,
+            celebrated_at: None,
+            segment_born: Vec::new(),
+            food_born: None,
+            reduced_motion: settings.effective_reduced_motion(),
+            beat: None,
+            music_level: 0.0,
+            transition: None,
+            showcase: false,
             target_pos: vec3(0.0, 0.0, 4.0),
             target_up: vec3(0.0, 1.0, 0.0),
             time: 0.0,
+            upload_ms: 0.0,
+            uploads: UploadCache::default(),
+            season,
+        }
+    }
+
+    pub fn set_render_scale(&mut self, scale: f32) {
+        self.render_scale = scale;
+    }
+
+    // Switching projection needs no rebuild; it applies from the next frame.
+    pub fn set_projection(&mut self, projection: Projection) {
+        self.projection = projection;
+    }
+
+    pub fn set_far_side_view(&mut self, far_side_view: bool) {
+        self.far_side_view = far_side_view;
+    }
+
+    pub fn set_food_hint(&mut self, food_hint: bool) {
+        self.food_hint = food_hint;
+    }
+
+    pub fn set_reduced_motion(&mut self, reduced_motion: bool) {
+        self.reduced_motion = reduced_motion;
+        if reduced_motion {
+            self.particles.clear();
+            self.transition = None;
+        }
+    }
+
+    pub fn start_transition(&mut self, kind: TransitionKind) {
+        if !self.reduced_motion {
+            self.transition = Some((kind, self.time));
         }
     }
 
+    // Draws the running transition, if any, over whatever is on `target`.
+    pub fn render_transition(&mut self, target: &RenderTarget) {
+        let (kind, start) = match self.transition {
+            Some(transition) => transition,
+            None => return,
+        };
+        let progress = match Transition::progress(start, self.time) {
+            Some(progress) => progress,
+            None => {
+                self.transition = None;
+                return;
+            }
+        };
+        let (r, g, b) = self.clear_color;
+        let effect = Transition { kind, color: vec3(r, g, b), progress };
+        target.apply_screen_effect(&effect, &self.camera, &[], None, None);
+    }
+
+    pub fn set_beat(&mut self, beat: Option<f32>) {
+        self.beat = beat;
+    }
+
+    pub fn set_music_level(&mut self, level: f32) {
+        self.music_level = level;
+    }
+
+    pub fn set_showcase(&mut self, showcase: bool) {
+        self.showcase = showcase;
+    }
+
+    // Shows the editor cursor, which the camera follows instead of the snake.
+    #[cfg(feature = "ui")]
+    pub fn set_cursor(&mut self, cursor: Option<Position>) {
+        self.cursor = cursor;
+    }
+
+    pub fn grid_size(&self) -> i32 {
+        self.grid_size
+    }
+
+    // Time spent uploading instance buffers during the last `render`, in ms.
+    pub fn upload_ms(&self) -> f64 {
+        self.upload_ms
+    }
+
     pub fn resize(&mut self, width: u32, height: u32) {
         self.camera.set_viewport(Viewport::new_at_origo(width, height));
     }
@@ -237,8 +948,12 @@ impl GameRenderer {
         self.target_up = up;
     }
 
-    pub fn render(&mut self, game: &GameState, target: &RenderTarget, dt: f64) {
+    // `prev_body` is the snake before the latest tick and `alpha` the fraction
+    // of the way to the next tick; segments are drawn in between the two.
+    pub fn render(&mut self, game: &GameState, prev_body: &[Position], alpha: f32, target: &RenderTarget, dt: f64) {
         self.time += dt;
+        // Clock for purely decorative animation, stopped in reduced motion.
+        let motion = if self.reduced_motion { 0.0 } else { self.time };
 
         // Calculate required distance based on aspect ratio
         let viewport = self.camera.viewport();
@@ -251,11 +966,16 @@ impl GameRenderer {
         };
 
         // Update Camera Position based on Snake Head
-        self.update_camera_target(game.snake.head().face, dist);
+        self.update_camera_target(self.cursor.map_or(game.snake.head().face, |cursor| cursor.face), dist);
+        if self.showcase {
+            let angle = (motion * 0.25) as f32;
+            self.target_pos = vec3(angle.sin() * dist * 0.8, dist * 0.5, angle.cos() * dist * 0.8);
+            self.target_up = vec3(0.0, 1.0, 0.0);
+        }
 
         // Smoothly interpolate camera
         let speed = 5.0; // Adjust for smoothness
-        let t = (speed * dt as f32).min(1.0);
+        let t = if self.reduced_motion { 1.0 } else { (speed * dt as f32).min(1.0) };
         
         let current_pos = *self.camera.position();
         let current_up = *self.camera.up();
@@ -263,15 +983,32 @@ impl GameRenderer {
         let new_pos = current_pos.lerp(self.target_pos, t);
         let new_up = current_up.lerp(self.target_up, t).normalize();
         
-        self.camera = Camera::new_perspective(
-            self.camera.viewport(),
-            new_pos,
-            vec3(0.0, 0.0, 0.0),
-            new_up,
-            degrees(45.0),
-            0.1,
-            100.0,
-        );
+        self.camera = match self.projection {
+            Projection::Perspective => Camera::new_perspective(
+                self.camera.viewport(),
+                new_pos,
+                vec3(0.0, 0.0, 0.0),
+                new_up,
+                degrees(45.0),
+                0.1,
+                100.0,
+            ),
+            // Extents rather than distance: tall enough for the cube and
+            // whatever sits on it, and wide enough on portrait screens.
+            Projection::Orthographic => {
+                let extent = 3.2;
+                let height = if aspect < 1.0 { extent / aspect } else { extent };
+                Camera::new_orthographic(
+                    self.camera.viewport(),
+                    new_pos,
+                    vec3(0.0, 0.0, 0.0),
+                    new_up,
+                    height,
+                    0.1,
+                    100.0,
+                )
+            }
+        };
 
         let cell_size = 2.0 / self.grid_size as f32;
         let offset = 0.05; // Lift off surface
@@ -286,55 +1023,510 @@ impl GameRenderer {
         // Let's just spawn particles in `lib.rs` by calling a new method on renderer.
 
         // Update Snake Instances
-        let transformations: Vec<Mat4> = game.snake.body.iter().map(|pos| {
-            let center = self.pos_to_vec3(*pos, cell_size, offset);
-            Mat4::from_translation(center) * Mat4::from_scale(cell_size * 0.6) // Smaller snake
+        // A segment that just grew has no previous position and stays put.
+        let surface = 1.0 + offset;
+        let centers: Vec<Vec3> = game.snake.body.iter().enumerate().map(|(i, pos)| {
+            let from = prev_body.get(i).copied().unwrap_or(*pos);
+            let center = self.pos_to_vec3(from, cell_size, offset)
+                .lerp(self.pos_to_vec3(*pos, cell_size, offset), alpha);
+            project_to_cube(center, surface)
         }).collect();
+
+        // Extra spheres along a Catmull-Rom spline through the segment centers
+        // round off the corners of the chain.
+        let steps = match self.graphics {
+            GraphicsPreset::Low => 1,
+            GraphicsPreset::Medium => 2,
+            GraphicsPreset::High => 4,
+        };
+        let at = |i: isize| centers[i.clamp(0, centers.len() as isize - 1) as usize];
+        let mut path = Vec::with_capacity(centers.len() * steps);
+        for i in 0..centers.len() as isize {
+            path.push(at(i));
+            if i + 1 == centers.len() as isize {
+                break;
+            }
+            for s in 1..steps {
+                let t = s as f32 / steps as f32;
+                path.push(project_to_cube(catmull_rom(at(i - 1), at(i), at(i + 1), at(i + 2), t), surface));
+            }
+        }
+        // New tail segments grow in from nothing. Segments already there when
+        // the renderer first sees the snake start at full size.
+        let len = game.snake.body.len();
+        let born = if self.segment_born.is_empty() || self.reduced_motion { f64::NEG_INFINITY } else { self.time };
+        self.segment_born.resize(len, born);
+        let segment_scale = |i: usize| ((self.time - self.segment_born[i]) / GROW_SECONDS).min(1.0) as f32;
+
+        // Idle breathing: a slow swell that runs down the body. Spline points
+        // between two segments take the size of the one further back.
+        let transformations: Vec<Mat4> = path.into_iter().enumerate().map(|(i, center)| {
+            let breath = 1.0 + 0.04 * (motion * 3.0 - i as f64 / steps as f64 * 0.6).sin() as f32;
+            let grow = segment_scale(i.div_ceil(steps).min(len - 1));
+            Mat4::from_translation(center) * Mat4::from_scale(cell_size * 0.6 * breath * grow) // Smaller snake
+        }).collect();
+
+        let (axis_u, axis_v) = face_axes(game.snake.head().face);
+        let normal = axis_u.cross(axis_v);
+        let forward = match game.snake.direction {
+            Direction::Up => axis_v,
+            Direction::Down => -axis_v,
+            Direction::Left => -axis_u,
+            Direction::Right => axis_u,
+        };
+        // A head model faces along its +Z with +Y up, off the board.
+        if let Some(model) = &mut self.head_model {
+            let facing = Mat4::from_cols(
+                normal.cross(forward).extend(0.0),
+                normal.extend(0.0),
+                forward.extend(0.0),
+                vec4(0.0, 0.0, 0.0, 1.0),
+            );
+            model.place(Mat4::from_translation(centers[0]) * facing * Mat4::from_scale(cell_size * 0.6));
+        }
+
+        // Tongue: flicks out ahead of the head now and then.
+        let flick = self.time % FLICK_PERIOD;
+        let tongue_out = !game.game_over && !self.reduced_motion && flick < FLICK_SECONDS;
+        if tongue_out {
+            // Out and back in, with a little side-to-side flutter.
+            let reach = (flick / FLICK_SECONDS * std::f64::consts::PI).sin() as f32;
+            let flutter = Mat4::from_axis_angle(normal, radians((self.time * 40.0).sin() as f32 * 0.3));
+            let basis = Mat4::from_cols(
+                forward.extend(0.0),
+                normal.extend(0.0),
+                forward.cross(normal).extend(0.0),
+                vec4(0.0, 0.0, 0.0, 1.0),
+            );
+            let tip = centers[0] + forward * cell_size * 0.3;
+            self.tongue_mesh.set_transformation(
+                Mat4::from_translation(tip)
+                    * flutter
+                    * basis
+                    * Mat4::from_nonuniform_scale(cell_size * 0.4 * reach, cell_size * 0.03, cell_size * 0.03),
+            );
+        }
         
+        // Reflections need the glass, so high contrast goes without.
+        let reflect = self.graphics == GraphicsPreset::High && !self.high_contrast;
+        let mut outline_transformations = Vec::new();
+        if self.high_contrast {
+            outline_transformations.extend(transformations.iter().map(|m| m * Mat4::from_scale(1.3)));
+        }
+        let mut reflection_transformations = Vec::new();
+        let mut reflection_colors = Vec::new();
+        if reflect {
+            let ghost = Srgba::new(self.snake_color.r, self.snake_color.g, self.snake_color.b, 60);
+            reflection_transformations.extend(transformations.iter().map(|m| reflect_in_board(*m)));
+            reflection_colors.resize(reflection_transformations.len(), ghost);
+        }
+
+        // The head model, if any, stands in for the first sphere.
+        let skip = usize::from(self.head_model.is_some());
         let instances = Instances {
-            transformations,
+            transformations: transformations.into_iter().skip(skip).collect(),
             ..Default::default()
         };
-        self.snake_instances.geometry.set_instances(&instances);
+        let upload_start = profiler::now();
+        self.uploads.set("snake_instances", &mut self.snake_instances.geometry, &instances);
+        let mut upload_ms = profiler::now() - upload_start;
+
+        let partner_transformations: Vec<Mat4> = game.partner.iter().flat_map(|partner| {
+            partner.body.iter().enumerate().map(|(i, pos)| {
+                let scale = if i == 0 { 0.55 } else { 0.45 };
+                Mat4::from_translation(self.pos_to_vec3(*pos, cell_size, offset)) * Mat4::from_scale(cell_size * scale)
+            })
+        }).collect();
+        let upload_start = profiler::now();
+        self.uploads.set("partner_instances", &mut self.partner_instances.geometry, &Instances {
+            transformations: partner_transformations,
+            ..Default::default()
+        });
+        upload_ms += profiler::now() - upload_start;
 
         // Update Food Position & Animation
         let food_pos = self.pos_to_vec3(game.food, cell_size, offset);
-        let bounce = (self.time * 5.0).sin() as f32 * 0.05;
-        let rotate = Mat4::from_angle_y(radians((self.time * 2.0) as f32));
+        let bounce = (motion * 5.0).sin() as f32 * 0.05;
+        // New food pops in with an overshoot and an extra half turn.
+        if self.food_born.is_none_or(|(pos, prize, _)| pos != game.food || prize != game.is_prize) {
+            self.food_born = Some((game.food, game.is_prize, self.time));
+            let model = if game.is_prize { &mut self.prize_model } else { &mut self.food_model };
+            if let (Some(model), false) = (model, self.reduced_motion) {
+                model.cue(Cue::Spawn, self.time);
+            }
+        }
+        let spawn = match self.food_born {
+            Some((_, _, at)) if !self.reduced_motion => ((self.time - at) / FOOD_SPAWN_SECONDS).min(1.0) as f32,
+            _ => 1.0,
+        };
+        let pop = 1.0 + 2.7 * (spawn - 1.0).powi(3) + 1.7 * (spawn - 1.0).powi(2);
+        let spin = (1.0 - spawn) * std::f32::consts::PI;
+        let rotate = Mat4::from_angle_y(radians((motion * 2.0) as f32 + spin));
 
-        let food_scale = if game.is_prize { cell_size * 0.5 } else { cell_size * 0.4 };
-        let food_transform = Mat4::from_translation(food_pos + vec3(0.0, 0.0, bounce)) * rotate * Mat4::from_scale(food_scale);
+        // Expiring food shrinks over its lifetime and blinks near the end.
+        let remaining = game.food_remaining().unwrap_or(1.0);
+        let blink_out = remaining < 0.25 && (motion * 10.0).sin() < 0.0;
+        let life_scale = if blink_out { 0.0 } else { 0.5 + 0.5 * remaining };
+        let food_scale = pop * life_scale * if game.is_prize { cell_size * 0.5 } else { cell_size * 0.4 };
+        // Pumpkins are squat.
+        let squash = if self.season == Some(Season::Halloween) { vec3(1.2, 0.85, 1.2) } else { vec3(1.0, 1.0, 1.0) };
+        let food_transform = Mat4::from_translation(food_pos + vec3(0.0, 0.0, bounce))
+            * rotate
+            * Mat4::from_nonuniform_scale(squash.x * food_scale, squash.y * food_scale, squash.z * food_scale);
 
-        if game.is_prize {
-            self.prize_mesh.set_transformation(food_transform);
-        } else {
-            self.food_mesh.set_transformation(food_transform);
+        // Every pickup goes into one instanced draw, each with its atlas tile,
+        // unless a model stands in for the food.
+        let mut pickup_transformations = Vec::new();
+        let mut pickup_tiles = Vec::new();
+        let food_model = if game.is_prize { &mut self.prize_model } else { &mut self.food_model };
+        match food_model {
+            Some(model) => model.place(food_transform),
+            None => {
+                pickup_transformations.push(food_transform);
+                pickup_tiles.push(if game.is_prize { Tile::Prize } else { atlas::food_tile(self.season) });
+            }
         }
 
-        // Update Particles
-        let mut particle_transformations = Vec::new();
-        let mut particle_colors = Vec::new();
+        if self.high_contrast {
+            outline_transformations.push(food_transform * Mat4::from_scale(1.4));
+        }
+        // Model clips play on the same clock; reduced motion holds them at
+        // the start of their idle.
+        let clip_time = if self.reduced_motion { 0.0 } else { self.time };
+        for model in [&mut self.head_model, &mut self.food_model, &mut self.prize_model].into_iter().flatten() {
+            model.animate(clip_time);
+        }
+        let upload_start = profiler::now();
+        self.uploads.set("outline_instances", &mut self.outline_instances.geometry, &Instances {
+            transformations: outline_transformations,
+            ..Default::default()
+        });
+        upload_ms += profiler::now() - upload_start;
 
-        self.particles.retain(|p| self.time - p.spawn_time < 1.0);
+        if reflect {
+            let color = if game.is_prize { Srgba::new_opaque(255, 215, 0) } else { self.food_color };
+            reflection_transformations.push(reflect_in_board(food_transform));
+            reflection_colors.push(Srgba::new(color.r, color.g, color.b, 60));
+        }
+        let upload_start = profiler::now();
+        self.uploads.set("reflection_instances", &mut self.reflection_instances.geometry, &Instances {
+            transformations: reflection_transformations,
+            colors: Some(reflection_colors),
+            ..Default::default()
+        });
+        upload_ms += profiler::now() - upload_start;
 
-        for p in &self.particles {
-            let age = (self.time - p.spawn_time) as f32;
-            let pos = p.start_pos + p.velocity * age;
-            let scale = (1.0 - age) * 0.05;
-            particle_transformations.push(Mat4::from_translation(pos) * Mat4::from_scale(scale));
-            particle_colors.push(p.color);
+        // Shield: spinning pickup on the board, or a bubble around the head
+        let shield_pos = if game.shielded {
+            Some((self.pos_to_vec3(game.snake.head(), cell_size, offset), cell_size * 0.45))
+        } else {
+            game.shield.map(|pos| (self.pos_to_vec3(pos, cell_size, offset), cell_size * 0.3))
+        };
+        if let Some((center, scale)) = shield_pos {
+            let spin = Mat4::from_angle_z(radians((motion * 3.0) as f32)) * Mat4::from_angle_x(radians(0.6));
+            pickup_transformations.push(Mat4::from_translation(center) * spin * Mat4::from_scale(scale));
+            pickup_tiles.push(Tile::Shield);
         }
 
-        let particle_instances = Instances {
-            transformations: particle_transformations,
-            colors: Some(particle_colors),
+        // Update Hazards
+        let spikes = [
+            Mat4::from_scale(1.0),
+            Mat4::from_angle_y(degrees(180.0)),
+            Mat4::from_angle_z(degrees(90.0)),
+            Mat4::from_angle_z(degrees(-90.0)),
+            Mat4::from_angle_y(degrees(-90.0)),
+            Mat4::from_angle_y(degrees(90.0)),
+        ];
+        let wobble = Mat4::from_angle_z(radians((motion * 1.5) as f32));
+        let spike_shape = Mat4::from_nonuniform_scale(cell_size * 0.45, cell_size * 0.15, cell_size * 0.15);
+        let hazard_transformations: Vec<Mat4> = game.hazards.iter().flat_map(|h| {
+            let center = Mat4::from_translation(self.pos_to_vec3(h.pos, cell_size, offset)) * wobble;
+            spikes.iter().map(move |spike| center * spike * spike_shape)
+        }).collect();
+        let upload_start = profiler::now();
+        self.uploads.set("hazard_instances", &mut self.hazard_instances.geometry, &Instances {
+            transformations: hazard_transformations,
             ..Default::default()
+        });
+        upload_ms += profiler::now() - upload_start;
+
+        // Tron walls & rival
+        let wall_transformations: Vec<Mat4> = game.walls.iter().map(|cell| {
+            Mat4::from_translation(self.pos_to_vec3(*cell, cell_size, offset)) * Mat4::from_scale(cell_size * 0.4)
+        }).collect();
+        let upload_start = profiler::now();
+        self.uploads.set("wall_instances", &mut self.wall_instances.geometry, &Instances {
+            transformations: wall_transformations,
+            ..Default::default()
+        });
+        upload_ms += profiler::now() - upload_start;
+        // Portals
+        let mut portal_transformations = Vec::new();
+        let mut portal_colors = Vec::new();
+        for (i, portal) in game.portals.iter().enumerate() {
+            for end in [portal.a, portal.b] {
+                let (axis_u, axis_v) = face_axes(end.face);
+                let center = self.pos_to_vec3(end, cell_size, offset);
+                for dot in 0..PORTAL_DOTS {
+                    let angle = dot as f32 / PORTAL_DOTS as f32 * std::f32::consts::TAU + motion as f32 * 2.0;
+                    let at = center + (axis_u * angle.sin() + axis_v * angle.cos()) * cell_size * 0.35;
+                    portal_transformations.push(Mat4::from_translation(at) * Mat4::from_scale(cell_size * 0.07));
+                    portal_colors.push(PORTAL_COLORS[i % PORTAL_COLORS.len()]);
+                }
+            }
+        }
+        let upload_start = profiler::now();
+        self.uploads.set("portal_instances", &mut self.portal_instances.geometry, &Instances {
+            transformations: portal_transformations,
+            colors: Some(portal_colors),
+            ..Default::default()
+        });
+        upload_ms += profiler::now() - upload_start;
+        if let Some(cursor) = self.cursor {
+            let pulse = 1.0 + (motion * 6.0).sin() as f32 * 0.1;
+            let center = self.pos_to_vec3(cursor, cell_size, offset);
+            self.cursor_mesh.set_transformation(Mat4::from_translation(center) * Mat4::from_scale(cell_size * 0.3 * pulse));
+        }
+
+        if let Some(rival) = game.rival {
+            let center = self.pos_to_vec3(rival.pos, cell_size, offset);
+            self.rival_mesh.set_transformation(Mat4::from_translation(center) * Mat4::from_scale(cell_size * 0.35));
+        }
+
+        // Prize countdown ring, in the plane of the prize's face
+        let ring_transformations: Vec<Mat4> = match game.prize_remaining() {
+            Some(remaining) => {
+                let (axis_u, axis_v) = face_axes(game.food.face);
+                let radius = cell_size * 0.8;
+                let dots = (RING_DOTS as f32 * remaining).ceil() as usize;
+                (0..dots).map(|i| {
+                    let angle = i as f32 / RING_DOTS as f32 * std::f32::consts::TAU;
+                    let at = food_pos + (axis_u * angle.sin() + axis_v * angle.cos()) * radius;
+                    Mat4::from_translation(at) * Mat4::from_scale(cell_size * 0.06)
+                }).collect()
+            }
+            None => Vec::new(),
         };
-        self.particle_system.geometry.set_instances(&particle_instances);
+        let upload_start = profiler::now();
+        self.uploads.set("ring_instances", &mut self.ring_instances.geometry, &Instances {
+            transformations: ring_transformations,
+            ..Default::default()
+        });
+        upload_ms += profiler::now() - upload_start;
+
+        // Update Rush Food
+        for pos in &game.rush_food {
+            pickup_transformations.push(
+                Mat4::from_translation(self.pos_to_vec3(*pos, cell_size, offset) + vec3(0.0, 0.0, bounce)) * Mat4::from_scale(cell_size * 0.3),
+            );
+            pickup_tiles.push(atlas::food_tile(self.season));
+        }
+        let upload_start = profiler::now();
+        self.uploads.set("pickup_instances", &mut self.pickup_instances.geometry, &Instances {
+            transformations: pickup_transformations,
+            texture_transformations: Some(pickup_tiles.into_iter().map(Tile::uv_transform).collect()),
+            ..Default::default()
+        });
+        upload_ms += profiler::now() - upload_start;
+
+        // Update Feast
+        // The remaining cells are drawn as one block that loses a corner per bite.
+        let feast_pulse = 1.0 + (motion * 4.0).sin() as f32 * 0.04;
+        let feast_transformations: Vec<Mat4> = game.feast.iter().flat_map(|f| f.cells.iter()).map(|cell| {
+            Mat4::from_translation(self.pos_to_vec3(*cell, cell_size, offset)) * Mat4::from_scale(cell_size * 0.5 * feast_pulse)
+        }).collect();
+        let upload_start = profiler::now();
+        self.uploads.set("feast_instances", &mut self.feast_instances.geometry, &Instances {
+            transformations: feast_transformations,
+            ..Default::default()
+        });
+        upload_ms += profiler::now() - upload_start;
+
+        // Update Boss
+        // Its body darkens towards red as the script goes on; the weak point
+        // to eat next pulses, the rest wait dimly in line.
+        let mut boss_transformations = Vec::new();
+        let mut boss_colors = Vec::new();
+        let mut weak_transformations = Vec::new();
+        let mut weak_colors = Vec::new();
+        if let Some(boss) = &game.boss {
+            let cells: Vec<Vec3> = boss.cells().map(|cell| self.pos_to_vec3(cell, cell_size, offset)).collect();
+            let center = cells.iter().fold(vec3(0.0, 0.0, 0.0), |sum, c| sum + *c) / cells.len() as f32;
+            boss_transformations.push(Mat4::from_translation(center) * Mat4::from_scale(cell_size * 0.95));
+            boss_colors.push(BOSS_COLORS[boss.phase.min(BOSS_COLORS.len() - 1)]);
+            let pulse = 1.0 + (motion * 8.0).sin() as f32 * 0.2;
+            for (i, pos) in boss.weak_points.iter().enumerate() {
+                let scale = if i == 0 { cell_size * 0.3 * pulse } else { cell_size * 0.2 };
+                weak_transformations.push(
+                    Mat4::from_translation(self.pos_to_vec3(*pos, cell_size, offset)) * Mat4::from_scale(scale),
+                );
+                weak_colors.push(if i == 0 { Srgba::new_opaque(255, 230, 0) } else { Srgba::new_opaque(120, 110, 60) });
+            }
+        }
+        let upload_start = profiler::now();
+        self.uploads.set("boss_instances", &mut self.boss_instances.geometry, &Instances {
+            transformations: boss_transformations,
+            colors: Some(boss_colors),
+            ..Default::default()
+        });
+        self.uploads.set("weak_point_instances", &mut self.weak_point_instances.geometry, &Instances {
+            transformations: weak_transformations,
+            colors: Some(weak_colors),
+            ..Default::default()
+        });
+        upload_ms += profiler::now() - upload_start;
+
+        // Update Turrets & Projectiles
+        // A charging turret blinks and marks the first cells of its line of fire.
+        let blink = (self.time * 8.0).sin() > 0.0;
+        let mut turret_transformations = Vec::new();
+        let mut turret_colors = Vec::new();
+        let mut shot_transformations = Vec::new();
+        let mut shot_colors = Vec::new();
+        for turret in &game.turrets {
+            let center = self.pos_to_vec3(turret.pos, cell_size, offset);
+            turret_transformations.push(
+                Mat4::from_translation(center) * Mat4::from_angle_z(degrees(90.0)) * Mat4::from_scale(cell_size * 0.35),
+            );
+            let warning = turret.charging() && blink;
+            turret_colors.push(if warning { Srgba::new_opaque(255, 60, 0) } else { Srgba::new_opaque(90, 90, 100) });
+
+            if turret.charging() {
+                let (mut pos, mut dir) = (turret.pos, turret.dir);
+                for _ in 0..3 {
+                    (pos, dir) = game.calculate_next_position(pos, dir);
+                    shot_transformations.push(
+                        Mat4::from_translation(self.pos_to_vec3(pos, cell_size, 0.0)) * Mat4::from_scale(cell_size * 0.1),
+                    );
+                    shot_colors.push(Srgba::new(255, 40, 0, if blink { 255 } else { 120 }));
+                }
+            }
+        }
+        for shot in &game.projectiles {
+            shot_transformations.push(
+                Mat4::from_translation(self.pos_to_vec3(shot.pos, cell_size, offset)) * Mat4::from_scale(cell_size * 0.2),
+            );
+            shot_colors.push(Srgba::new_opaque(255, 160, 0));
+        }
+        let upload_start = profiler::now();
+        self.uploads.set("turret_instances", &mut self.turret_instances.geometry, &Instances {
+            transformations: turret_transformations,
+            colors: Some(turret_colors),
+            ..Default::default()
+        });
+        self.uploads.set("shot_instances", &mut self.shot_instances.geometry, &Instances {
+            transformations: shot_transformations,
+            colors: Some(shot_colors),
+            ..Default::default()
+        });
+        upload_ms += profiler::now() - upload_start;
+
+        // Update Storm
+        // The next ring pulses dark red during its warning, and the fatal cell
+        // flashes once the run is lost.
+        let mut storm_transformations = Vec::new();
+        let mut storm_colors = Vec::new();
+        if game.config.storm {
+            let warning = game.storm_warning();
+            let pulse = ((self.time * 6.0).sin() * 0.5 + 0.5) as f32;
+            for face in Face::ALL {
+                for u in 0..self.grid_size {
+                    for v in 0..self.grid_size {
+                        let pos = Position { face, u, v };
+                        let color = if game.storm_at(pos) {
+                            Srgba::new_opaque(200, 0, 0)
+                        } else if warning == Some(game.ring_of(pos)) {
+                            Srgba::new(120, 0, 0, (80.0 + 120.0 * pulse) as u8)
+                        } else {
+                            continue;
+                        };
+                        storm_transformations.push(
+                            Mat4::from_translation(self.pos_to_vec3(pos, cell_size, 0.01)) * face_tile(face, cell_size * 0.48),
+                        );
+                        storm_colors.push(color);
+                    }
+                }
+            }
+        }
+        if let (true, Some(death)) = (game.game_over, game.death) {
+            let flash = if self.reduced_motion { 1.0 } else { ((self.time * 8.0).sin() * 0.5 + 0.5) as f32 };
+            storm_transformations.push(
+                Mat4::from_translation(self.pos_to_vec3(death.at, cell_size, 0.02)) * face_tile(death.at.face, cell_size * 0.5),
+            );
+            storm_colors.push(Srgba::new(255, 40, 40, (110.0 + 140.0 * flash) as u8));
+        }
+        let upload_start = profiler::now();
+        self.uploads.set("storm_instances", &mut self.storm_instances.geometry, &Instances {
+            transformations: storm_transformations,
+            colors: Some(storm_colors),
+            ..Default::default()
+        });
+        upload_ms += profiler::now() - upload_start;
+
+        // Update Food Hint
+        // Small tiles on the way to the food, brightening in a wave that runs
+        // from the head; the food's own cell is left bare.
+        let mut hint_transformations = Vec::new();
+        let mut hint_colors = Vec::new();
+        if self.food_hint && !game.config.mode.competitive() && !game.game_over {
+            let (head, food) = (game.snake.head(), game.food);
+            if !matches!(&self.hint_route, Some((h, f, _)) if *h == head && *f == food) {
+                let cells = game.route(head, food).map(|route| route.cells).unwrap_or_default();
+                self.hint_route = Some((head, food, cells));
+            }
+            if let Some((_, _, cells)) = &self.hint_route {
+                for (i, &pos) in cells[..cells.len().saturating_sub(1)].iter().enumerate() {
+                    let pulse = if self.reduced_motion { 0.5 } else { ((self.time * 4.0 - i as f64 * 0.6).sin() * 0.5 + 0.5) as f32 };
+                    hint_transformations.push(
+                        Mat4::from_translation(self.pos_to_vec3(pos, cell_size, 0.01)) * face_tile(pos.face, cell_size * 0.15),
+                    );
+                    hint_colors.push(Srgba { a: (40.0 + 110.0 * pulse) as u8, ..self.food_color });
+                }
+            }
+        }
+        let upload_start = profiler::now();
+        self.uploads.set("hint_instances", &mut self.hint_instances.geometry, &Instances {
+            transformations: hint_transformations,
+            colors: Some(hint_colors),
+            ..Default::default()
+        });
+        upload_ms += profiler::now() - upload_start;
+
+        // Golden grid flash after a new record, fading back over a second and a half.
+        if let Some(at) = self.celebrated_at {
+            let fade = ((self.time - at) / 1.5).min(1.0) as f32;
+            let gold = Srgba::new_opaque(255, 200, 0);
+            let mix = |from: u8, to: u8| (to as f32 + (from as f32 - to as f32) * fade) as u8;
+            let base = self.grid_emissive;
+            self.grid_mesh.material.emissive = Srgba::new_opaque(mix(base.r, gold.r), mix(base.g, gold.g), mix(base.b, gold.b));
+            if fade >= 1.0 {
+                self.celebrated_at = None;
+            }
+        } else {
+            // Rhythm mode: the grid brightens into each beat, telegraphing
+            // it, and throbs with the music.
+            let glow = self.beat.map_or(0.0, |phase| phase.powi(4) * 0.6).max(self.music_level * 0.8);
+            let mix = |c: u8| (c as f32 + (255.0 - c as f32) * glow) as u8;
+            let base = self.grid_emissive;
+            self.grid_mesh.material.emissive = Srgba::new_opaque(mix(base.r), mix(base.g), mix(base.b));
+        }
+
+        if self.season == Some(Season::Winter) {
+            self.snow();
+        }
+
+        // Update Particles
+        let upload_start = profiler::now();
+        let particle_instances = self.particles.update(self.time);
+        self.uploads.set("particle_system", &mut self.particle_system.geometry, particle_instances);
+        upload_ms += profiler::now() - upload_start;
+        self.upload_ms = upload_ms;
 
         // Render
-        let ambient = AmbientLight::new(&self.context, 0.3, Srgba::WHITE);
-        let directional = DirectionalLight::new(&self.context, 2.0, Srgba::WHITE, &vec3(1.0, 1.0, 1.0));
+        // A blackout leaves little more than the glow around the food.
+        let dim = if game.blackout() { 0.1 } else { 1.0 };
+        let ambient = AmbientLight::new(&self.context, 0.3 * dim, Srgba::WHITE);
+        let directional = DirectionalLight::new(&self.context, 2.0 * dim, Srgba::WHITE, &vec3(1.0, 1.0, 1.0));
 
         let point_light_color = if game.is_prize { Srgba::new_opaque(255, 215, 0) } else { Srgba::new_opaque(255, 50, 50) };
         let point_light = PointLight::new(
@@ -348,28 +1540,180 @@ impl GameRenderer {
         let lights: Vec<&dyn Light> = vec![&ambient, &directional, &point_light];
         let lights = lights.as_slice();
 
-        // Clear
-        target.clear(ClearState::color_and_depth(0.1, 0.1, 0.1, 1.0, 1.0)); // Dark grey
-
         // Render objects
-        let mut objects: Vec<&dyn Object> = vec![&self.board_instances, &self.grid_instances, &self.snake_instances, &self.particle_system];
-        if game.is_prize {
-            objects.push(&self.prize_mesh);
+        let mut objects: Vec<&dyn Object> = vec![&*self.board, &self.grid_mesh, &self.label_mesh, &self.snake_instances, &self.partner_instances, &self.hazard_instances, &self.wall_instances, &self.portal_instances, &self.ring_instances, &self.pickup_instances, &self.feast_instances, &self.turret_instances, &self.boss_instances, &self.weak_point_instances, &self.shot_instances, &self.storm_instances, &self.hint_instances, &self.particle_system];
+        if reflect {
+            objects.push(&self.reflection_instances);
+        }
+        if self.high_contrast {
+            objects.push(&self.outline_instances);
+        }
+        if game.rival.is_some() {
+            objects.push(&self.rival_mesh);
+        }
+        if self.cursor.is_some() {
+            objects.push(&self.cursor_mesh);
+        }
+        if tongue_out {
+            objects.push(&self.tongue_mesh);
+        }
+        objects.extend(self.head_model.iter().flat_map(Placed::objects));
+        let food_model = if game.is_prize { &self.prize_model } else { &self.food_model };
+        objects.extend(food_model.iter().flat_map(Placed::objects));
+
+        let (r, g, b) = self.clear_color;
+        if let Some(background) = &mut self.background {
+            background.time = motion as f32;
+        }
+        let draw = |target: &RenderTarget, camera: &Camera| {
+            target.clear(ClearState::color_and_depth(r, g, b, 1.0, 1.0));
+            if let Some(background) = &self.background {
+                target.apply_screen_effect(background, camera, &[], None, None);
+            }
+            target.render(camera, objects.as_slice(), lights);
+        };
+        if !self.ambient_occlusion && self.render_scale >= 1.0 {
+            draw(target, &self.camera);
         } else {
-            objects.push(&self.food_mesh);
+            // Draw at the reduced size offscreen, then stretch it over the screen.
+            let full = self.camera.viewport();
+            let width = ((full.width as f32 * self.render_scale).round() as u32).max(1);
+            let height = ((full.height as f32 * self.render_scale).round() as u32).max(1);
+            self.camera.set_viewport(Viewport::new_at_origo(width, height));
+            let context = &self.context;
+            self.offscreen
+                .get_or_insert_with(|| Offscreen::new(context, width, height))
+                .draw(context, width, height, |t| draw(t, &self.camera));
+            self.camera.set_viewport(full);
+            if let Some(offscreen) = &self.offscreen {
+                offscreen.composite(target, &self.camera, self.ambient_occlusion);
+            }
+        }
+
+        // The far side: the same scene from straight through the cube, at
+        // full resolution and without ambient occlusion since it's small.
+        if self.far_side_view && !self.showcase {
+            let full = self.camera.viewport();
+            let size = (full.width.min(full.height) / 4).max(1);
+            let margin = size / 16;
+            let inset = Viewport {
+                x: (full.width - size - margin) as i32,
+                y: (full.height - size - margin) as i32,
+                width: size,
+                height: size,
+            };
+            let camera = Camera::new_perspective(
+                inset,
+                -*self.camera.position(),
+                vec3(0.0, 0.0, 0.0),
+                *self.camera.up(),
+                degrees(45.0),
+                0.1,
+                100.0,
+            );
+            target
+                .clear_partially(inset.into(), ClearState::color_and_depth(r, g, b, 1.0, 1.0))
+                .render_partially(inset.into(), &camera, objects.as_slice(), lights);
         }
+    }
 
-        target.render(&self.camera, objects.as_slice(), lights);
+    // Reads back what was just rendered to `target`, top row first.
+    // Must run in the same frame as `render`, before the browser composites.
+    pub fn read_pixels(&self, target: &RenderTarget) -> (Vec<[u8; 4]>, u32, u32) {
+        let viewport = self.camera.viewport();
+        (target.read_color::<[u8; 4]>(), viewport.width, viewport.height)
     }
 
     pub fn spawn_particles(&mut self, pos: Position, is_prize: bool) {
+        let color = if is_prize { Srgba::new_opaque(255, 215, 0) } else { self.food_color };
+        self.spawn_burst(pos, color, 1);
+    }
+
+    // Plays a clip on the head model, if it has one for the cue.
+    pub fn cue_head(&mut self, cue: Cue) {
+        if let (Some(model), false) = (&mut self.head_model, self.reduced_motion) {
+            model.cue(cue, self.time);
+        }
+    }
+
+    pub fn spawn_crash(&mut self, pos: Position) {
+        self.spawn_burst(pos, RIVAL_COLOR, 2);
+    }
+
+    pub fn spawn_crumbs(&mut self, pos: Position) {
+        self.spawn_burst(pos, FEAST_COLOR, 1);
+    }
+
+    // The sparks trail, from the cell the tail just left.
+    pub fn spawn_trail(&mut self, pos: Position) {
+        self.spawn_burst(pos, self.snake_color, 1);
+    }
+
+    // A denser cyan burst where the shield broke.
+    pub fn spawn_shatter(&mut self, pos: Position) {
+        self.spawn_burst(pos, SHIELD_COLOR, 2);
+    }
+
+    // New record: confetti from the middle of every face and a golden flash
+    // across the grid.
+    pub fn celebrate(&mut self) {
+        let middle = self.grid_size / 2;
+        for (i, face) in Face::ALL.into_iter().enumerate() {
+            for (j, (r, g, b)) in [(255, 60, 60), (255, 215, 0), (60, 200, 255), (120, 255, 80)].into_iter().enumerate() {
+                // Spread the colors over neighbouring cells.
+                let pos = Position { face, u: middle - 1 + (i + j) as i32 % 3, v: middle - 1 + j as i32 % 3 };
+                self.spawn_burst(pos, Srgba::new_opaque(r, g, b), 1);
+            }
+        }
+        self.celebrated_at = Some(self.time);
+    }
+
+    // All-faces bonus: one burst per rainbow color.
+    pub fn spawn_rainbow(&mut self, pos: Position) {
+        for (r, g, b) in [(255, 0, 0), (255, 140, 0), (255, 235, 0), (0, 220, 60), (0, 120, 255), (150, 60, 255)] {
+            self.spawn_burst(pos, Srgba::new_opaque(r, g, b), 1);
+        }
+    }
+
+    // A few flakes a frame, drifting down past the cube.
+    fn snow(&mut self) {
+        if self.reduced_motion {
+            return;
+        }
+        let flakes = match self.graphics {
+            GraphicsPreset::Low => 0,
+            GraphicsPreset::Medium => 1,
+            GraphicsPreset::High => 2,
+        };
+        for _ in 0..flakes {
+            let mut rng_buf = [0u8; 2];
+            getrandom::getrandom(&mut rng_buf).unwrap_or(());
+            let x = (rng_buf[0] as f32 / 255.0) * 4.0 - 2.0;
+            let z = (rng_buf[1] as f32 / 255.0) * 4.0 - 2.0;
+            self.particles.spawn(Particle {
+                start_pos: vec3(x, 2.0, z),
+                velocity: vec3(0.1, -2.5, 0.0),
+                spawn_time: self.time,
+                color: Srgba::WHITE,
+            });
+        }
+    }
+
+    fn spawn_burst(&mut self, pos: Position, color: Srgba, multiplier: usize) {
+        if self.reduced_motion {
+            return;
+        }
         let cell_size = 2.0 / self.grid_size as f32;
         let offset = 0.05;
         let center = self.pos_to_vec3(pos, cell_size, offset);
 
-        let color = if is_prize { Srgba::new_opaque(255, 215, 0) } else { Srgba::new_opaque(200, 50, 50) };
+        let count = multiplier * match self.graphics {
+            GraphicsPreset::Low => 4,
+            GraphicsPreset::Medium => 8,
+            GraphicsPreset::High => 10,
+        };
 
-        for _ in 0..10 {
+        for _ in 0..count {
              // Simple random velocity
              let mut rng_buf = [0u8; 3];
              getrandom::getrandom(&mut rng_buf).unwrap_or(());
@@ -378,7 +1722,7 @@ impl GameRenderer {
              let rz = (rng_buf[2] as f32 / 255.0) - 0.5;
              let velocity = vec3(rx, ry, rz).normalize() * 1.0; // Explosion speed
 
-             self.particles.push(Particle {
+             self.particles.spawn(Particle {
                  start_pos: center,
                  velocity,
                  spawn_time: self.time,
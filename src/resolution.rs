@@ -0,0 +1,87 @@
+// Adaptive render resolution. Frame times are averaged, and the scale drops a
+// step when frames run long and creeps back up after a stretch of smooth
+// frames. With the browser capping frames at the display rate there's no
+// headroom signal, so going up is a guess: each raise that has to be undone
+// doubles the wait before the next try, which keeps the scale from
+// oscillating.
+
+const TARGET_MS: f64 = 1000.0 / 60.0;
+// Weak devices that can't hold 60 even at the lowest scale settle for 30.
+const LOW_END_MS: f64 = 1000.0 / 30.0;
+const MIN_SCALE: f32 = 0.5;
+const STEP: f32 = 0.1;
+// Frames to let the average settle after a change before judging again.
+const SETTLE_FRAMES: u32 = 90;
+// Smooth frames before trying a higher scale, and the most the backoff
+// stretches that to.
+const RAISE_FRAMES: u32 = 300;
+const MAX_RAISE_FRAMES: u32 = 3600;
+// Longer frames are stalls (tab switched, GC) rather than load.
+const STALL_MS: f64 = 250.0;
+
+pub struct DynamicResolution {
+    scale: f32,
+    average: f64,
+    target_ms: f64,
+    frames_since_change: u32,
+    raise_frames: u32,
+    // The last change was a raise, so a quick drop means it was too far.
+    just_raised: bool,
+}
+
+impl Default for DynamicResolution {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            average: 0.0,
+            target_ms: TARGET_MS,
+            frames_since_change: 0,
+            raise_frames: RAISE_FRAMES,
+            just_raised: false,
+        }
+    }
+}
+
+impl DynamicResolution {
+    // Fraction of the viewport to render at.
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    // Feeds in one frame time; returns true when the scale changed.
+    pub fn record(&mut self, frame_ms: f64) -> bool {
+        if frame_ms <= 0.0 || frame_ms > STALL_MS {
+            return false;
+        }
+        self.average = if self.average == 0.0 { frame_ms } else { self.average * 0.95 + frame_ms * 0.05 };
+        self.frames_since_change = self.frames_since_change.saturating_add(1);
+        if self.frames_since_change < SETTLE_FRAMES {
+            return false;
+        }
+
+        if self.average > self.target_ms * 1.2 {
+            if self.scale <= MIN_SCALE {
+                self.target_ms = LOW_END_MS;
+                self.frames_since_change = 0;
+                return false;
+            }
+            if self.just_raised && self.frames_since_change < SETTLE_FRAMES * 2 {
+                self.raise_frames = (self.raise_frames * 2).min(MAX_RAISE_FRAMES);
+            }
+            self.set_scale(self.scale - STEP, false);
+            return true;
+        }
+        if self.scale < 1.0 && self.frames_since_change >= self.raise_frames && self.average < self.target_ms * 1.05 {
+            self.set_scale(self.scale + STEP, true);
+            return true;
+        }
+        false
+    }
+
+    fn set_scale(&mut self, scale: f32, raised: bool) {
+        // Rounded so repeated steps don't drift off the tenths.
+        self.scale = ((scale * 10.0).round() / 10.0).clamp(MIN_SCALE, 1.0);
+        self.frames_since_change = 0;
+        self.just_raised = raised;
+    }
+}
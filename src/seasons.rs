@@ -0,0 +1,84 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+use crate::app::Controls;
+use crate::assets;
+use crate::loader;
+
+// Themed content for part of the year: its own palette and bass line, plus
+// pumpkin food at Halloween and snow in winter.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Season {
+    Halloween,
+    Winter,
+}
+
+impl Season {
+    pub fn from_name(name: &str) -> Option<Season> {
+        serde_json::from_value(serde_json::Value::String(name.to_string())).ok()
+    }
+
+    // Key in assets/themes.json; it stands in for the player's theme.
+    pub fn theme_key(self) -> &'static str {
+        match self {
+            Season::Halloween => "halloween",
+            Season::Winter => "winter",
+        }
+    }
+}
+
+// One entry of a calendar: `from` and `to` are "MM-DD", both included, and
+// a window may run over new year.
+#[derive(Deserialize, Clone, Debug)]
+pub struct SeasonWindow {
+    pub season: Season,
+    pub from: String,
+    pub to: String,
+}
+
+fn month_day(text: &str) -> Option<(u32, u32)> {
+    let (month, day) = text.split_once('-')?;
+    Some((month.parse().ok()?, day.parse().ok()?))
+}
+
+// The season `calendar` has on a date, the first listed winning overlaps.
+pub fn on(calendar: &[SeasonWindow], month: u32, day: u32) -> Option<Season> {
+    let today = (month, day);
+    calendar.iter().find_map(|window| {
+        let from = month_day(&window.from)?;
+        let to = month_day(&window.to)?;
+        let inside = if from <= to { from <= today && today <= to } else { today >= from || today <= to };
+        inside.then_some(window.season)
+    })
+}
+
+fn today(calendar: &[SeasonWindow]) -> Option<Season> {
+    let now = js_sys::Date::new_0();
+    on(calendar, now.get_month() + 1, now.get_date())
+}
+
+// The season from the calendar built into the game.
+pub fn local() -> Option<Season> {
+    let calendar = assets::json::<Vec<SeasonWindow>>("seasons.json")?;
+    today(&calendar)
+}
+
+// Replaces the built-in calendar with one from the host's server, same
+// format as assets/seasons.json. Until it arrives, or if it never does, the
+// built-in one stands.
+pub fn fetch(url: &str, controls: Rc<RefCell<Controls>>) -> Result<(), JsValue> {
+    loader::fetch_text(url, "Season calendar", move |text| {
+        let calendar: Vec<SeasonWindow> = match serde_json::from_str(&text) {
+            Ok(calendar) => calendar,
+            Err(_) => {
+                log::warn!("Season calendar from the server didn't parse; keeping the built-in one");
+                return;
+            }
+        };
+        controls.borrow_mut().season = today(&calendar);
+    })
+}
@@ -0,0 +1,481 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::app::Controls;
+use crate::dom::Dom;
+use crate::error;
+use crate::i18n::{self, Language};
+use crate::unlocks;
+
+const STORAGE_KEY: &str = "snake3d_settings";
+
+pub const MIN_GRID_SIZE: i32 = 4;
+pub const MAX_GRID_SIZE: i32 = 20;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ControlScheme {
+    Both,
+    Arrows,
+    Wasd,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphicsPreset {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Projection {
+    Perspective,
+    Orthographic,
+}
+
+// How much is read out to screen readers (see `narrator.rs`).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Narration {
+    Off,
+    Brief,
+    Full,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    Neon,
+    Retro,
+}
+
+impl Theme {
+    // Key in assets/themes.json.
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Neon => "neon",
+            Theme::Retro => "retro",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Theme> {
+        [Theme::Neon, Theme::Retro].into_iter().find(|theme| theme.name() == name)
+    }
+}
+
+// Snake colors over the theme's.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Skin {
+    Classic,
+    Gold,
+    Ghost,
+}
+
+// What the snake leaves behind as it moves.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Trail {
+    None,
+    Sparks,
+}
+
+// The voice of the sound effects.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SoundPack {
+    Classic,
+    Chiptune,
+}
+
+// Persisted player preferences. Missing fields fall back to defaults so
+// older saves keep loading as new settings are added.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub master_volume: f32,
+    pub sfx_volume: f32,
+    // Spoken "Level up!", "Combo x5" and similar cues.
+    pub announcer: bool,
+    pub narration: Narration,
+    pub control_scheme: ControlScheme,
+    pub graphics: GraphicsPreset,
+    // Screen-space ambient occlusion; off by default as it costs a full
+    // extra pass.
+    pub ambient_occlusion: bool,
+    pub projection: Projection,
+    // A small view of the cube's far side in the corner.
+    pub far_side_view: bool,
+    // Breadcrumbs along the shortest way to the food. An assist, so it
+    // never shows in competitive modes.
+    pub food_hint: bool,
+    // Adaptive difficulty (see `snake3d_core::director`); casual modes only.
+    pub director: bool,
+    // Opaque board, outlined snake and food, thicker grid and solid UI
+    // panels, for low-vision players.
+    pub high_contrast: bool,
+    // `None` follows the system's `prefers-reduced-motion`.
+    pub reduced_motion: Option<bool>,
+    pub theme: Theme,
+    // Skin, trail and sound pack are unlocked by playing (see `unlocks.rs`).
+    pub skin: Skin,
+    pub trail: Trail,
+    pub sound_pack: SoundPack,
+    pub grid_size: i32,
+    // `None` follows the browser language.
+    pub language: Option<Language>,
+    pub fullscreen: bool,
+    // Keeps the last seconds of play for saving as a GIF (see `capture.rs`).
+    pub record_clips: bool,
+    // Sends anonymous run statistics to the host's analytics endpoint.
+    pub analytics: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            sfx_volume: 1.0,
+            announcer: true,
+            narration: Narration::Brief,
+            control_scheme: ControlScheme::Both,
+            graphics: GraphicsPreset::High,
+            ambient_occlusion: false,
+            projection: Projection::Perspective,
+            far_side_view: false,
+            food_hint: false,
+            director: false,
+            high_contrast: false,
+            reduced_motion: None,
+            theme: Theme::Neon,
+            skin: Skin::Classic,
+            trail: Trail::None,
+            sound_pack: SoundPack::Classic,
+            grid_size: 10,
+            language: None,
+            fullscreen: false,
+            record_clips: false,
+            analytics: false,
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Settings {
+        let storage = match error::storage() {
+            Ok(storage) => storage,
+            Err(_) => return Settings::default(),
+        };
+        match storage.get_item(STORAGE_KEY) {
+            Ok(Some(json)) => serde_json::from_str::<Settings>(&json)
+                .map(Settings::sanitized)
+                .unwrap_or_default(),
+            _ => Settings::default(),
+        }
+    }
+
+    pub fn save(&self) {
+        if let Ok(storage) = error::storage() {
+            if let Ok(json) = serde_json::to_string(self) {
+                let _ = storage.set_item(STORAGE_KEY, &json);
+            }
+        }
+    }
+
+    pub fn sanitized(mut self) -> Settings {
+        self.master_volume = self.master_volume.clamp(0.0, 1.0);
+        self.sfx_volume = self.sfx_volume.clamp(0.0, 1.0);
+        self.grid_size = self.grid_size.clamp(MIN_GRID_SIZE, MAX_GRID_SIZE);
+        self
+    }
+
+    pub fn effective_sfx_volume(&self) -> f32 {
+        self.master_volume * self.sfx_volume
+    }
+
+    pub fn effective_language(&self) -> Language {
+        self.language.unwrap_or_else(Language::detect)
+    }
+
+    pub fn effective_reduced_motion(&self) -> bool {
+        self.reduced_motion.unwrap_or_else(prefers_reduced_motion)
+    }
+
+    // The page side of high contrast: a body class the stylesheet keys off.
+    pub fn apply_to_document(&self) {
+        if let Some(body) = error::document().ok().and_then(|d| d.body()) {
+            let _ = body.class_list().toggle_with_force("high-contrast", self.high_contrast);
+        }
+    }
+}
+
+fn prefers_reduced_motion() -> bool {
+    error::window()
+        .ok()
+        .and_then(|w| w.match_media("(prefers-reduced-motion: reduce)").ok().flatten())
+        .is_some_and(|query| query.matches())
+}
+
+// Settings enums are shown in <select> elements using their serde names.
+fn to_name<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(s)) => s,
+        _ => String::new(),
+    }
+}
+
+fn from_name<T: DeserializeOwned>(name: &str) -> Option<T> {
+    serde_json::from_value(serde_json::Value::String(name.to_string())).ok()
+}
+
+fn input(dom: &Dom, id: &str) -> Option<web_sys::HtmlInputElement> {
+    dom.get(id)?.dyn_into().ok()
+}
+
+fn select(dom: &Dom, id: &str) -> Option<web_sys::HtmlSelectElement> {
+    dom.get(id)?.dyn_into().ok()
+}
+
+fn fill_panel(dom: &Dom, settings: &Settings) {
+    if let Some(e) = input(dom, "settings-master-volume") {
+        e.set_value(&((settings.master_volume * 100.0).round() as i32).to_string());
+    }
+    if let Some(e) = input(dom, "settings-sfx-volume") {
+        e.set_value(&((settings.sfx_volume * 100.0).round() as i32).to_string());
+    }
+    if let Some(e) = input(dom, "settings-announcer") {
+        e.set_checked(settings.announcer);
+    }
+    if let Some(e) = select(dom, "settings-narration") {
+        e.set_value(&to_name(&settings.narration));
+    }
+    if let Some(e) = select(dom, "settings-controls") {
+        e.set_value(&to_name(&settings.control_scheme));
+    }
+    if let Some(e) = select(dom, "settings-graphics") {
+        e.set_value(&to_name(&settings.graphics));
+    }
+    if let Some(e) = input(dom, "settings-ambient-occlusion") {
+        e.set_checked(settings.ambient_occlusion);
+    }
+    if let Some(e) = select(dom, "settings-projection") {
+        e.set_value(&to_name(&settings.projection));
+    }
+    if let Some(e) = input(dom, "settings-far-side-view") {
+        e.set_checked(settings.far_side_view);
+    }
+    if let Some(e) = input(dom, "settings-food-hint") {
+        e.set_checked(settings.food_hint);
+    }
+    if let Some(e) = input(dom, "settings-director") {
+        e.set_checked(settings.director);
+    }
+    if let Some(e) = input(dom, "settings-record-clips") {
+        e.set_checked(settings.record_clips);
+    }
+    if let Some(e) = input(dom, "settings-high-contrast") {
+        e.set_checked(settings.high_contrast);
+    }
+    if let Some(e) = select(dom, "settings-reduced-motion") {
+        e.set_value(match settings.reduced_motion {
+            None => "auto",
+            Some(true) => "on",
+            Some(false) => "off",
+        });
+    }
+    if let Some(e) = select(dom, "settings-theme") {
+        e.set_value(&to_name(&settings.theme));
+    }
+    if let Some(e) = select(dom, "settings-skin") {
+        e.set_value(&to_name(&settings.skin));
+    }
+    if let Some(e) = select(dom, "settings-trail") {
+        e.set_value(&to_name(&settings.trail));
+    }
+    if let Some(e) = select(dom, "settings-sound-pack") {
+        e.set_value(&to_name(&settings.sound_pack));
+    }
+    if let Some(e) = input(dom, "settings-grid-size") {
+        e.set_value(&settings.grid_size.to_string());
+    }
+    if let Some(e) = input(dom, "settings-analytics") {
+        e.set_checked(settings.analytics);
+    }
+    if let Some(e) = select(dom, "settings-language") {
+        e.set_value(&settings.language.map(|l| to_name(&l)).unwrap_or_else(|| "auto".to_string()));
+    }
+}
+
+fn read_panel(dom: &Dom, current: &Settings) -> Settings {
+    let mut settings = current.clone();
+    if let Some(v) = input(dom, "settings-master-volume").and_then(|e| e.value().parse::<f32>().ok()) {
+        settings.master_volume = v / 100.0;
+    }
+    if let Some(v) = input(dom, "settings-sfx-volume").and_then(|e| e.value().parse::<f32>().ok()) {
+        settings.sfx_volume = v / 100.0;
+    }
+    if let Some(e) = input(dom, "settings-announcer") {
+        settings.announcer = e.checked();
+    }
+    if let Some(v) = select(dom, "settings-narration").and_then(|e| from_name(&e.value())) {
+        settings.narration = v;
+    }
+    if let Some(v) = select(dom, "settings-controls").and_then(|e| from_name(&e.value())) {
+        settings.control_scheme = v;
+    }
+    if let Some(v) = select(dom, "settings-graphics").and_then(|e| from_name(&e.value())) {
+        settings.graphics = v;
+    }
+    if let Some(e) = input(dom, "settings-ambient-occlusion") {
+        settings.ambient_occlusion = e.checked();
+    }
+    if let Some(v) = select(dom, "settings-projection").and_then(|e| from_name(&e.value())) {
+        settings.projection = v;
+    }
+    if let Some(e) = input(dom, "settings-far-side-view") {
+        settings.far_side_view = e.checked();
+    }
+    if let Some(e) = input(dom, "settings-food-hint") {
+        settings.food_hint = e.checked();
+    }
+    if let Some(e) = input(dom, "settings-director") {
+        settings.director = e.checked();
+    }
+    if let Some(e) = input(dom, "settings-record-clips") {
+        settings.record_clips = e.checked();
+    }
+    if let Some(e) = input(dom, "settings-high-contrast") {
+        settings.high_contrast = e.checked();
+    }
+    if let Some(e) = select(dom, "settings-reduced-motion") {
+        settings.reduced_motion = match e.value().as_str() {
+            "on" => Some(true),
+            "off" => Some(false),
+            _ => None,
+        };
+    }
+    if let Some(v) = select(dom, "settings-theme").and_then(|e| from_name(&e.value())) {
+        settings.theme = v;
+    }
+    if let Some(v) = select(dom, "settings-skin").and_then(|e| from_name(&e.value())) {
+        settings.skin = v;
+    }
+    if let Some(v) = select(dom, "settings-trail").and_then(|e| from_name(&e.value())) {
+        settings.trail = v;
+    }
+    if let Some(v) = select(dom, "settings-sound-pack").and_then(|e| from_name(&e.value())) {
+        settings.sound_pack = v;
+    }
+    if let Some(v) = input(dom, "settings-grid-size").and_then(|e| e.value().parse::<i32>().ok()) {
+        settings.grid_size = v;
+    }
+    if let Some(e) = select(dom, "settings-language") {
+        settings.language = from_name(&e.value());
+    }
+    if let Some(e) = input(dom, "settings-analytics") {
+        settings.analytics = e.checked();
+    }
+    settings.sanitized()
+}
+
+// Saves changed settings and hands them to the render loop, which applies
+// them live. Language only touches the page, so it's applied right here.
+pub fn commit(controls: &mut Controls, settings: Settings) {
+    // Locked cosmetics stay as they were.
+    let settings = unlocks::gate(settings, &controls.settings);
+    settings.save();
+    if settings.language != controls.settings.language {
+        i18n::set_language(settings.effective_language());
+        if let Ok(document) = error::document() {
+            i18n::apply_to_document(&document);
+        }
+        crate::leaderboard::update_leaderboard_ui(&controls.dom);
+    }
+    if !settings.analytics {
+        controls.analytics.clear();
+    }
+    controls.settings = settings;
+    controls.settings_changed = true;
+}
+
+// Wires the optional settings panel. Every change is saved immediately and
+// handed to the render loop through `Controls`, which applies it live.
+pub fn attach_settings_panel(dom: &Dom, controls: &Rc<RefCell<Controls>>) -> Result<(), JsValue> {
+    let modal = match dom.get("settings-modal") {
+        Some(modal) => modal,
+        None => return Ok(()),
+    };
+
+    fill_panel(dom, &controls.borrow().settings);
+
+    if let Some(btn) = dom.get("settings-btn") {
+        let modal = modal.clone();
+        let controls = controls.clone();
+        let dom = dom.clone();
+        let closure = Closure::wrap(Box::new(move || {
+            fill_panel(&dom, &controls.borrow().settings);
+            modal.class_list().remove_1("hidden").unwrap_or(());
+        }) as Box<dyn FnMut()>);
+        btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+
+    if let Some(btn) = dom.get("close-settings-btn") {
+        let closure = Closure::wrap(Box::new(move || {
+            modal.class_list().add_1("hidden").unwrap_or(());
+        }) as Box<dyn FnMut()>);
+        btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+
+    let on_change = {
+        let controls = controls.clone();
+        let dom = dom.clone();
+        Closure::wrap(Box::new(move || {
+            let mut controls = controls.borrow_mut();
+            let settings = read_panel(&dom, &controls.settings);
+            if settings != controls.settings {
+                commit(&mut controls, settings);
+                // Puts back any locked choice.
+                fill_panel(&dom, &controls.settings);
+            }
+        }) as Box<dyn FnMut()>)
+    };
+    for id in [
+        "settings-master-volume",
+        "settings-sfx-volume",
+        "settings-announcer",
+        "settings-narration",
+        "settings-controls",
+        "settings-graphics",
+        "settings-ambient-occlusion",
+        "settings-projection",
+        "settings-far-side-view",
+        "settings-food-hint",
+        "settings-director",
+        "settings-record-clips",
+        "settings-high-contrast",
+        "settings-reduced-motion",
+        "settings-theme",
+        "settings-skin",
+        "settings-trail",
+        "settings-sound-pack",
+        "settings-grid-size",
+        "settings-language",
+        "settings-analytics",
+    ] {
+        if let Some(e) = dom.get(id) {
+            e.add_event_listener_with_callback("change", on_change.as_ref().unchecked_ref())?;
+        }
+    }
+    on_change.forget();
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::{Clamped, JsCast};
+use js_sys::{Array, Function, Object, Reflect};
+
+use crate::error;
+use crate::i18n::t;
+
+const CARD_NAME: &str = "snake3d.png";
+const CLIP_NAME: &str = "snake3d.gif";
+
+// Builds a share card from a captured frame (top row first, RGBA) with a
+// score banner along the bottom, then shares or downloads it as a PNG.
+pub fn share_card(pixels: Vec<[u8; 4]>, width: u32, height: u32, score: u32) -> Result<(), JsValue> {
+    let document = error::document()?;
+    let canvas = document
+        .create_element("canvas")?
+        .dyn_into::<web_sys::HtmlCanvasElement>()?;
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let ctx = canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("2d canvas context unavailable"))?
+        .dyn_into::<web_sys::CanvasRenderingContext2d>()?;
+
+    let bytes: Vec<u8> = pixels.into_iter().flatten().collect();
+    let image = web_sys::ImageData::new_with_u8_clamped_array_and_sh(Clamped(&bytes), width, height)?;
+    ctx.put_image_data(&image, 0.0, 0.0)?;
+
+    // Score banner
+    let w = width as f64;
+    let h = height as f64;
+    let banner_height = (h * 0.12).max(48.0);
+    ctx.set_fill_style_str("rgba(0, 0, 0, 0.6)");
+    ctx.fill_rect(0.0, h - banner_height, w, banner_height);
+    ctx.set_fill_style_str("#ffeb3b");
+    ctx.set_font(&format!("bold {}px 'Segoe UI', sans-serif", (banner_height * 0.45).round()));
+    ctx.set_text_align("center");
+    ctx.set_text_baseline("middle");
+    let banner = format!("3D Snake · {}", t("final_score").replace("{score}", &score.to_string()));
+    ctx.fill_text(&banner, w / 2.0, h - banner_height / 2.0)?;
+
+    let text = t("share_text").replace("{score}", &score.to_string());
+    let on_blob = Closure::once(move |blob: Option<web_sys::Blob>| {
+        if let Some(blob) = blob {
+            share_or_download(&blob, CARD_NAME, "image/png", &text);
+        }
+    });
+    canvas.to_blob(on_blob.as_ref().unchecked_ref())?;
+    on_blob.forget();
+
+    Ok(())
+}
+
+// Shares or downloads an animated GIF of the run's last moments (see
+// `capture.rs`).
+pub fn share_clip(gif: &[u8], score: u32) -> Result<(), JsValue> {
+    let bytes = js_sys::Uint8Array::from(gif);
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type("image/gif");
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&Array::of1(&bytes), &options)?;
+    share_or_download(&blob, CLIP_NAME, "image/gif", &t("share_text").replace("{score}", &score.to_string()));
+    Ok(())
+}
+
+fn share_or_download(blob: &web_sys::Blob, name: &str, mime: &str, text: &str) {
+    if !web_share(blob, name, mime, text).unwrap_or(false) {
+        download(blob, name).unwrap_or(());
+    }
+}
+
+// Web Share API with files. Looked up dynamically because support varies
+// widely; returns false when the browser can't share images.
+fn web_share(blob: &web_sys::Blob, name: &str, mime: &str, text: &str) -> Result<bool, JsValue> {
+    let navigator = error::window()?.navigator();
+    let can_share = match Reflect::get(&navigator, &"canShare".into())?.dyn_into::<Function>() {
+        Ok(f) => f,
+        Err(_) => return Ok(false),
+    };
+    let share = match Reflect::get(&navigator, &"share".into())?.dyn_into::<Function>() {
+        Ok(f) => f,
+        Err(_) => return Ok(false),
+    };
+
+    let options = web_sys::FilePropertyBag::new();
+    options.set_type(mime);
+    let file = web_sys::File::new_with_blob_sequence_and_options(&Array::of1(blob), name, &options)?;
+
+    let data = Object::new();
+    Reflect::set(&data, &"files".into(), &Array::of1(&file))?;
+    Reflect::set(&data, &"title".into(), &"3D Snake".into())?;
+    Reflect::set(&data, &"text".into(), &text.into())?;
+
+    if !can_share.call1(&navigator, &data)?.is_truthy() {
+        return Ok(false);
+    }
+    // Resolves after the share sheet closes; cancelling is not an error worth surfacing.
+    share.call1(&navigator, &data)?;
+    Ok(true)
+}
+
+fn download(blob: &web_sys::Blob, name: &str) -> Result<(), JsValue> {
+    let document = error::document()?;
+    let url = web_sys::Url::create_object_url_with_blob(blob)?;
+    let link = document
+        .create_element("a")?
+        .dyn_into::<web_sys::HtmlAnchorElement>()?;
+    link.set_href(&url);
+    link.set_download(name);
+    link.click();
+    web_sys::Url::revoke_object_url(&url)?;
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::dom::Dom;
+use crate::error;
+use crate::i18n::t;
+use crate::unlocks::{self, Cosmetic, Requirement, CATALOG};
+
+const WALLET_KEY: &str = "snake3d_wallet";
+// A run pays a coin per this many points, plus one for finishing at all.
+const POINTS_PER_COIN: u32 = 5;
+
+// Coins and bought cosmetics (by `Cosmetic::id`). Kept on this device only;
+// there are no accounts to sync it to.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct Wallet {
+    pub coins: u32,
+    pub owned: Vec<String>,
+}
+
+impl Wallet {
+    pub fn load() -> Wallet {
+        error::storage()
+            .ok()
+            .and_then(|s| s.get_item(WALLET_KEY).ok().flatten())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        if let (Ok(storage), Ok(json)) = (error::storage(), serde_json::to_string(self)) {
+            let _ = storage.set_item(WALLET_KEY, &json);
+        }
+    }
+}
+
+pub fn owns(cosmetic: Cosmetic) -> bool {
+    Wallet::load().owned.iter().any(|id| id == cosmetic.id())
+}
+
+// Coins for a run that scored `score`; nothing for a run that scored nothing.
+pub fn coins_for(score: u32) -> u32 {
+    if score == 0 {
+        0
+    } else {
+        score / POINTS_PER_COIN + 1
+    }
+}
+
+// Pays out a finished run. Returns the coins added.
+pub fn award(score: u32) -> u32 {
+    let coins = coins_for(score);
+    if coins > 0 {
+        let mut wallet = Wallet::load();
+        wallet.coins = wallet.coins.saturating_add(coins);
+        wallet.save();
+    }
+    coins
+}
+
+// What a cosmetic costs, for the ones that can be bought instead of earned.
+// Free ones aren't for sale.
+pub fn price(cosmetic: Cosmetic) -> Option<u32> {
+    let (_, requirement) = CATALOG.into_iter().find(|(c, _)| *c == cosmetic)?;
+    match requirement {
+        Requirement::Free => None,
+        Requirement::LifetimeScore(points) => Some(points / 4),
+        Requirement::Achievement(_) => Some(300),
+    }
+}
+
+// Spends coins on `cosmetic`. Returns false if it's already unlocked, not
+// for sale or too dear.
+pub fn buy(cosmetic: Cosmetic) -> bool {
+    let price = match price(cosmetic) {
+        Some(price) if !unlocks::is_unlocked(cosmetic) => price,
+        _ => return false,
+    };
+    let mut wallet = Wallet::load();
+    if wallet.coins < price {
+        return false;
+    }
+    wallet.coins -= price;
+    wallet.owned.push(cosmetic.id().to_string());
+    wallet.save();
+    true
+}
+
+// Fills `#shop-list` with every cosmetic still locked, each with a buy button
+// (`data-buy` holds its id), and `#coins` with the balance.
+pub fn render(dom: &Dom) -> error::Result<()> {
+    let list = match dom.get("shop-list") {
+        Some(list) => list,
+        None => return Ok(()),
+    };
+    let document = error::document()?;
+    let wallet = Wallet::load();
+    list.set_inner_html("");
+    let for_sale: Vec<(Cosmetic, u32)> = CATALOG
+        .into_iter()
+        .filter(|(cosmetic, _)| !unlocks::is_unlocked(*cosmetic))
+        .filter_map(|(cosmetic, _)| Some((cosmetic, price(cosmetic)?)))
+        .collect();
+    if for_sale.is_empty() {
+        let li = document.create_element("li")?;
+        li.set_text_content(Some(t("shop_sold_out")));
+        list.append_child(&li)?;
+    }
+    for (cosmetic, price) in for_sale {
+        let li = document.create_element("li")?;
+        let name = document.create_element("span")?;
+        name.set_text_content(Some(t(cosmetic.title_key())));
+        let buy = document.create_element("button")?;
+        buy.set_class_name("ui-btn");
+        buy.set_attribute("data-buy", cosmetic.id())?;
+        buy.set_text_content(Some(&format!("🪙 {}", price)));
+        if wallet.coins < price {
+            buy.set_attribute("disabled", "")?;
+        }
+        li.append_child(&name)?;
+        li.append_child(&buy)?;
+        list.append_child(&li)?;
+    }
+    if let Some(coins) = dom.get("coins") {
+        coins.set_text_content(Some(&wallet.coins.to_string()));
+    }
+    Ok(())
+}
+
+fn rerender(dom: &Dom) {
+    if let Err(e) = render(dom) {
+        log::error!("Failed to render shop: {}", e);
+    }
+}
+
+// Opens the shop from `#shop-btn` and handles its buy buttons, when the page
+// has them.
+pub fn attach(dom: &Dom) -> Result<(), JsValue> {
+    for (id, open) in [("shop-btn", true), ("close-shop-btn", false)] {
+        if let Some(btn) = dom.get(id) {
+            let dom = dom.clone();
+            let closure = Closure::wrap(Box::new(move || {
+                if open {
+                    rerender(&dom);
+                }
+                if let Some(modal) = dom.get("shop-modal") {
+                    modal.class_list().toggle_with_force("hidden", !open).unwrap_or(false);
+                }
+            }) as Box<dyn FnMut()>);
+            btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+            closure.forget();
+        }
+    }
+
+    if let Some(list) = dom.get("shop-list") {
+        let dom = dom.clone();
+        let closure = Closure::wrap(Box::new(move |e: web_sys::Event| {
+            let button = e.target().and_then(|t| t.dyn_into::<web_sys::Element>().ok());
+            let id = match button.and_then(|b| b.get_attribute("data-buy")) {
+                Some(id) => id,
+                None => return,
+            };
+            let cosmetic = CATALOG.into_iter().map(|(c, _)| c).find(|c| c.id() == id);
+            if let Some(cosmetic) = cosmetic {
+                if buy(cosmetic) {
+                    crate::achievements::show_toast(&dom, &format!("{} {}", t("cosmetic_unlocked"), t(cosmetic.title_key())));
+                }
+            }
+            rerender(&dom);
+        }) as Box<dyn FnMut(_)>);
+        list.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use snake3d_core::game::SPLIT_POINTS;
+use snake3d_core::GameState;
+
+use crate::dom::Dom;
+use crate::error;
+
+// Personal best splits; the last entry is the finishing time.
+const PB_KEY: &str = "snake3d_speedrun_pb";
+
+pub fn load_best() -> Option<Vec<f64>> {
+    let json = error::storage().ok()?.get_item(PB_KEY).ok().flatten()?;
+    serde_json::from_str(&json).ok()
+}
+
+// Stores `splits` if they finish faster than the saved best.
+pub fn save_if_best(splits: &[f64]) -> bool {
+    let time = match splits.last() {
+        Some(time) => *time,
+        None => return false,
+    };
+    let best = load_best().and_then(|b| b.last().copied());
+    if best.is_some_and(|best| best <= time) {
+        return false;
+    }
+    if let (Ok(storage), Ok(json)) = (error::storage(), serde_json::to_string(splits)) {
+        let _ = storage.set_item(PB_KEY, &json);
+    }
+    true
+}
+
+// "1:02.35"
+pub fn format_time(seconds: f64) -> String {
+    let centis = (seconds * 100.0).round() as u64;
+    format!("{}:{:02}.{:02}", centis / 6000, (centis / 100) % 60, centis % 100)
+}
+
+// Timer and split list for speedrun mode. The split list is only rebuilt
+// when a new split comes in.
+pub struct SpeedrunHud {
+    shown_splits: Option<usize>,
+    best: Option<Vec<f64>>,
+}
+
+impl SpeedrunHud {
+    pub fn new() -> Self {
+        Self { shown_splits: None, best: load_best() }
+    }
+
+    // Call at the start of each run so a new best from the last one is used.
+    pub fn reset(&mut self) {
+        self.shown_splits = None;
+        self.best = load_best();
+    }
+
+    pub fn update(&mut self, dom: &Dom, game: &GameState) -> error::Result<()> {
+        let document = error::document()?;
+        let hud = match dom.get("speedrun-hud") {
+            Some(hud) => hud,
+            None => return Ok(()),
+        };
+        let active = game.config.mode.target_score().is_some();
+        hud.class_list().toggle_with_force("hidden", !active)?;
+        if !active {
+            return Ok(());
+        }
+
+        if let Some(timer) = dom.get("speedrun-timer") {
+            timer.set_text_content(Some(&format_time(game.elapsed)));
+        }
+
+        if self.shown_splits == Some(game.splits.len()) {
+            return Ok(());
+        }
+        self.shown_splits = Some(game.splits.len());
+        let list = match dom.get("speedrun-splits") {
+            Some(list) => list,
+            None => return Ok(()),
+        };
+        list.set_inner_html("");
+        for (i, split) in game.splits.iter().enumerate() {
+            let li = document.create_element("li")?;
+            let points = (i as u32 + 1) * SPLIT_POINTS;
+            li.set_text_content(Some(&format!("{:>3}  {}", points, format_time(*split))));
+
+            // Green when ahead of the personal best at this split.
+            if let Some(best) = self.best.as_ref().and_then(|b| b.get(i)) {
+                let delta = split - best;
+                let span = document.create_element("span")?;
+                span.set_text_content(Some(&format!(" {}{:.2}", if delta < 0.0 { "-" } else { "+" }, delta.abs())));
+                span.set_class_name(if delta < 0.0 { "split-ahead" } else { "split-behind" });
+                li.append_child(&span)?;
+            }
+            list.append_child(&li)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use three_d::*;
+
+// Screen-space ambient occlusion. Applied while compositing the offscreen
+// scene (see `offscreen.rs`), it darkens each pixel by how many nearby pixels
+// sit in front of it.
+
+// Sample offsets in pixels before scaling by `RADIUS`; two rings so both
+// tight creases and wider contact shadows show up.
+const KERNEL: [(f32, f32); 12] = [
+    (1.0, 0.0), (0.5, 0.87), (-0.5, 0.87), (-1.0, 0.0), (-0.5, -0.87), (0.5, -0.87),
+    (0.0, 2.0), (1.73, 1.0), (1.73, -1.0), (0.0, -2.0), (-1.73, -1.0), (-1.73, 1.0),
+];
+const RADIUS: f32 = 4.0;
+// How dark a fully occluded pixel gets.
+const STRENGTH: f32 = 0.6;
+
+const SHADER: &str = "
+uniform vec2 texelSize;
+uniform vec2 kernel[12];
+uniform float zNear;
+uniform float zFar;
+uniform float orthographic;
+uniform float strength;
+
+in vec2 uvs;
+layout (location = 0) out vec4 outColor;
+
+float linearDepth(float depth) {
+    if (orthographic > 0.5) {
+        return zNear + depth * (zFar - zNear);
+    }
+    float z = depth * 2.0 - 1.0;
+    return 2.0 * zNear * zFar / (zFar + zNear - z * (zFar - zNear));
+}
+
+void main() {
+    vec4 color = sample_color(uvs);
+    float depth = sample_depth(uvs);
+    float center = linearDepth(depth);
+    float occlusion = 0.0;
+    for (int i = 0; i < 12; i++) {
+        float d = linearDepth(sample_depth(uvs + kernel[i] * texelSize));
+        float diff = center - d;
+        // In front, but not so far that it's just a different object.
+        occlusion += step(0.005, diff) * (1.0 - smoothstep(0.05, 0.3, diff));
+    }
+    outColor = vec4(color.rgb * (1.0 - strength * occlusion / 12.0), color.a);
+    gl_FragDepth = depth;
+}
+";
+
+pub struct SsaoEffect {
+    // One texel of the scene textures, which may be smaller than the screen.
+    pub texel_size: Vec2,
+}
+
+impl Effect for SsaoEffect {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> String {
+        let color = color_texture.expect("ssao needs the scene color");
+        let depth = depth_texture.expect("ssao needs the scene depth");
+        format!("{}\n{}\n{}", color.fragment_shader_source(), depth.fragment_shader_source(), SHADER)
+    }
+
+    fn id(&self, color_texture: Option<ColorTexture>, depth_texture: Option<DepthTexture>) -> u16 {
+        0b1u16 << 14
+            | 0b1u16 << 13
+            | color_texture.map(|t| t.id()).unwrap_or(0)
+            | depth_texture.map(|t| t.id()).unwrap_or(0)
+    }
+
+    fn fragment_attributes(&self) -> FragmentAttributes {
+        FragmentAttributes { uv: true, ..FragmentAttributes::NONE }
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        camera: &Camera,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if let Some(color) = color_texture {
+            color.use_uniforms(program);
+        }
+        if let Some(depth) = depth_texture {
+            depth.use_uniforms(program);
+        }
+        program.use_uniform("texelSize", self.texel_size);
+        let kernel: Vec<Vec2> = KERNEL.iter().map(|(x, y)| vec2(*x, *y) * RADIUS).collect();
+        program.use_uniform_array("kernel", &kernel);
+        program.use_uniform("zNear", camera.z_near());
+        program.use_uniform("zFar", camera.z_far());
+        // Perspective projections put depth into w; orthographic ones leave it at 1.
+        let orthographic = camera.projection().w.w == 1.0;
+        program.use_uniform("orthographic", if orthographic { 1.0f32 } else { 0.0 });
+        program.use_uniform("strength", STRENGTH);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR_AND_DEPTH,
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use snake3d_core::HighScoreStore;
+
+use crate::error;
+
+const HIGH_SCORE_KEY: &str = "snake3d_high_score";
+
+// High score backed by `localStorage`. Without storage (private mode, or
+// disabled by the user) scores just don't survive a reload.
+pub struct LocalStorageStore;
+
+impl HighScoreStore for LocalStorageStore {
+    fn load(&self) -> u32 {
+        error::storage()
+            .ok()
+            .and_then(|s| s.get_item(HIGH_SCORE_KEY).ok().flatten())
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0)
+    }
+
+    fn save(&mut self, high_score: u32) {
+        if let Ok(storage) = error::storage() {
+            let _ = storage.set_item(HIGH_SCORE_KEY, &high_score.to_string());
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use three_d::*;
+
+// Reveal played when the app changes state (menu, game, game over): the
+// screen starts covered in the theme's clear color and either fades or wipes
+// away diagonally.
+
+const SECONDS: f64 = 0.45;
+
+const SHADER: &str = "
+uniform float progress;
+uniform float wipe;
+uniform vec3 color;
+
+in vec2 uvs;
+layout (location = 0) out vec4 outColor;
+
+void main() {
+    float alpha;
+    if (wipe > 0.5) {
+        // A soft edge sweeping from the bottom left corner to the top right.
+        float along = (uvs.x + uvs.y) * 0.5;
+        float edge = progress * 1.1;
+        alpha = smoothstep(edge - 0.1, edge, along);
+    } else {
+        alpha = 1.0 - progress;
+    }
+    outColor = vec4(color, alpha);
+}
+";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TransitionKind {
+    Fade,
+    Wipe,
+}
+
+pub struct Transition {
+    pub kind: TransitionKind,
+    pub color: Vec3,
+    // 0 when fully covered, 1 when done.
+    pub progress: f32,
+}
+
+impl Transition {
+    // How far along a transition started at `start` is at `now` (seconds),
+    // eased out, or None once it's over.
+    pub fn progress(start: f64, now: f64) -> Option<f32> {
+        let t = (now - start) / SECONDS;
+        (t < 1.0).then(|| {
+            let t = t.max(0.0) as f32;
+            1.0 - (1.0 - t) * (1.0 - t)
+        })
+    }
+}
+
+impl Effect for Transition {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        _color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> String {
+        SHADER.to_string()
+    }
+
+    fn id(&self, _color_texture: Option<ColorTexture>, _depth_texture: Option<DepthTexture>) -> u16 {
+        0b1u16 << 14 | 0b1u16 << 10
+    }
+
+    fn fragment_attributes(&self) -> FragmentAttributes {
+        FragmentAttributes { uv: true, ..FragmentAttributes::NONE }
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        _camera: &Camera,
+        _lights: &[&dyn Light],
+        _color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) {
+        program.use_uniform("progress", self.progress);
+        program.use_uniform("wipe", if self.kind == TransitionKind::Wipe { 1.0f32 } else { 0.0 });
+        program.use_uniform("color", self.color);
+    }
+
+    // Over everything, menus included.
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            blend: Blend::TRANSPARENCY,
+            ..Default::default()
+        }
+    }
+}
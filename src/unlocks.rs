@@ -0,0 +1,193 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::achievements::{self, Achievement};
+use crate::dom::Dom;
+use crate::error;
+use crate::i18n::t;
+use crate::settings::{Settings, Skin, SoundPack, Theme, Trail};
+use crate::shop;
+
+// Points scored over every run on this device.
+const LIFETIME_KEY: &str = "snake3d_lifetime_score";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cosmetic {
+    Theme(Theme),
+    Skin(Skin),
+    Trail(Trail),
+    SoundPack(SoundPack),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Requirement {
+    Free,
+    Achievement(Achievement),
+    // Points over all runs.
+    LifetimeScore(u32),
+}
+
+// Every cosmetic and what it takes, in the order the unlocks screen lists
+// them. The settings' defaults are free.
+pub const CATALOG: [(Cosmetic, Requirement); 9] = [
+    (Cosmetic::Theme(Theme::Neon), Requirement::Free),
+    (Cosmetic::Theme(Theme::Retro), Requirement::LifetimeScore(500)),
+    (Cosmetic::Skin(Skin::Classic), Requirement::Free),
+    (Cosmetic::Skin(Skin::Gold), Requirement::LifetimeScore(2000)),
+    (Cosmetic::Skin(Skin::Ghost), Requirement::Achievement(Achievement::GrandTour)),
+    (Cosmetic::Trail(Trail::None), Requirement::Free),
+    (Cosmetic::Trail(Trail::Sparks), Requirement::LifetimeScore(1000)),
+    (Cosmetic::SoundPack(SoundPack::Classic), Requirement::Free),
+    (Cosmetic::SoundPack(SoundPack::Chiptune), Requirement::LifetimeScore(250)),
+];
+
+impl Cosmetic {
+    // Stored in the wallet for bought cosmetics.
+    pub fn id(self) -> &'static str {
+        self.title_key().trim_start_matches("cosmetic_")
+    }
+
+    pub fn title_key(self) -> &'static str {
+        match self {
+            Cosmetic::Theme(Theme::Neon) => "cosmetic_theme_neon",
+            Cosmetic::Theme(Theme::Retro) => "cosmetic_theme_retro",
+            Cosmetic::Skin(Skin::Classic) => "cosmetic_skin_classic",
+            Cosmetic::Skin(Skin::Gold) => "cosmetic_skin_gold",
+            Cosmetic::Skin(Skin::Ghost) => "cosmetic_skin_ghost",
+            Cosmetic::Trail(Trail::None) => "cosmetic_trail_none",
+            Cosmetic::Trail(Trail::Sparks) => "cosmetic_trail_sparks",
+            Cosmetic::SoundPack(SoundPack::Classic) => "cosmetic_sound_classic",
+            Cosmetic::SoundPack(SoundPack::Chiptune) => "cosmetic_sound_chiptune",
+        }
+    }
+}
+
+pub fn lifetime_score() -> u32 {
+    error::storage()
+        .ok()
+        .and_then(|s| s.get_item(LIFETIME_KEY).ok().flatten())
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0)
+}
+
+// Adds a finished run's score. Returns the cosmetics it unlocked.
+pub fn add_to_lifetime(score: u32) -> Vec<Cosmetic> {
+    let storage = match error::storage() {
+        Ok(storage) => storage,
+        Err(_) => return Vec::new(),
+    };
+    let before = lifetime_score();
+    let after = before.saturating_add(score);
+    let _ = storage.set_item(LIFETIME_KEY, &after.to_string());
+    CATALOG
+        .into_iter()
+        .filter(|(cosmetic, requirement)| {
+            matches!(requirement, Requirement::LifetimeScore(points) if before < *points && after >= *points)
+                && !shop::owns(*cosmetic)
+        })
+        .map(|(cosmetic, _)| cosmetic)
+        .collect()
+}
+
+fn requirement(cosmetic: Cosmetic) -> Requirement {
+    CATALOG
+        .iter()
+        .find(|(c, _)| *c == cosmetic)
+        .map(|(_, r)| *r)
+        .unwrap_or(Requirement::Free)
+}
+
+fn met(requirement: Requirement, lifetime: u32) -> bool {
+    match requirement {
+        Requirement::Free => true,
+        Requirement::Achievement(achievement) => achievements::is_unlocked(achievement),
+        Requirement::LifetimeScore(points) => lifetime >= points,
+    }
+}
+
+// Earned, or bought in the shop.
+pub fn is_unlocked(cosmetic: Cosmetic) -> bool {
+    met(requirement(cosmetic), lifetime_score()) || shop::owns(cosmetic)
+}
+
+// `settings` with every locked cosmetic put back to what `current` has.
+pub fn gate(mut settings: Settings, current: &Settings) -> Settings {
+    if !is_unlocked(Cosmetic::Theme(settings.theme)) {
+        settings.theme = current.theme;
+    }
+    if !is_unlocked(Cosmetic::Skin(settings.skin)) {
+        settings.skin = current.skin;
+    }
+    if !is_unlocked(Cosmetic::Trail(settings.trail)) {
+        settings.trail = current.trail;
+    }
+    if !is_unlocked(Cosmetic::SoundPack(settings.sound_pack)) {
+        settings.sound_pack = current.sound_pack;
+    }
+    settings
+}
+
+fn requirement_text(requirement: Requirement, lifetime: u32) -> String {
+    match requirement {
+        Requirement::Free => String::new(),
+        Requirement::Achievement(achievement) => {
+            t("unlock_by_achievement").replace("{achievement}", t(achievement.title_key()))
+        }
+        Requirement::LifetimeScore(points) => t("unlock_by_score")
+            .replace("{points}", &points.to_string())
+            .replace("{progress}", &lifetime.min(points).to_string()),
+    }
+}
+
+// Fills `#unlocks-list` with every cosmetic, locked ones greyed out with
+// what they take.
+pub fn render(dom: &Dom) -> error::Result<()> {
+    let list = match dom.get("unlocks-list") {
+        Some(list) => list,
+        None => return Ok(()),
+    };
+    let document = error::document()?;
+    let lifetime = lifetime_score();
+    list.set_inner_html("");
+    for (cosmetic, requirement) in CATALOG {
+        let li = document.create_element("li")?;
+        let unlocked = met(requirement, lifetime) || shop::owns(cosmetic);
+        li.class_list().toggle_with_force("locked", !unlocked)?;
+
+        let name = document.create_element("span")?;
+        name.set_text_content(Some(&format!("{} {}", if unlocked { "✔" } else { "🔒" }, t(cosmetic.title_key()))));
+        let detail = document.create_element("span")?;
+        if !unlocked {
+            detail.set_text_content(Some(&requirement_text(requirement, lifetime)));
+        }
+        li.append_child(&name)?;
+        li.append_child(&detail)?;
+        list.append_child(&li)?;
+    }
+    if let Some(total) = dom.get("lifetime-score") {
+        total.set_text_content(Some(&lifetime.to_string()));
+    }
+    Ok(())
+}
+
+// Opens the unlocks screen from `#unlocks-btn`, when the page has one.
+pub fn attach(dom: &Dom) -> Result<(), JsValue> {
+    for (id, open) in [("unlocks-btn", true), ("close-unlocks-btn", false)] {
+        if let Some(btn) = dom.get(id) {
+            let dom = dom.clone();
+            let closure = Closure::wrap(Box::new(move || {
+                if open {
+                    if let Err(e) = render(&dom) {
+                        log::error!("Failed to render unlocks: {}", e);
+                    }
+                }
+                if let Some(modal) = dom.get("unlocks-modal") {
+                    modal.class_list().toggle_with_force("hidden", !open).unwrap_or(false);
+                }
+            }) as Box<dyn FnMut()>);
+            btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+            closure.forget();
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+// WebXR. A VR mode needs the frames drawn twice, once per eye, into the
+// session's framebuffer from the session's own animation loop. three-d 0.16
+// draws only to the canvas, from the window's loop, so a session can't be
+// fed yet. Builds with the `xr` feature check whether the browser could run
+// one, ready for when the renderer can target an XR layer.
+
+const MODE: &str = "immersive-vr";
+
+// Resolves to whether an immersive VR session is on offer: `false` without
+// the `xr` feature, without `navigator.xr`, or with no headset.
+pub fn supported() -> js_sys::Promise {
+    let unsupported = || js_sys::Promise::resolve(&JsValue::FALSE);
+    if !cfg!(feature = "xr") {
+        return unsupported();
+    }
+    let Some(navigator) = web_sys::window().map(|w| w.navigator()) else { return unsupported() };
+    let xr = js_sys::Reflect::get(&navigator, &"xr".into()).unwrap_or(JsValue::UNDEFINED);
+    let check = js_sys::Reflect::get(&xr, &"isSessionSupported".into())
+        .ok()
+        .and_then(|f| f.dyn_into::<js_sys::Function>().ok());
+    match check.map(|f| f.call1(&xr, &MODE.into())) {
+        Some(Ok(promise)) => promise.dyn_into().unwrap_or_else(|_| unsupported()),
+        _ => unsupported(),
+    }
+}